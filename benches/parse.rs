@@ -0,0 +1,73 @@
+//! Compares parsing into an owned `ClassFile` with the borrowed `ClassFileRef` and the
+//! header-only and skip-scanning modes.
+//!
+//! Run with `cargo bench --bench parse`.
+
+extern crate classfile_parser;
+
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use classfile_parser::{parse_class_from_bytes, parse_class_ref, parse_header, scan_class};
+
+const ITERATIONS: u32 = 2000;
+
+fn time<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    // Warm up before timing
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{:<32} {:>10.1?} per iteration", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let classes: Vec<Vec<u8>> = fs::read_dir("java-assets/compiled-classes")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "class") && !path.ends_with("malformed.class")
+        })
+        .map(|path| fs::read(path).unwrap())
+        .collect();
+    let bytes: usize = classes.iter().map(Vec::len).sum();
+    println!("{} classes, {} bytes", classes.len(), bytes);
+
+    let owned = time("owned ClassFile", || {
+        for class_bytes in &classes {
+            black_box(parse_class_from_bytes(class_bytes).unwrap());
+        }
+    });
+    let borrowed = time("borrowed ClassFileRef", || {
+        for class_bytes in &classes {
+            black_box(parse_class_ref(class_bytes).unwrap());
+        }
+    });
+    time("borrowed, resolving class names", || {
+        for class_bytes in &classes {
+            let class_file = parse_class_ref(class_bytes).unwrap();
+            black_box(class_file.this_class_name().unwrap());
+            black_box(class_file.super_class_name().unwrap());
+        }
+    });
+    time("header only", || {
+        for class_bytes in &classes {
+            black_box(parse_header(class_bytes).unwrap());
+        }
+    });
+    time("skip-scanning members", || {
+        for class_bytes in &classes {
+            black_box(scan_class(class_bytes).unwrap());
+        }
+    });
+    println!(
+        "borrowed parsing is {:.1}x faster",
+        owned.as_secs_f64() / borrowed.as_secs_f64()
+    );
+}
@@ -31,6 +31,19 @@ javac -d java-assets/compiled-classes/ java-assets/src/module-info.java java-ass
 
 javac -g -d java-assets/compiled-classes/ java-assets/src/LocalVariableTable.java
 javac -d java-assets/compiled-classes/ java-assets/src/HelloWorld.java
+javac -d java-assets/compiled-classes/ java-assets/src/Generics.java
+javac -d java-assets/compiled-classes/ java-assets/src/Disassembly.java
+javac -d java-assets/compiled-classes/ java-assets/src/Point.java
+javac -d java-assets/compiled-classes/ java-assets/src/Nestmates.java
+javac -d java-assets/compiled-classes/ java-assets/src/Dispatch.java
+
+# jar adds ModulePackages and ModuleMainClass to module-info.class
+app_classes=$(mktemp -d)
+javac -d "$app_classes" $(find java-assets/src/app -name '*.java')
+jar --create --file "$app_classes.jar" --main-class com.app.Main -C "$app_classes" .
+unzip -p "$app_classes.jar" module-info.class > java-assets/compiled-classes/app-module-info.class
+rm -r "$app_classes" "$app_classes.jar"
+
 printf '\xde\xad\xbe\xef' > java-assets/compiled-classes/malformed.class
 tail -c+5 java-assets/compiled-classes/HelloWorld.class >> java-assets/compiled-classes/malformed.class
 
@@ -0,0 +1,41 @@
+import java.lang.annotation.*;
+import java.util.*;
+
+@Retention(RetentionPolicy.RUNTIME)
+@interface Defaults {
+  byte b() default 1; char c() default 'x'; double d() default 1.5; float f() default 2.5f;
+  int i() default 3; long j() default 4L; short s() default 5; boolean z() default true;
+  String str() default "q\"uo'te\t"; ElementType e() default ElementType.FIELD; Class<?> k() default int[].class;
+  Retention a() default @Retention(RetentionPolicy.CLASS); int[] arr() default {1, 2}; String[] none() default {};
+}
+
+interface Greeter {
+  default String greet() { return "hello"; }
+  static Greeter create() { return null; }
+}
+
+@Defaults
+public abstract class Disassembly<T> implements Runnable, Comparable<T>, java.io.Serializable {
+  public static final int CONST = 42;
+  public static final long LCONST = 1L << 40;
+  public static final float FCONST = 1e10f;
+  public static final double DCONST = Double.NaN;
+  public static final String SCONST = "a\nb\u0001c";
+  protected volatile transient int v;
+  static double[][] grid;
+  static { grid = new double[3][4]; }
+
+  public synchronized void run() {
+    try { Thread.sleep(1); } catch (InterruptedException | IllegalStateException e) { throw new RuntimeException(e); } finally { v++; }
+  }
+  public static void varargs(String... xs) { long a = 1; double d = 2.0; float f = 3f; for (String x : xs) { a += x.length(); } long fa = a; Runnable r = () -> System.out.println(fa); r.run(); }
+  abstract void abs() throws java.io.IOException;
+  native int nat();
+  double sf(double x) { return x * 2; }
+  int wide(int x) { int a0=0,a1=0,a2=0,a3=0,a4=0,a5=0,a6=0,a7=0,a8=0,a9=0; int[] big = new int[300]; long l = 70000; return x + 100000 + (int) l; }
+  Object multi() { return new String[2][3][4]; }
+  private int sw(String s) { switch (s) { case "a": return 1; case "b": return 2; default: return 0; } }
+  int table(int i) { switch (i) { case 1: return 10; case 2: return 20; case 3: return 30; default: return -1; } }
+  @Defaults(i = 7) public <E extends Exception> void gen(List<? extends T> in) throws E {}
+  long lc() { return 0x7fffffffffffffffL; } float fc() { return 0.1f; } double dc() { return 1e-5; } int ic() { return -2147483648; }
+}
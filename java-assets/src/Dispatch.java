@@ -0,0 +1,47 @@
+public class Dispatch {
+    interface Greeter {
+        String greet();
+
+        default String name() {
+            return "greeter";
+        }
+    }
+
+    static abstract class Base implements Greeter {
+        public String greet() {
+            return "base " + name();
+        }
+
+        abstract int size();
+    }
+
+    static class Loud extends Base {
+        public String greet() {
+            return super.greet().toUpperCase();
+        }
+
+        int size() {
+            return 1;
+        }
+    }
+
+    static class Quiet extends Base {
+        int size() {
+            return 2;
+        }
+
+        public String name() {
+            return "quiet";
+        }
+    }
+
+    static void run(Greeter greeter, Base base) {
+        System.out.println(greeter.greet());
+        System.out.println(base.size());
+        System.out.println(greeter.name());
+    }
+
+    public static void main(String[] args) {
+        run(new Loud(), new Quiet());
+    }
+}
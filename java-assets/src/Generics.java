@@ -0,0 +1,18 @@
+import java.io.IOException;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Map;
+
+public class Generics<K extends Comparable<K>, V> extends ArrayList<Map.Entry<K, V>>
+    implements Comparable<Generics<K, ?>> {
+  private Map<? super K, List<? extends V>[]> index;
+  private int[] plain;
+
+  public <E extends Exception & Runnable> V lookup(K key, List<? super E> sink) throws E, IOException {
+    return null;
+  }
+
+  public int compareTo(Generics<K, ?> other) {
+    return 0;
+  }
+}
@@ -0,0 +1,17 @@
+public class Nestmates {
+    private int secret = 42;
+
+    sealed interface Shape permits Circle, Square {}
+
+    static final class Circle implements Shape {
+        private double radius;
+    }
+
+    static non-sealed class Square implements Shape {}
+
+    class Inner {
+        int peek() {
+            return secret;
+        }
+    }
+}
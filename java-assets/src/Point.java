@@ -0,0 +1,9 @@
+import java.lang.annotation.*;
+import java.util.List;
+
+public record Point<T>(int x, int y, @Label T label, List<String> tags) {
+}
+
+@Retention(RetentionPolicy.RUNTIME)
+@Target(ElementType.RECORD_COMPONENT)
+@interface Label {}
@@ -0,0 +1,12 @@
+package com.app;
+
+import com.app.api.Service;
+import java.util.ServiceLoader;
+
+public class Main {
+    public static void main(String[] args) {
+        for (Service service : ServiceLoader.load(Service.class)) {
+            System.out.println(service.name());
+        }
+    }
+}
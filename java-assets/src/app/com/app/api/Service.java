@@ -0,0 +1,5 @@
+package com.app.api;
+
+public interface Service {
+    String name();
+}
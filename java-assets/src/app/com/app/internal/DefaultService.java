@@ -0,0 +1,9 @@
+package com.app.internal;
+
+import com.app.api.Service;
+
+public class DefaultService implements Service {
+    public String name() {
+        return "default";
+    }
+}
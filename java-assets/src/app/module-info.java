@@ -0,0 +1,7 @@
+module my.app {
+    requires transitive java.logging;
+    exports com.app.api;
+    opens com.app.internal to java.base;
+    uses com.app.api.Service;
+    provides com.app.api.Service with com.app.internal.DefaultService;
+}
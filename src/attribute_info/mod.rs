@@ -1,12 +1,19 @@
+mod module;
 mod parser;
+mod stack_map;
 mod types;
 
+pub use self::module::{
+    ModuleDescriptor, ModuleExports, ModuleOpens, ModuleProvides, ModuleRequires,
+};
+pub use self::stack_map::{ExpandedFrame, StackMapFrames};
 pub use self::types::*;
 
 pub use self::parser::attribute_parser;
 pub use self::parser::bootstrap_methods_attribute_parser;
 pub use self::parser::code_attribute_parser;
 pub use self::parser::constant_value_attribute_parser;
+pub use self::parser::deprecated_attribute_parser;
 pub use self::parser::element_value_parser;
 pub use self::parser::enclosing_method_attribute_parser;
 pub use self::parser::exceptions_attribute_parser;
@@ -14,6 +21,12 @@ pub use self::parser::inner_classes_attribute_parser;
 pub use self::parser::line_number_table_attribute_parser;
 pub use self::parser::method_parameters_attribute_parser;
 pub use self::parser::module_attribute_parser;
+pub use self::parser::module_main_class_attribute_parser;
+pub use self::parser::module_packages_attribute_parser;
+pub use self::parser::nest_host_attribute_parser;
+pub use self::parser::nest_members_attribute_parser;
+pub use self::parser::permitted_subclasses_attribute_parser;
+pub use self::parser::record_attribute_parser;
 pub use self::parser::runtime_invisible_annotations_attribute_parser;
 pub use self::parser::runtime_invisible_parameter_annotations_attribute_parser;
 pub use self::parser::runtime_invisible_type_annotations_attribute_parser;
@@ -24,3 +37,9 @@ pub use self::parser::signature_attribute_parser;
 pub use self::parser::source_debug_extension_parser;
 pub use self::parser::sourcefile_attribute_parser;
 pub use self::parser::stack_map_table_attribute_parser;
+pub use self::parser::synthetic_attribute_parser;
+pub use self::parser::typed_attribute_parser;
+pub use self::parser::typed_attributes_parser;
+
+pub(crate) use self::parser::{exception_entry_parser, has_attribute, has_deprecated};
+pub(crate) use self::types::decode_instructions;
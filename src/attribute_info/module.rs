@@ -0,0 +1,154 @@
+use crate::constant_info::{ConstantPool, ConstantPoolError};
+
+use super::types::{ModuleAttribute, ModuleMainClassAttribute, ModulePackagesAttribute};
+
+/// A module declaration with its constant pool references resolved, combining the Module,
+/// ModulePackages and ModuleMainClass attributes of a `module-info` class.
+///
+/// Module names are dotted (`java.base`), while package and class names keep their internal
+/// form (`java/util`, `java/lang/Runnable`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleDescriptor {
+    pub name: String,
+    pub flags: u16,
+    pub version: Option<String>,
+    pub requires: Vec<ModuleRequires>,
+    pub exports: Vec<ModuleExports>,
+    pub opens: Vec<ModuleOpens>,
+    /// Service interfaces the module looks up with `ServiceLoader`.
+    pub uses: Vec<String>,
+    pub provides: Vec<ModuleProvides>,
+    /// Every package of the module, empty if the class has no ModulePackages attribute.
+    pub packages: Vec<String>,
+    pub main_class: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleRequires {
+    pub module: String,
+    pub flags: u16,
+    /// The version of the required module at compile time, if it was recorded.
+    pub version: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleExports {
+    pub package: String,
+    pub flags: u16,
+    /// The modules the package is exported to, empty for an unqualified export.
+    pub to: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleOpens {
+    pub package: String,
+    pub flags: u16,
+    /// The modules the package is opened to, empty for an unqualified opens.
+    pub to: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleProvides {
+    pub service: String,
+    pub with: Vec<String>,
+}
+
+impl ModuleDescriptor {
+    /// Resolve a Module attribute, and the ModulePackages and ModuleMainClass attributes of
+    /// the same class if present, against the class's constant pool.
+    pub fn new(
+        module: &ModuleAttribute,
+        packages: Option<&ModulePackagesAttribute>,
+        main_class: Option<&ModuleMainClassAttribute>,
+        pool: &ConstantPool,
+    ) -> Result<Self, ConstantPoolError> {
+        let optional_utf8 = |index: u16| match index {
+            0 => Ok(None),
+            index => pool.get_utf8(index).map(|value| Some(value.into_owned())),
+        };
+        let modules = |indices: &[u16]| -> Result<Vec<String>, ConstantPoolError> {
+            indices
+                .iter()
+                .map(|&index| pool.get_module_name(index).map(|name| name.into_owned()))
+                .collect()
+        };
+        let classes = |indices: &[u16]| -> Result<Vec<String>, ConstantPoolError> {
+            indices
+                .iter()
+                .map(|&index| pool.get_class_name(index).map(|name| name.into_owned()))
+                .collect()
+        };
+
+        let requires = module
+            .requires
+            .iter()
+            .map(|requires| {
+                Ok(ModuleRequires {
+                    module: pool.get_module_name(requires.requires_index)?.into_owned(),
+                    flags: requires.requires_flags,
+                    version: optional_utf8(requires.requires_version_index)?,
+                })
+            })
+            .collect::<Result<_, ConstantPoolError>>()?;
+        let exports = module
+            .exports
+            .iter()
+            .map(|exports| {
+                Ok(ModuleExports {
+                    package: pool.get_package_name(exports.exports_index)?.into_owned(),
+                    flags: exports.exports_flags,
+                    to: modules(&exports.exports_to_index)?,
+                })
+            })
+            .collect::<Result<_, ConstantPoolError>>()?;
+        let opens = module
+            .opens
+            .iter()
+            .map(|opens| {
+                Ok(ModuleOpens {
+                    package: pool.get_package_name(opens.opens_index)?.into_owned(),
+                    flags: opens.opens_flags,
+                    to: modules(&opens.opens_to_index)?,
+                })
+            })
+            .collect::<Result<_, ConstantPoolError>>()?;
+        let provides = module
+            .provides
+            .iter()
+            .map(|provides| {
+                Ok(ModuleProvides {
+                    service: pool.get_class_name(provides.provides_index)?.into_owned(),
+                    with: classes(&provides.provides_with_index)?,
+                })
+            })
+            .collect::<Result<_, ConstantPoolError>>()?;
+        let packages = match packages {
+            Some(packages) => packages
+                .package_index
+                .iter()
+                .map(|&index| pool.get_package_name(index).map(|name| name.into_owned()))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        let main_class = match main_class {
+            Some(main_class) => Some(
+                pool.get_class_name(main_class.main_class_index)?
+                    .into_owned(),
+            ),
+            None => None,
+        };
+
+        Ok(ModuleDescriptor {
+            name: pool.get_module_name(module.module_name_index)?.into_owned(),
+            flags: module.module_flags,
+            version: optional_utf8(module.module_version_index)?,
+            requires,
+            exports,
+            opens,
+            uses: classes(&module.uses)?,
+            provides,
+            packages,
+            main_class,
+        })
+    }
+}
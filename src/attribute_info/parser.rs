@@ -9,6 +9,9 @@ use nom::{
 
 use crate::attribute_info::types::StackMapFrame::*;
 use crate::attribute_info::*;
+use crate::code_attribute::{local_variable_table_parser, local_variable_type_table_parser};
+use crate::constant_info::ConstantInfo;
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
 
 // Using a type alias here evades a Clippy warning about complex types.
 type Err<E> = BaseErr<Error<E>>;
@@ -140,6 +143,16 @@ pub fn enclosing_method_attribute_parser(
     ))
 }
 
+pub fn synthetic_attribute_parser(input: &[u8]) -> Result<(&[u8], SyntheticAttribute), Err<&[u8]>> {
+    Ok((input, SyntheticAttribute {}))
+}
+
+pub fn deprecated_attribute_parser(
+    input: &[u8],
+) -> Result<(&[u8], DeprecatedAttribute), Err<&[u8]>> {
+    Ok((input, DeprecatedAttribute {}))
+}
+
 pub fn signature_attribute_parser(input: &[u8]) -> Result<(&[u8], SignatureAttribute), Err<&[u8]>> {
     let (input, signature_index) = be_u16(input)?;
     Ok((input, SignatureAttribute { signature_index }))
@@ -300,12 +313,7 @@ pub fn type_annotation_parser(input: &[u8]) -> Result<(&[u8], TypeAnnotation), E
                 type_argument_index,
             };
         }
-        _ => {
-            eprintln!(
-                "Parsing RuntimeVisibleTypeAnnotationsAttribute with target_type = {}",
-                target_type
-            );
-        }
+        _ => {}
     }
     let (input, target_path) = target_path_parser(input)?;
     let (input, type_index) = be_u16(input)?;
@@ -363,10 +371,6 @@ pub fn local_variable_table_annotation_parser(
 fn annotation_parser(input: &[u8]) -> Result<(&[u8], RuntimeAnnotation), Err<&[u8]>> {
     let (input, type_index) = be_u16(input)?;
     let (input, num_element_value_pairs) = be_u16(input)?;
-    eprintln!(
-        "Parsing annotation with type index = {}, and {} element value pairs",
-        type_index, num_element_value_pairs
-    );
     let (input, element_value_pairs) =
         count(element_value_pair_parser, num_element_value_pairs as usize)(input)?;
     Ok((
@@ -399,15 +403,9 @@ fn array_value_parser(input: &[u8]) -> Result<(&[u8], ElementArrayValue), Err<&[
 
 pub fn element_value_parser(input: &[u8]) -> Result<(&[u8], ElementValue), Err<&[u8]>> {
     let (input, tag) = be_u8(input)?;
-    eprintln!("Element value parsing: tag = {}", tag as char);
-
     match tag as char {
         'B' | 'C' | 'I' | 'S' | 'Z' | 'D' | 'F' | 'J' | 's' => {
             let (input, const_value_index) = be_u16(input)?;
-            eprintln!(
-                "Element value parsing: const_value_index = {}",
-                const_value_index
-            );
             Ok((
                 input,
                 ElementValue::ConstValueIndex {
@@ -418,31 +416,18 @@ pub fn element_value_parser(input: &[u8]) -> Result<(&[u8], ElementValue), Err<&
         }
         'e' => {
             let (input, enum_const_value) = enum_const_value_parser(input)?;
-            eprintln!(
-                "Element value parsing: enum_const_value = {:?}",
-                enum_const_value
-            );
             Ok((input, ElementValue::EnumConst(enum_const_value)))
         }
         'c' => {
             let (input, class_info_index) = be_u16(input)?;
-            eprintln!(
-                "Element value parsing: class_info_index = {}",
-                class_info_index
-            );
             Ok((input, ElementValue::ClassInfoIndex(class_info_index)))
         }
         '@' => {
             let (input, annotation_value) = annotation_parser(input)?;
-            eprintln!(
-                "Element value parsing: annotation_value = {:?}",
-                annotation_value
-            );
             Ok((input, ElementValue::AnnotationValue(annotation_value)))
         }
         '[' => {
             let (input, array_value) = array_value_parser(input)?;
-            eprintln!("Element value parsing: array_value = {:?}", array_value);
             Ok((input, ElementValue::ElementArray(array_value)))
         }
         _ => Result::Err(Err::Error(error_position!(input, ErrorKind::NoneOf))),
@@ -506,8 +491,7 @@ fn same_frame_parser(input: &[u8], frame_type: u8) -> Result<(&[u8], StackMapFra
 
 fn verification_type_parser(input: &[u8]) -> Result<(&[u8], VerificationTypeInfo), Err<&[u8]>> {
     use self::VerificationTypeInfo::*;
-    let v = input[0];
-    let new_input = &input[1..];
+    let (new_input, v) = be_u8(input)?;
     match v {
         0 => Ok((new_input, Top)),
         1 => Ok((new_input, Integer)),
@@ -804,3 +788,232 @@ pub fn module_provides_attribute_parser(
         },
     ))
 }
+
+pub fn module_packages_attribute_parser(
+    input: &[u8],
+) -> Result<(&[u8], ModulePackagesAttribute), Err<&[u8]>> {
+    let (input, package_count) = be_u16(input)?;
+    let (input, package_index) = count(be_u16, package_count as usize)(input)?;
+    Ok((
+        input,
+        ModulePackagesAttribute {
+            package_count,
+            package_index,
+        },
+    ))
+}
+
+pub fn module_main_class_attribute_parser(
+    input: &[u8],
+) -> Result<(&[u8], ModuleMainClassAttribute), Err<&[u8]>> {
+    let (input, main_class_index) = be_u16(input)?;
+    Ok((input, ModuleMainClassAttribute { main_class_index }))
+}
+
+pub fn record_attribute_parser(input: &[u8]) -> Result<(&[u8], RecordAttribute), Err<&[u8]>> {
+    let (input, components_count) = be_u16(input)?;
+    let (input, components) =
+        count(record_component_info_parser, components_count as usize)(input)?;
+    Ok((
+        input,
+        RecordAttribute {
+            components_count,
+            components,
+        },
+    ))
+}
+
+pub fn record_component_info_parser(
+    input: &[u8],
+) -> Result<(&[u8], RecordComponentInfo), Err<&[u8]>> {
+    let (input, name_index) = be_u16(input)?;
+    let (input, descriptor_index) = be_u16(input)?;
+    let (input, attributes_count) = be_u16(input)?;
+    let (input, attributes) = count(attribute_parser, attributes_count as usize)(input)?;
+    Ok((
+        input,
+        RecordComponentInfo {
+            name_index,
+            descriptor_index,
+            attributes_count,
+            attributes,
+        },
+    ))
+}
+
+pub fn nest_host_attribute_parser(input: &[u8]) -> Result<(&[u8], NestHostAttribute), Err<&[u8]>> {
+    let (input, host_class_index) = be_u16(input)?;
+    Ok((input, NestHostAttribute { host_class_index }))
+}
+
+pub fn nest_members_attribute_parser(
+    input: &[u8],
+) -> Result<(&[u8], NestMembersAttribute), Err<&[u8]>> {
+    let (input, number_of_classes) = be_u16(input)?;
+    let (input, classes) = count(be_u16, number_of_classes as usize)(input)?;
+    Ok((
+        input,
+        NestMembersAttribute {
+            number_of_classes,
+            classes,
+        },
+    ))
+}
+
+pub fn permitted_subclasses_attribute_parser(
+    input: &[u8],
+) -> Result<(&[u8], PermittedSubclassesAttribute), Err<&[u8]>> {
+    let (input, number_of_classes) = be_u16(input)?;
+    let (input, classes) = count(be_u16, number_of_classes as usize)(input)?;
+    Ok((
+        input,
+        PermittedSubclassesAttribute {
+            number_of_classes,
+            classes,
+        },
+    ))
+}
+
+fn attribute_name(const_pool: &[ConstantInfo], attribute_name_index: u16) -> Option<String> {
+    match const_pool.get((attribute_name_index as usize).checked_sub(1)?) {
+        Some(ConstantInfo::Utf8(utf8)) => Some(utf8.utf8_string.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether `attributes` contain an attribute called `name`, without decoding any of them.
+pub(crate) fn has_attribute(
+    attributes: &[AttributeInfo],
+    const_pool: &[ConstantInfo],
+    name: &str,
+) -> bool {
+    attributes.iter().any(|attribute| {
+        attribute_name(const_pool, attribute.attribute_name_index).as_deref() == Some(name)
+    })
+}
+
+/// Whether `attributes` contain a Deprecated attribute or a `@java.lang.Deprecated` annotation.
+/// javac emits both for a `@Deprecated` element, but other compilers may only emit one.
+pub(crate) fn has_deprecated(attributes: &[AttributeInfo], const_pool: &[ConstantInfo]) -> bool {
+    attributes.iter().any(|attribute| {
+        match attribute_name(const_pool, attribute.attribute_name_index).as_deref() {
+            Some("Deprecated") => true,
+            Some("RuntimeVisibleAnnotations") => runtime_visible_annotations_attribute_parser(
+                &attribute.info,
+            )
+            .is_ok_and(|(_, annotations)| {
+                annotations.annotations.iter().any(|annotation| {
+                    attribute_name(const_pool, annotation.type_index).as_deref()
+                        == Some("Ljava/lang/Deprecated;")
+                })
+            }),
+            _ => false,
+        }
+    })
+}
+
+/// Decode a raw `AttributeInfo` into a `TypedAttribute`, picking the parser based on the
+/// attribute name found in the constant pool.
+pub fn typed_attribute_parser(
+    attribute: &AttributeInfo,
+    const_pool: &[ConstantInfo],
+) -> Result<TypedAttribute, ClassParseError> {
+    let input = &attribute.info[..];
+    let name = match attribute_name(const_pool, attribute.attribute_name_index) {
+        Some(name) => name,
+        None => {
+            return Result::Err(ClassParseError::malformed(
+                0,
+                ParseErrorCause::BadAttributeName(attribute.attribute_name_index),
+            ));
+        }
+    };
+    typed_attribute_body_parser(name.clone(), input)
+        .map_err(|e| ClassParseError::from_nom(input, e).within(0, ParseContext::Attribute(name)))
+}
+
+fn typed_attribute_body_parser(name: String, input: &[u8]) -> Result<TypedAttribute, Err<&[u8]>> {
+    let typed = match name.as_str() {
+        "ConstantValue" => TypedAttribute::ConstantValue(constant_value_attribute_parser(input)?.1),
+        "Code" => TypedAttribute::Code(code_attribute_parser(input)?.1),
+        "StackMapTable" => {
+            TypedAttribute::StackMapTable(stack_map_table_attribute_parser(input)?.1)
+        }
+        "Exceptions" => TypedAttribute::Exceptions(exceptions_attribute_parser(input)?.1),
+        "InnerClasses" => TypedAttribute::InnerClasses(inner_classes_attribute_parser(input)?.1),
+        "EnclosingMethod" => {
+            TypedAttribute::EnclosingMethod(enclosing_method_attribute_parser(input)?.1)
+        }
+        "Synthetic" => TypedAttribute::Synthetic(synthetic_attribute_parser(input)?.1),
+        "Signature" => TypedAttribute::Signature(signature_attribute_parser(input)?.1),
+        "Deprecated" => TypedAttribute::Deprecated(deprecated_attribute_parser(input)?.1),
+        "SourceFile" => TypedAttribute::SourceFile(sourcefile_attribute_parser(input)?.1),
+        "SourceDebugExtension" => {
+            TypedAttribute::SourceDebugExtension(source_debug_extension_parser(input)?.1)
+        }
+        "LineNumberTable" => {
+            TypedAttribute::LineNumberTable(line_number_table_attribute_parser(input)?.1)
+        }
+        "LocalVariableTable" => {
+            TypedAttribute::LocalVariableTable(local_variable_table_parser(input)?.1)
+        }
+        "LocalVariableTypeTable" => {
+            TypedAttribute::LocalVariableTypeTable(local_variable_type_table_parser(input)?.1)
+        }
+        "RuntimeVisibleAnnotations" => TypedAttribute::RuntimeVisibleAnnotations(
+            runtime_visible_annotations_attribute_parser(input)?.1,
+        ),
+        "RuntimeInvisibleAnnotations" => TypedAttribute::RuntimeInvisibleAnnotations(
+            runtime_invisible_annotations_attribute_parser(input)?.1,
+        ),
+        "RuntimeVisibleParameterAnnotations" => TypedAttribute::RuntimeVisibleParameterAnnotations(
+            runtime_visible_parameter_annotations_attribute_parser(input)?.1,
+        ),
+        "RuntimeInvisibleParameterAnnotations" => {
+            TypedAttribute::RuntimeInvisibleParameterAnnotations(
+                runtime_invisible_parameter_annotations_attribute_parser(input)?.1,
+            )
+        }
+        "RuntimeVisibleTypeAnnotations" => TypedAttribute::RuntimeVisibleTypeAnnotations(
+            runtime_visible_type_annotations_attribute_parser(input)?.1,
+        ),
+        "RuntimeInvisibleTypeAnnotations" => TypedAttribute::RuntimeInvisibleTypeAnnotations(
+            runtime_invisible_type_annotations_attribute_parser(input)?.1,
+        ),
+        "AnnotationDefault" => TypedAttribute::AnnotationDefault(element_value_parser(input)?.1),
+        "BootstrapMethods" => {
+            TypedAttribute::BootstrapMethods(bootstrap_methods_attribute_parser(input)?.1)
+        }
+        "MethodParameters" => {
+            TypedAttribute::MethodParameters(method_parameters_attribute_parser(input)?.1)
+        }
+        "Module" => TypedAttribute::Module(module_attribute_parser(input)?.1),
+        "ModulePackages" => {
+            TypedAttribute::ModulePackages(module_packages_attribute_parser(input)?.1)
+        }
+        "ModuleMainClass" => {
+            TypedAttribute::ModuleMainClass(module_main_class_attribute_parser(input)?.1)
+        }
+        "Record" => TypedAttribute::Record(record_attribute_parser(input)?.1),
+        "NestHost" => TypedAttribute::NestHost(nest_host_attribute_parser(input)?.1),
+        "NestMembers" => TypedAttribute::NestMembers(nest_members_attribute_parser(input)?.1),
+        "PermittedSubclasses" => {
+            TypedAttribute::PermittedSubclasses(permitted_subclasses_attribute_parser(input)?.1)
+        }
+        _ => TypedAttribute::Unknown {
+            name,
+            bytes: input.to_vec(),
+        },
+    };
+    Ok(typed)
+}
+
+pub fn typed_attributes_parser(
+    attributes: &[AttributeInfo],
+    const_pool: &[ConstantInfo],
+) -> Result<Vec<TypedAttribute>, ClassParseError> {
+    attributes
+        .iter()
+        .map(|attribute| typed_attribute_parser(attribute, const_pool))
+        .collect()
+}
@@ -0,0 +1,150 @@
+use crate::constant_info::{ConstantPool, ConstantPoolError};
+use crate::verifier::VerificationType;
+
+use super::types::{StackMapFrame, StackMapTableAttribute, VerificationTypeInfo};
+
+/// A StackMapTable frame at its bytecode offset, with its locals and operand stack expanded
+/// to one entry per value (a long or double is a single entry).
+pub type ExpandedFrame = (usize, Vec<VerificationType>, Vec<VerificationType>);
+
+/// An iterator over the frames of a StackMapTable with absolute offsets, created by
+/// `StackMapTableAttribute::frames`.
+#[derive(Clone, Debug)]
+pub struct StackMapFrames<'a> {
+    entries: std::slice::Iter<'a, StackMapFrame>,
+    pool: ConstantPool<'a>,
+    locals: Vec<VerificationType>,
+    offset: Option<usize>,
+}
+
+fn resolve(
+    pool: &ConstantPool,
+    info: &VerificationTypeInfo,
+) -> Result<VerificationType, ConstantPoolError> {
+    Ok(match info {
+        VerificationTypeInfo::Top => VerificationType::Top,
+        VerificationTypeInfo::Integer => VerificationType::Integer,
+        VerificationTypeInfo::Float => VerificationType::Float,
+        VerificationTypeInfo::Double => VerificationType::Double,
+        VerificationTypeInfo::Long => VerificationType::Long,
+        VerificationTypeInfo::Null => VerificationType::Null,
+        VerificationTypeInfo::UninitializedThis => VerificationType::UninitializedThis,
+        VerificationTypeInfo::Object { class } => {
+            VerificationType::Object(pool.get_class_name(*class)?.into_owned())
+        }
+        VerificationTypeInfo::Uninitialized { offset } => VerificationType::Uninitialized(*offset),
+    })
+}
+
+impl StackMapFrames<'_> {
+    fn expand(&mut self, entry: &StackMapFrame) -> Result<ExpandedFrame, ConstantPoolError> {
+        let pool = self.pool;
+        let resolve_all = |infos: &[VerificationTypeInfo]| -> Result<Vec<_>, ConstantPoolError> {
+            infos.iter().map(|info| resolve(&pool, info)).collect()
+        };
+        let (offset_delta, stack) = match entry {
+            StackMapFrame::SameFrame { frame_type } => (*frame_type as u16, Vec::new()),
+            StackMapFrame::SameLocals1StackItemFrame { frame_type, stack } => {
+                (*frame_type as u16 - 64, vec![resolve(&pool, stack)?])
+            }
+            StackMapFrame::SameLocals1StackItemFrameExtended {
+                offset_delta,
+                stack,
+                ..
+            } => (*offset_delta, vec![resolve(&pool, stack)?]),
+            StackMapFrame::ChopFrame {
+                frame_type,
+                offset_delta,
+            } => {
+                let chopped = (251 - *frame_type as usize).min(self.locals.len());
+                self.locals.truncate(self.locals.len() - chopped);
+                (*offset_delta, Vec::new())
+            }
+            StackMapFrame::SameFrameExtended { offset_delta, .. } => (*offset_delta, Vec::new()),
+            StackMapFrame::AppendFrame {
+                offset_delta,
+                locals,
+                ..
+            } => {
+                self.locals.extend(resolve_all(locals)?);
+                (*offset_delta, Vec::new())
+            }
+            StackMapFrame::FullFrame {
+                offset_delta,
+                locals,
+                stack,
+                ..
+            } => {
+                self.locals = resolve_all(locals)?;
+                (*offset_delta, resolve_all(stack)?)
+            }
+        };
+        // The first frame's offset is its delta, each later one is delta + 1 past the last
+        let offset = match self.offset {
+            None => offset_delta as usize,
+            Some(previous) => previous + offset_delta as usize + 1,
+        };
+        self.offset = Some(offset);
+        Ok((offset, self.locals.clone(), stack))
+    }
+}
+
+impl Iterator for StackMapFrames<'_> {
+    type Item = Result<ExpandedFrame, ConstantPoolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+        Some(self.expand(entry))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl StackMapTableAttribute {
+    /// Iterate over the frames with the bytecode offset each applies to, replaying the offset
+    /// deltas and the append and chop frames against `initial_locals`, the locals of the
+    /// implicit frame at the start of the method (see `verifier::initial_locals`).
+    ///
+    /// ```rust
+    /// use classfile_parser::attribute_info::TypedAttribute;
+    /// use classfile_parser::verifier::{VerificationType, initial_locals};
+    ///
+    /// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    /// let method = &class_file.methods[1];
+    /// let code = match &method.typed_attributes(&class_file.const_pool).unwrap()[0] {
+    ///     TypedAttribute::Code(code) => code.clone(),
+    ///     _ => unreachable!(),
+    /// };
+    /// let table = code
+    ///     .typed_attributes(&class_file.const_pool)
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .find_map(|attribute| match attribute {
+    ///         TypedAttribute::StackMapTable(table) => Some(table),
+    ///         _ => None,
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let initial_locals = initial_locals(&class_file, method).unwrap();
+    /// let frames = table
+    ///     .frames(initial_locals, class_file.constant_pool())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// use VerificationType::Integer;
+    /// assert_eq!(frames, [(9, vec![Integer], vec![]), (17, vec![Integer], vec![Integer])]);
+    /// ```
+    pub fn frames<'a>(
+        &'a self,
+        initial_locals: Vec<VerificationType>,
+        pool: ConstantPool<'a>,
+    ) -> StackMapFrames<'a> {
+        StackMapFrames {
+            entries: self.entries.iter(),
+            pool,
+            locals: initial_locals,
+            offset: None,
+        }
+    }
+}
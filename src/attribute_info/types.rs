@@ -1,5 +1,13 @@
 use binrw::binrw;
 
+use crate::attribute_info::typed_attributes_parser;
+use crate::code_attribute::{
+    Instruction, LocalVariableTableAttribute, LocalVariableTypeTableAttribute, code_parser,
+    instruction_parser,
+};
+use crate::constant_info::ConstantInfo;
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
+
 #[derive(Clone, Debug)]
 #[binrw]
 #[brw(big)]
@@ -83,6 +91,10 @@ pub struct EnclosingMethodAttribute {
 #[derive(Clone, Debug)]
 pub struct SyntheticAttribute {}
 
+// "Deprecated" is a marker attribute, like "Synthetic"
+#[derive(Clone, Debug)]
+pub struct DeprecatedAttribute {}
+
 #[derive(Clone, Debug)]
 pub struct SignatureAttribute {
     pub signature_index: u16,
@@ -379,3 +391,139 @@ pub struct ModuleProvidesAttribute {
     pub provides_index: u16,
     pub provides_with_index: Vec<u16>,
 }
+
+/// The ModulePackages attribute of a `module-info` class, listing every package of the module,
+/// including those that are neither exported nor opened.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModulePackagesAttribute {
+    pub package_count: u16,
+    pub package_index: Vec<u16>,
+}
+
+/// The ModuleMainClass attribute of a `module-info` class.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ModuleMainClassAttribute {
+    pub main_class_index: u16,
+}
+
+/// The Record attribute of a record class, listing its components in declaration order.
+#[derive(Clone, Debug)]
+pub struct RecordAttribute {
+    pub components_count: u16,
+    pub components: Vec<RecordComponentInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordComponentInfo {
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    pub attributes_count: u16,
+    /// Signature, annotation and type annotation attributes of the component.
+    pub attributes: Vec<AttributeInfo>,
+}
+
+/// The NestHost attribute of a nest member, naming the class that hosts its nest.
+#[derive(Clone, Debug)]
+pub struct NestHostAttribute {
+    pub host_class_index: u16,
+}
+
+/// The NestMembers attribute of a nest host, listing the classes allowed to share its private
+/// members.
+#[derive(Clone, Debug)]
+pub struct NestMembersAttribute {
+    pub number_of_classes: u16,
+    pub classes: Vec<u16>,
+}
+
+/// The PermittedSubclasses attribute of a sealed class or interface.
+#[derive(Clone, Debug)]
+pub struct PermittedSubclassesAttribute {
+    pub number_of_classes: u16,
+    pub classes: Vec<u16>,
+}
+
+/// An attribute decoded according to the name its `attribute_name_index` points to in the
+/// constant pool. Attributes this crate does not know how to decode are kept as `Unknown`.
+#[derive(Clone, Debug)]
+pub enum TypedAttribute {
+    ConstantValue(ConstantValueAttribute),
+    Code(CodeAttribute),
+    StackMapTable(StackMapTableAttribute),
+    Exceptions(ExceptionsAttribute),
+    InnerClasses(InnerClassesAttribute),
+    EnclosingMethod(EnclosingMethodAttribute),
+    Synthetic(SyntheticAttribute),
+    Signature(SignatureAttribute),
+    Deprecated(DeprecatedAttribute),
+    SourceFile(SourceFileAttribute),
+    SourceDebugExtension(SourceDebugExtensionAttribute),
+    LineNumberTable(LineNumberTable),
+    LocalVariableTable(LocalVariableTableAttribute),
+    LocalVariableTypeTable(LocalVariableTypeTableAttribute),
+    RuntimeVisibleAnnotations(RuntimeVisibleAnnotationsAttribute),
+    RuntimeInvisibleAnnotations(RuntimeInvisibleAnnotationsAttribute),
+    RuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotationsAttribute),
+    RuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotationsAttribute),
+    RuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotationsAttribute),
+    RuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotationsAttribute),
+    AnnotationDefault(DefaultAnnotation),
+    BootstrapMethods(BootstrapMethodsAttribute),
+    MethodParameters(MethodParametersAttribute),
+    Module(ModuleAttribute),
+    ModulePackages(ModulePackagesAttribute),
+    ModuleMainClass(ModuleMainClassAttribute),
+    Record(RecordAttribute),
+    NestHost(NestHostAttribute),
+    NestMembers(NestMembersAttribute),
+    PermittedSubclasses(PermittedSubclassesAttribute),
+    Unknown { name: String, bytes: Vec<u8> },
+}
+
+impl RecordComponentInfo {
+    /// Decode the attributes of this record component using the constant pool of the
+    /// record class.
+    pub fn typed_attributes(
+        &self,
+        const_pool: &[ConstantInfo],
+    ) -> Result<Vec<TypedAttribute>, ClassParseError> {
+        typed_attributes_parser(&self.attributes, const_pool)
+    }
+}
+
+impl CodeAttribute {
+    /// Decode the attributes nested in this Code attribute (LineNumberTable,
+    /// LocalVariableTable, StackMapTable, ...) using the given constant pool.
+    pub fn typed_attributes(
+        &self,
+        const_pool: &[ConstantInfo],
+    ) -> Result<Vec<TypedAttribute>, ClassParseError> {
+        typed_attributes_parser(&self.attributes, const_pool)
+    }
+
+    /// Decode the bytecode into `(address, Instruction)` pairs, failing on the first byte
+    /// that is not a valid instruction.
+    pub fn instructions(&self) -> Result<Vec<(usize, Instruction)>, ClassParseError> {
+        decode_instructions(&self.code)
+    }
+}
+
+/// Decode a code array into `(address, Instruction)` pairs, failing on the first byte that
+/// is not a valid instruction.
+pub(crate) fn decode_instructions(
+    code: &[u8],
+) -> Result<Vec<(usize, Instruction)>, ClassParseError> {
+    let (rest, instructions) = code_parser(code).map_err(|e| ClassParseError::from_nom(code, e))?;
+    if rest.is_empty() {
+        return Ok(instructions);
+    }
+    let address = code.len() - rest.len();
+    let cause = match instruction_parser(rest, address) {
+        Err(nom::Err::Error(e)) if e.code == nom::error::ErrorKind::Fail => match rest {
+            [0xc4, opcode, ..] => ParseErrorCause::BadOpcode(*opcode),
+            _ => ParseErrorCause::BadOpcode(rest[0]),
+        },
+        _ => ParseErrorCause::Truncated,
+    };
+    Err(ClassParseError::malformed(address, cause).within(0, ParseContext::Code))
+}
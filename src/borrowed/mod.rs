@@ -0,0 +1,17 @@
+//! A zero-copy view of a class file.
+//!
+//! `ClassFileRef` borrows constant pool strings, attribute bodies and code arrays from the
+//! class file bytes instead of copying them, which makes it much cheaper to parse when only
+//! a few parts of each class are looked at, as when scanning a classpath.
+//!
+//! For indexing, `ClassHeader` stops after the interfaces and `ClassLayout` steps over the
+//! fields, methods and attributes, recording only where each one is.
+
+mod parser;
+mod types;
+
+pub use self::types::*;
+
+pub(crate) use self::parser::{
+    class_file_ref_parser, class_header_parser, class_layout_parser, class_prefix_parser,
+};
@@ -0,0 +1,327 @@
+use nom::bytes::complete::take;
+use nom::multi::count;
+use nom::number::complete::{be_u8, be_u16, be_u32};
+use nom::{Err, IResult};
+
+use crate::attribute_info::exception_entry_parser;
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
+use crate::field_info::FieldAccessFlags;
+use crate::method_info::MethodAccessFlags;
+use crate::parser::{check_magic, member_header_parser};
+use crate::types::ClassAccessFlags;
+
+use super::types::*;
+
+fn attribute_ref_parser(input: &[u8]) -> IResult<&[u8], AttributeInfoRef<'_>> {
+    let (input, attribute_name_index) = be_u16(input)?;
+    let (input, attribute_length) = be_u32(input)?;
+    let (input, info) = take(attribute_length)(input)?;
+    Ok((
+        input,
+        AttributeInfoRef {
+            attribute_name_index,
+            info,
+        },
+    ))
+}
+
+pub(crate) fn code_attribute_ref_parser(input: &[u8]) -> IResult<&[u8], CodeAttributeRef<'_>> {
+    let (input, max_stack) = be_u16(input)?;
+    let (input, max_locals) = be_u16(input)?;
+    let (input, code_length) = be_u32(input)?;
+    let (input, code) = take(code_length)(input)?;
+    let (input, exception_table_length) = be_u16(input)?;
+    let (input, exception_table) =
+        count(exception_entry_parser, exception_table_length as usize)(input)?;
+    let (input, attributes_count) = be_u16(input)?;
+    let (input, attributes) = count(attribute_ref_parser, attributes_count as usize)(input)?;
+    Ok((
+        input,
+        CodeAttributeRef {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        },
+    ))
+}
+
+/// The size of the constant starting at `input`, including its tag.
+fn constant_size(input: &[u8]) -> Result<usize, ParseErrorCause> {
+    let (rest, tag) =
+        be_u8::<_, nom::error::Error<&[u8]>>(input).map_err(|_| ParseErrorCause::Truncated)?;
+    let size = match tag {
+        1 => {
+            let (_, length) = be_u16::<_, nom::error::Error<&[u8]>>(rest)
+                .map_err(|_| ParseErrorCause::Truncated)?;
+            3 + length as usize
+        }
+        7 | 8 | 16 | 19 | 20 => 3,
+        15 => 4,
+        3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 5,
+        5 | 6 => 9,
+        tag => return Err(ParseErrorCause::UnknownConstantTag(tag)),
+    };
+    if size > input.len() {
+        return Err(ParseErrorCause::Truncated);
+    }
+    Ok(size)
+}
+
+/// Find the start of each of the `const_pool_size - 1` constants at the start of `input`,
+/// checking their tags and lengths without decoding them. Offsets are relative to `whole`.
+fn constant_pool_ref_parser<'a>(
+    whole: &'a [u8],
+    input: &'a [u8],
+    const_pool_size: u16,
+) -> Result<(&'a [u8], ConstantPoolRef<'a>), ClassParseError> {
+    let entries = const_pool_size.saturating_sub(1) as usize;
+    let mut offsets = Vec::with_capacity(entries);
+    let mut input = input;
+    while offsets.len() < entries {
+        let offset = whole.len() - input.len();
+        let size = constant_size(input).map_err(|cause| {
+            let index = offsets.len() as u16 + 1;
+            ClassParseError::malformed(offset, cause).within(0, ParseContext::Constant(index))
+        })?;
+        offsets.push(Some(offset as u32));
+        // Long and Double constants take up two entries
+        if matches!(input[0], 5 | 6) {
+            offsets.push(None);
+        }
+        input = &input[size..];
+    }
+    offsets.truncate(entries);
+    Ok((input, ConstantPoolRef::new(whole, offsets)))
+}
+
+/// The error for an attribute that could not be parsed, named if its name can be resolved.
+fn attribute_ref_error(
+    whole: &[u8],
+    input: &[u8],
+    const_pool: &ConstantPoolRef,
+    e: Err<nom::error::Error<&[u8]>>,
+) -> ClassParseError {
+    let name_index = be_u16::<_, nom::error::Error<&[u8]>>(input)
+        .map(|(_, index)| index)
+        .unwrap_or(0);
+    let name = match const_pool.get_utf8(name_index) {
+        Ok(name) => name.into_owned(),
+        Err(_) => format!("#{}", name_index),
+    };
+    ClassParseError::from_nom(whole, e).within(0, ParseContext::Attribute(name))
+}
+
+type AttributesRefResult<'a> = Result<(&'a [u8], Vec<AttributeInfoRef<'a>>), ClassParseError>;
+
+fn attributes_ref_parser<'a>(
+    whole: &'a [u8],
+    input: &'a [u8],
+    const_pool: &ConstantPoolRef,
+) -> AttributesRefResult<'a> {
+    let (mut input, attributes_count) =
+        be_u16(input).map_err(|e| ClassParseError::from_nom(whole, e))?;
+    let mut attributes = Vec::with_capacity(attributes_count as usize);
+    for _ in 0..attributes_count {
+        let (rest, attribute) = attribute_ref_parser(input)
+            .map_err(|e| attribute_ref_error(whole, input, const_pool, e))?;
+        attributes.push(attribute);
+        input = rest;
+    }
+    Ok((input, attributes))
+}
+
+/// Step over an attributes table, checking only that each attribute fits in the input.
+fn skip_attributes<'a>(
+    whole: &'a [u8],
+    input: &'a [u8],
+    const_pool: &ConstantPoolRef,
+) -> Result<&'a [u8], ClassParseError> {
+    let (mut input, attributes_count) =
+        be_u16(input).map_err(|e| ClassParseError::from_nom(whole, e))?;
+    for _ in 0..attributes_count {
+        let (rest, _) = attribute_ref_parser(input)
+            .map_err(|e| attribute_ref_error(whole, input, const_pool, e))?;
+        input = rest;
+    }
+    Ok(input)
+}
+
+type MembersResult<'a, T> = Result<(&'a [u8], Vec<T>), ClassParseError>;
+
+/// Parse a field or method table. `member` is given the offset of each member, its access
+/// flags, name and descriptor indexes, and the input following them.
+fn members_parser<'a, T>(
+    whole: &'a [u8],
+    input: &'a [u8],
+    context: fn(u16) -> ParseContext,
+    mut member: impl FnMut(usize, (u16, u16, u16), &'a [u8]) -> Result<(&'a [u8], T), ClassParseError>,
+) -> MembersResult<'a, T> {
+    let (mut input, members_count) = be_u16(input)
+        .map_err(|e| ClassParseError::from_nom(whole, e).within(0, ParseContext::ClassInfo))?;
+    let mut members = Vec::with_capacity(members_count as usize);
+    for index in 0..members_count {
+        let start = whole.len() - input.len();
+        let (rest, header) = member_header_parser(input)
+            .map_err(|e| ClassParseError::from_nom(whole, e).within(0, context(index)))?;
+        let (rest, parsed) =
+            member(start, header, rest).map_err(|e| e.within(0, context(index)))?;
+        members.push(parsed);
+        input = rest;
+    }
+    Ok((input, members))
+}
+
+/// Parse the magic number, version and constant pool.
+pub(crate) fn class_prefix_parser(
+    input: &[u8],
+) -> Result<(&[u8], ClassPrefix<'_>), ClassParseError> {
+    let whole = input;
+    let at =
+        |context: ParseContext| move |e| ClassParseError::from_nom(whole, e).within(0, context);
+
+    let input = check_magic(input)?;
+    let (input, minor_version) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, major_version) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, const_pool_size) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, const_pool) = constant_pool_ref_parser(whole, input, const_pool_size)?;
+    Ok((
+        input,
+        ClassPrefix {
+            minor_version,
+            major_version,
+            const_pool,
+        },
+    ))
+}
+
+/// Parse everything up to the field table: the prefix, access flags, this and super class
+/// and interfaces.
+pub(crate) fn class_header_parser(
+    input: &[u8],
+) -> Result<(&[u8], ClassHeader<'_>), ClassParseError> {
+    let whole = input;
+    let at = |e| ClassParseError::from_nom(whole, e).within(0, ParseContext::ClassInfo);
+
+    let (input, prefix) = class_prefix_parser(input)?;
+    let (input, access_flags) = be_u16(input).map_err(at)?;
+    let (input, this_class) = be_u16(input).map_err(at)?;
+    let (input, super_class) = be_u16(input).map_err(at)?;
+    let (input, interfaces_count) = be_u16(input).map_err(at)?;
+    let (input, interfaces) = count(be_u16, interfaces_count as usize)(input).map_err(at)?;
+    Ok((
+        input,
+        ClassHeader {
+            minor_version: prefix.minor_version,
+            major_version: prefix.major_version,
+            const_pool: prefix.const_pool,
+            access_flags: ClassAccessFlags::from_bits_truncate(access_flags),
+            this_class,
+            super_class,
+            interfaces,
+        },
+    ))
+}
+
+/// Parse the header, then step over the fields, methods and attributes, recording where
+/// each one is.
+pub(crate) fn class_layout_parser(
+    input: &[u8],
+) -> Result<(&[u8], ClassLayout<'_>), ClassParseError> {
+    let whole = input;
+    let (input, header) = class_header_parser(input)?;
+    let const_pool = &header.const_pool;
+    let (input, fields) = members_parser(
+        whole,
+        input,
+        ParseContext::Field,
+        |start, (access_flags, name_index, descriptor_index), input| {
+            let rest = skip_attributes(whole, input, const_pool)?;
+            let field = MemberLayout {
+                access_flags: FieldAccessFlags::from_bits_truncate(access_flags),
+                name_index,
+                descriptor_index,
+                range: start..whole.len() - rest.len(),
+            };
+            Ok((rest, field))
+        },
+    )?;
+    let (input, methods) = members_parser(
+        whole,
+        input,
+        ParseContext::Method,
+        |start, (access_flags, name_index, descriptor_index), input| {
+            let rest = skip_attributes(whole, input, const_pool)?;
+            let method = MemberLayout {
+                access_flags: MethodAccessFlags::from_bits_truncate(access_flags),
+                name_index,
+                descriptor_index,
+                range: start..whole.len() - rest.len(),
+            };
+            Ok((rest, method))
+        },
+    )?;
+    let start = whole.len() - input.len();
+    let input = skip_attributes(whole, input, const_pool)?;
+    let attributes = start..whole.len() - input.len();
+    Ok((
+        input,
+        ClassLayout {
+            header,
+            fields,
+            methods,
+            attributes,
+        },
+    ))
+}
+
+/// Parse a complete class file without copying, returning any unconsumed trailing bytes
+/// alongside it. Error offsets are relative to the start of `input`.
+pub(crate) fn class_file_ref_parser(
+    input: &[u8],
+) -> Result<(&[u8], ClassFileRef<'_>), ClassParseError> {
+    let whole = input;
+    let (input, header) = class_header_parser(input)?;
+    let const_pool = &header.const_pool;
+    let (input, fields) = members_parser(
+        whole,
+        input,
+        ParseContext::Field,
+        |_, (access_flags, name_index, descriptor_index), input| {
+            let (rest, attributes) = attributes_ref_parser(whole, input, const_pool)?;
+            let field = FieldInfoRef {
+                access_flags: FieldAccessFlags::from_bits_truncate(access_flags),
+                name_index,
+                descriptor_index,
+                attributes,
+            };
+            Ok((rest, field))
+        },
+    )?;
+    let (input, methods) = members_parser(
+        whole,
+        input,
+        ParseContext::Method,
+        |_, (access_flags, name_index, descriptor_index), input| {
+            let (rest, attributes) = attributes_ref_parser(whole, input, const_pool)?;
+            let method = MethodInfoRef {
+                access_flags: MethodAccessFlags::from_bits_truncate(access_flags),
+                name_index,
+                descriptor_index,
+                attributes,
+            };
+            Ok((rest, method))
+        },
+    )?;
+    let (input, attributes) = attributes_ref_parser(whole, input, const_pool)?;
+    Ok((
+        input,
+        ClassFileRef {
+            header,
+            fields,
+            methods,
+            attributes,
+        },
+    ))
+}
@@ -0,0 +1,416 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::attribute_info::{AttributeInfo, ExceptionEntry, decode_instructions};
+use crate::code_attribute::Instruction;
+use crate::constant_info::{
+    ConstantInfo, ConstantPoolError, MemberRef, single_constant_parser, wrong_kind,
+};
+use crate::error::{ClassParseError, ParseContext};
+use crate::field_info::{FieldAccessFlags, FieldInfo};
+use crate::method_info::{MethodAccessFlags, MethodInfo};
+use crate::types::{ClassAccessFlags, ClassFile};
+
+use super::parser::code_attribute_ref_parser;
+
+/// A class file parsed without copying: constant pool strings, attribute bodies and code
+/// arrays borrow from the input bytes, and constants are only decoded when accessed.
+///
+/// ```rust
+/// let classfile_bytes = include_bytes!("../../java-assets/compiled-classes/BasicClass.class");
+/// let class_file = classfile_parser::parse_class_ref(classfile_bytes).unwrap();
+///
+/// assert_eq!(class_file.this_class_name().unwrap(), "BasicClass");
+/// assert_eq!(class_file.methods.len(), 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClassFileRef<'a> {
+    pub header: ClassHeader<'a>,
+    pub fields: Vec<FieldInfoRef<'a>>,
+    pub methods: Vec<MethodInfoRef<'a>>,
+    pub attributes: Vec<AttributeInfoRef<'a>>,
+}
+
+impl<'a> ClassFileRef<'a> {
+    /// The internal name of this class, such as `java/lang/String`.
+    pub fn this_class_name(&self) -> Result<Cow<'a, str>, ConstantPoolError> {
+        self.header.this_class_name()
+    }
+
+    /// The internal name of the superclass, `None` for `java/lang/Object` and `module-info`.
+    pub fn super_class_name(&self) -> Result<Option<Cow<'a, str>>, ConstantPoolError> {
+        self.header.super_class_name()
+    }
+
+    /// The internal names of the directly implemented interfaces, in declaration order.
+    pub fn interface_names(&self) -> Result<Vec<Cow<'a, str>>, ConstantPoolError> {
+        self.header.interface_names()
+    }
+
+    /// Decode everything into an owned `ClassFile`.
+    pub fn to_class_file(&self) -> ClassFile {
+        let attributes = |attributes: &[AttributeInfoRef]| -> Vec<AttributeInfo> {
+            attributes
+                .iter()
+                .map(|&attribute| attribute.into())
+                .collect()
+        };
+        let header = &self.header;
+        let const_pool = header.const_pool.to_constants();
+        ClassFile {
+            minor_version: header.minor_version,
+            major_version: header.major_version,
+            const_pool_size: const_pool.len() as u16 + 1,
+            const_pool,
+            access_flags: header.access_flags,
+            this_class: header.this_class,
+            super_class: header.super_class,
+            interfaces_count: header.interfaces.len() as u16,
+            interfaces: header.interfaces.clone(),
+            fields_count: self.fields.len() as u16,
+            fields: self
+                .fields
+                .iter()
+                .map(|field| FieldInfo {
+                    access_flags: field.access_flags,
+                    name_index: field.name_index,
+                    descriptor_index: field.descriptor_index,
+                    attributes_count: field.attributes.len() as u16,
+                    attributes: attributes(&field.attributes),
+                })
+                .collect(),
+            methods_count: self.methods.len() as u16,
+            methods: self
+                .methods
+                .iter()
+                .map(|method| MethodInfo {
+                    access_flags: method.access_flags,
+                    name_index: method.name_index,
+                    descriptor_index: method.descriptor_index,
+                    attributes_count: method.attributes.len() as u16,
+                    attributes: attributes(&method.attributes),
+                })
+                .collect(),
+            attributes_count: self.attributes.len() as u16,
+            attributes: attributes(&self.attributes),
+        }
+    }
+}
+
+/// The start of a class file: its version and constant pool.
+#[derive(Clone, Debug)]
+pub struct ClassPrefix<'a> {
+    pub minor_version: u16,
+    pub major_version: u16,
+    pub const_pool: ConstantPoolRef<'a>,
+}
+
+/// Everything in a class file before its field table, which is enough to place the class in
+/// a hierarchy.
+///
+/// ```rust
+/// use classfile_parser::ClassAccessFlags;
+///
+/// let classfile_bytes = include_bytes!("../../java-assets/compiled-classes/BasicClass.class");
+/// let header = classfile_parser::parse_header(classfile_bytes).unwrap();
+///
+/// assert_eq!(header.this_class_name().unwrap(), "BasicClass");
+/// assert_eq!(header.super_class_name().unwrap().unwrap(), "java/lang/Object");
+/// assert!(header.access_flags.contains(ClassAccessFlags::PUBLIC));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClassHeader<'a> {
+    pub minor_version: u16,
+    pub major_version: u16,
+    pub const_pool: ConstantPoolRef<'a>,
+    pub access_flags: ClassAccessFlags,
+    pub this_class: u16,
+    pub super_class: u16,
+    pub interfaces: Vec<u16>,
+}
+
+impl<'a> ClassHeader<'a> {
+    /// The internal name of this class, such as `java/lang/String`.
+    pub fn this_class_name(&self) -> Result<Cow<'a, str>, ConstantPoolError> {
+        self.const_pool.get_class_name(self.this_class)
+    }
+
+    /// The internal name of the superclass, `None` for `java/lang/Object` and `module-info`.
+    pub fn super_class_name(&self) -> Result<Option<Cow<'a, str>>, ConstantPoolError> {
+        match self.super_class {
+            0 => Ok(None),
+            index => self.const_pool.get_class_name(index).map(Some),
+        }
+    }
+
+    /// The internal names of the directly implemented interfaces, in declaration order.
+    pub fn interface_names(&self) -> Result<Vec<Cow<'a, str>>, ConstantPoolError> {
+        self.interfaces
+            .iter()
+            .map(|&index| self.const_pool.get_class_name(index))
+            .collect()
+    }
+}
+
+/// Where a field or method is in the class file bytes, with the parts of its header needed
+/// to pick it out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemberLayout<F> {
+    pub access_flags: F,
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    /// The byte range of the whole `field_info` or `method_info` structure.
+    pub range: Range<usize>,
+}
+
+/// A class file whose fields, methods and attributes have been stepped over rather than
+/// parsed, recording the byte range of each so they can be decoded later.
+///
+/// ```rust
+/// use classfile_parser::method_info::method_parser;
+///
+/// let classfile_bytes = include_bytes!("../../java-assets/compiled-classes/Factorial.class");
+/// let layout = classfile_parser::scan_class(classfile_bytes).unwrap();
+///
+/// let range = layout.methods[1].range.clone();
+/// let (_, method) = method_parser(&classfile_bytes[range]).unwrap();
+/// assert_eq!(method.name_index, layout.methods[1].name_index);
+/// assert_eq!(layout.attributes.end, classfile_bytes.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClassLayout<'a> {
+    pub header: ClassHeader<'a>,
+    pub fields: Vec<MemberLayout<FieldAccessFlags>>,
+    pub methods: Vec<MemberLayout<MethodAccessFlags>>,
+    /// The byte range of the class's attributes table, including its count.
+    pub attributes: Range<usize>,
+}
+
+/// A constant pool that records where each constant starts and decodes it on access.
+#[derive(Clone, Debug)]
+pub struct ConstantPoolRef<'a> {
+    bytes: &'a [u8],
+    /// The offset of each entry in `bytes`, `None` for the slot after a Long or Double.
+    offsets: Vec<Option<u32>>,
+}
+
+impl<'a> ConstantPoolRef<'a> {
+    pub(crate) fn new(bytes: &'a [u8], offsets: Vec<Option<u32>>) -> Self {
+        ConstantPoolRef { bytes, offsets }
+    }
+
+    /// The number of entries, counting the unusable slots after Long and Double constants.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    fn offset(&self, index: u16) -> Result<usize, ConstantPoolError> {
+        match index
+            .checked_sub(1)
+            .and_then(|i| self.offsets.get(i as usize))
+        {
+            Some(Some(offset)) => Ok(*offset as usize),
+            _ => Err(ConstantPoolError::OutOfRange(index)),
+        }
+    }
+
+    /// Decode the constant at `index`, copying it out of the class file bytes.
+    pub fn get(&self, index: u16) -> Result<ConstantInfo, ConstantPoolError> {
+        let offset = self.offset(index)?;
+        let (_, constant) = single_constant_parser(&self.bytes[offset..])
+            .expect("constant pool entries are checked when the class file is parsed");
+        Ok(constant)
+    }
+
+    /// The modified UTF-8 bytes of a Utf8 constant, borrowed from the class file.
+    pub fn get_utf8_bytes(&self, index: u16) -> Result<&'a [u8], ConstantPoolError> {
+        let offset = self.offset(index)?;
+        let bytes = self.bytes;
+        if bytes[offset] != 1 {
+            return Err(wrong_kind(index, "Utf8", &self.get(index)?));
+        }
+        let length = u16::from_be_bytes([bytes[offset + 1], bytes[offset + 2]]) as usize;
+        Ok(&bytes[offset + 3..offset + 3 + length])
+    }
+
+    /// The value of a Utf8 constant, borrowed from the class file unless it has to be
+    /// converted from modified UTF-8.
+    pub fn get_utf8(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        let bytes = self.get_utf8_bytes(index)?;
+        Ok(cesu8::from_java_cesu8(bytes).unwrap_or_else(|_| String::from_utf8_lossy(bytes)))
+    }
+
+    /// The internal name of a Class constant, such as `java/lang/String` or `[I`.
+    pub fn get_class_name(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::Class(class) => self.get_utf8(class.name_index),
+            other => Err(wrong_kind(index, "Class", &other)),
+        }
+    }
+
+    /// The value of a String constant.
+    pub fn get_string(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::String(string) => self.get_utf8(string.string_index),
+            other => Err(wrong_kind(index, "String", &other)),
+        }
+    }
+
+    /// The name and descriptor of a NameAndType constant.
+    pub fn get_name_and_type(
+        &self,
+        index: u16,
+    ) -> Result<(Cow<'a, str>, Cow<'a, str>), ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::NameAndType(nat) => Ok((
+                self.get_utf8(nat.name_index)?,
+                self.get_utf8(nat.descriptor_index)?,
+            )),
+            other => Err(wrong_kind(index, "NameAndType", &other)),
+        }
+    }
+
+    /// The class name, member name and descriptor of a FieldRef, MethodRef or
+    /// InterfaceMethodRef constant.
+    pub fn resolve_member_ref(&self, index: u16) -> Result<MemberRef<'a>, ConstantPoolError> {
+        let (class_index, name_and_type_index) = match self.get(index)? {
+            ConstantInfo::FieldRef(r) => (r.class_index, r.name_and_type_index),
+            ConstantInfo::MethodRef(r) => (r.class_index, r.name_and_type_index),
+            ConstantInfo::InterfaceMethodRef(r) => (r.class_index, r.name_and_type_index),
+            other => {
+                return Err(wrong_kind(
+                    index,
+                    "FieldRef, MethodRef or InterfaceMethodRef",
+                    &other,
+                ));
+            }
+        };
+        let class = self.get_class_name(class_index)?;
+        let (name, descriptor) = self.get_name_and_type(name_and_type_index)?;
+        Ok((class, name, descriptor))
+    }
+
+    /// Decode every constant, as in `ClassFile::const_pool`.
+    pub fn to_constants(&self) -> Vec<ConstantInfo> {
+        (1..=self.offsets.len() as u16)
+            .map(|index| self.get(index).unwrap_or(ConstantInfo::Unusable))
+            .collect()
+    }
+}
+
+/// An attribute whose body borrows from the class file bytes.
+#[derive(Clone, Copy, Debug)]
+pub struct AttributeInfoRef<'a> {
+    pub attribute_name_index: u16,
+    pub info: &'a [u8],
+}
+
+impl From<AttributeInfoRef<'_>> for AttributeInfo {
+    fn from(attribute: AttributeInfoRef<'_>) -> Self {
+        AttributeInfo {
+            attribute_name_index: attribute.attribute_name_index,
+            attribute_length: attribute.info.len() as u32,
+            info: attribute.info.to_vec(),
+        }
+    }
+}
+
+/// The first attribute called `name`.
+fn find_attribute<'a>(
+    attributes: &[AttributeInfoRef<'a>],
+    const_pool: &ConstantPoolRef,
+    name: &str,
+) -> Option<AttributeInfoRef<'a>> {
+    attributes
+        .iter()
+        .find(|attribute| {
+            const_pool
+                .get_utf8_bytes(attribute.attribute_name_index)
+                .is_ok_and(|bytes| bytes == name.as_bytes())
+        })
+        .copied()
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldInfoRef<'a> {
+    pub access_flags: FieldAccessFlags,
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    pub attributes: Vec<AttributeInfoRef<'a>>,
+}
+
+impl<'a> FieldInfoRef<'a> {
+    /// The first attribute called `name`, such as `ConstantValue`.
+    pub fn attribute(
+        &self,
+        const_pool: &ConstantPoolRef,
+        name: &str,
+    ) -> Option<AttributeInfoRef<'a>> {
+        find_attribute(&self.attributes, const_pool, name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MethodInfoRef<'a> {
+    pub access_flags: MethodAccessFlags,
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    pub attributes: Vec<AttributeInfoRef<'a>>,
+}
+
+impl<'a> MethodInfoRef<'a> {
+    /// The first attribute called `name`, such as `Exceptions`.
+    pub fn attribute(
+        &self,
+        const_pool: &ConstantPoolRef,
+        name: &str,
+    ) -> Option<AttributeInfoRef<'a>> {
+        find_attribute(&self.attributes, const_pool, name)
+    }
+
+    /// The method's Code attribute, `None` for abstract and native methods.
+    pub fn code(
+        &self,
+        const_pool: &ConstantPoolRef,
+    ) -> Result<Option<CodeAttributeRef<'a>>, ClassParseError> {
+        let Some(attribute) = self.attribute(const_pool, "Code") else {
+            return Ok(None);
+        };
+        let (_, code) = code_attribute_ref_parser(attribute.info).map_err(|e| {
+            ClassParseError::from_nom(attribute.info, e)
+                .within(0, ParseContext::Attribute("Code".to_string()))
+        })?;
+        Ok(Some(code))
+    }
+}
+
+/// A Code attribute whose code array and nested attributes borrow from the class file bytes.
+#[derive(Clone, Debug)]
+pub struct CodeAttributeRef<'a> {
+    pub max_stack: u16,
+    pub max_locals: u16,
+    pub code: &'a [u8],
+    pub exception_table: Vec<ExceptionEntry>,
+    pub attributes: Vec<AttributeInfoRef<'a>>,
+}
+
+impl<'a> CodeAttributeRef<'a> {
+    /// Decode the bytecode into `(address, Instruction)` pairs, failing on the first byte
+    /// that is not a valid instruction.
+    pub fn instructions(&self) -> Result<Vec<(usize, Instruction)>, ClassParseError> {
+        decode_instructions(self.code)
+    }
+
+    /// The first nested attribute called `name`, such as `LineNumberTable`.
+    pub fn attribute(
+        &self,
+        const_pool: &ConstantPoolRef,
+        name: &str,
+    ) -> Option<AttributeInfoRef<'a>> {
+        find_attribute(&self.attributes, const_pool, name)
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::attribute_info::{BootstrapMethod, TypedAttribute};
+use crate::code_attribute::Instruction;
+use crate::constant_info::{ConstantInfo, ConstantPool, ConstantPoolError, wrong_kind};
+use crate::error::ClassParseError;
+use crate::hierarchy::ClassHierarchy;
+use crate::method_info::MethodAccessFlags;
+use crate::types::{ClassAccessFlags, ClassFile};
+
+use super::types::*;
+
+/// The methods declared by each class, by owner and then by name and descriptor.
+type Declarations = HashMap<String, HashMap<(String, String), MethodAccessFlags>>;
+
+fn declarations(classes: &[ClassFile]) -> Result<Declarations, ConstantPoolError> {
+    let mut declarations = Declarations::new();
+    for class_file in classes {
+        let pool = class_file.constant_pool();
+        let methods = declarations
+            .entry(class_file.this_class_name()?.into_owned())
+            .or_default();
+        for method in &class_file.methods {
+            let name = pool.get_utf8(method.name_index)?.into_owned();
+            let descriptor = pool.get_utf8(method.descriptor_index)?.into_owned();
+            methods.insert((name, descriptor), method.access_flags);
+        }
+    }
+    Ok(declarations)
+}
+
+/// Resolves virtual calls by class hierarchy analysis: a call can reach the implementation
+/// of the method seen by each concrete class that is a subtype of the named owner.
+struct Dispatcher<'a> {
+    hierarchy: &'a ClassHierarchy,
+    declarations: Declarations,
+}
+
+impl Dispatcher<'_> {
+    /// The implementation `class` declares for `name` and `descriptor`, if any.
+    fn declared(&self, class: &str, name: &str, descriptor: &str) -> Option<MethodId> {
+        let flags = self
+            .declarations
+            .get(class)?
+            .get(&(name.to_string(), descriptor.to_string()))?;
+        let not_virtual =
+            MethodAccessFlags::STATIC | MethodAccessFlags::PRIVATE | MethodAccessFlags::ABSTRACT;
+        (!flags.intersects(not_virtual)).then(|| MethodId::new(class, name, descriptor))
+    }
+
+    /// The implementation used for an instance of `receiver`: the nearest declaration in a
+    /// superclass, or failing that a default method of an interface.
+    fn lookup(&self, receiver: &str, name: &str, descriptor: &str) -> Option<MethodId> {
+        std::iter::once(receiver)
+            .chain(self.hierarchy.superclasses(receiver))
+            .find_map(|class| self.declared(class, name, descriptor))
+            .or_else(|| {
+                self.hierarchy
+                    .supertypes(receiver)
+                    .into_iter()
+                    .filter(|supertype| {
+                        self.hierarchy
+                            .get(supertype)
+                            .is_some_and(|class| class.is_interface())
+                    })
+                    .find_map(|interface| self.declared(interface, name, descriptor))
+            })
+    }
+
+    /// The method `receiver` inherits from outside the analysed classes, when no analysed
+    /// class implements it: the method on its first superclass that was not analysed, or
+    /// failing that the statically named `target`.
+    fn inherited_from_outside(&self, receiver: &str, target: &MethodId) -> MethodId {
+        std::iter::once(receiver)
+            .chain(self.hierarchy.superclasses(receiver))
+            .find(|class| !self.declarations.contains_key(*class))
+            .map_or_else(
+                || target.clone(),
+                |class| MethodId::new(class, &target.name, &target.descriptor),
+            )
+    }
+
+    fn targets(&self, target: &MethodId) -> Vec<MethodId> {
+        let abstract_type = ClassAccessFlags::INTERFACE | ClassAccessFlags::ABSTRACT;
+        let targets: BTreeSet<MethodId> = std::iter::once(target.owner.as_str())
+            .chain(self.hierarchy.subtypes(&target.owner))
+            .filter(|receiver| {
+                self.hierarchy
+                    .get(receiver)
+                    .is_none_or(|class| !class.access_flags.intersects(abstract_type))
+            })
+            .map(|receiver| {
+                self.lookup(receiver, &target.name, &target.descriptor)
+                    .unwrap_or_else(|| self.inherited_from_outside(receiver, target))
+            })
+            .collect();
+        if targets.is_empty() {
+            vec![target.clone()]
+        } else {
+            targets.into_iter().collect()
+        }
+    }
+}
+
+fn method_id(pool: &ConstantPool, index: u16) -> Result<MethodId, ConstantPoolError> {
+    let (owner, name, descriptor) = pool.resolve_member_ref(index)?;
+    Ok(MethodId::new(&owner, &name, &descriptor))
+}
+
+/// The method a MethodHandle constant refers to, `None` if `index` is another kind of
+/// constant.
+fn method_handle(pool: &ConstantPool, index: u16) -> Result<Option<MethodId>, ConstantPoolError> {
+    match pool.get(index)? {
+        ConstantInfo::MethodHandle(handle) => method_id(pool, handle.reference_index).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// The method implementing a lambda or method reference, which `LambdaMetafactory` takes
+/// as its second static argument.
+fn lambda_implementation(
+    pool: &ConstantPool,
+    bootstrap_method: &BootstrapMethod,
+    bootstrap: &MethodId,
+) -> Result<Option<MethodId>, ConstantPoolError> {
+    let metafactory = bootstrap.owner == "java/lang/invoke/LambdaMetafactory"
+        && matches!(bootstrap.name.as_str(), "metafactory" | "altMetafactory");
+    match bootstrap_method.bootstrap_arguments.get(1) {
+        Some(&index) if metafactory => method_handle(pool, index),
+        _ => Ok(None),
+    }
+}
+
+/// The source line of the instruction at `offset`, given `(start_pc, line_number)` pairs
+/// sorted by `start_pc`.
+fn line_at(lines: &[(u16, u16)], offset: usize) -> Option<u16> {
+    let after = lines.partition_point(|&(start_pc, _)| start_pc as usize <= offset);
+    after.checked_sub(1).map(|i| lines[i].1)
+}
+
+impl CallGraph {
+    /// Collect the call sites of every method of `classes`. With a `hierarchy`, virtual
+    /// and interface calls are expanded to every implementation in `classes` they can
+    /// reach, and to the nearest class outside `classes` for receivers that inherit the
+    /// method from there.
+    pub fn new(
+        classes: &[ClassFile],
+        hierarchy: Option<&ClassHierarchy>,
+    ) -> Result<CallGraph, ClassParseError> {
+        let dispatcher = match hierarchy {
+            Some(hierarchy) => Some(Dispatcher {
+                hierarchy,
+                declarations: declarations(classes)?,
+            }),
+            None => None,
+        };
+        let mut graph = CallGraph::default();
+        for class_file in classes {
+            let pool = class_file.constant_pool();
+            let this_class = class_file.this_class_name()?;
+            let bootstrap_methods = class_file
+                .typed_attributes()?
+                .into_iter()
+                .find_map(|attribute| match attribute {
+                    TypedAttribute::BootstrapMethods(attribute) => {
+                        Some(attribute.bootstrap_methods)
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default();
+
+            for method in &class_file.methods {
+                let Some(code) = method
+                    .typed_attributes(&class_file.const_pool)?
+                    .into_iter()
+                    .find_map(|attribute| match attribute {
+                        TypedAttribute::Code(code) => Some(code),
+                        _ => None,
+                    })
+                else {
+                    continue;
+                };
+                let mut lines: Vec<(u16, u16)> = code
+                    .typed_attributes(&class_file.const_pool)?
+                    .into_iter()
+                    .filter_map(|attribute| match attribute {
+                        TypedAttribute::LineNumberTable(table) => Some(table.line_number_table),
+                        _ => None,
+                    })
+                    .flatten()
+                    .map(|entry| (entry.start_pc, entry.line_number))
+                    .collect();
+                lines.sort_unstable();
+
+                let mut call_sites = Vec::new();
+                for (offset, instruction) in code.instructions()? {
+                    let (kind, index) = match instruction {
+                        Instruction::Invokevirtual(index) => (InvokeKind::Virtual, index),
+                        Instruction::Invokestatic(index) => (InvokeKind::Static, index),
+                        Instruction::Invokespecial(index) => (InvokeKind::Special, index),
+                        Instruction::Invokeinterface { index, .. } => {
+                            (InvokeKind::Interface, index)
+                        }
+                        Instruction::Invokedynamic(index) => (InvokeKind::Dynamic, index),
+                        _ => continue,
+                    };
+                    let (target, dynamic, targets) = if kind == InvokeKind::Dynamic {
+                        let call_site = match pool.get(index)? {
+                            ConstantInfo::InvokeDynamic(call_site) => call_site,
+                            other => return Err(wrong_kind(index, "InvokeDynamic", other).into()),
+                        };
+                        let (name, descriptor) =
+                            pool.get_name_and_type(call_site.name_and_type_index)?;
+                        let mut targets = Vec::new();
+                        let bootstrap_method =
+                            bootstrap_methods.get(call_site.bootstrap_method_attr_index as usize);
+                        let bootstrap = match bootstrap_method {
+                            Some(bootstrap_method) => {
+                                method_handle(&pool, bootstrap_method.bootstrap_method_ref)?
+                            }
+                            None => None,
+                        };
+                        if let (Some(bootstrap_method), Some(bootstrap)) =
+                            (bootstrap_method, &bootstrap)
+                        {
+                            targets.push(bootstrap.clone());
+                            targets.extend(lambda_implementation(
+                                &pool,
+                                bootstrap_method,
+                                bootstrap,
+                            )?);
+                        }
+                        let dynamic = DynamicCallSite {
+                            name: name.into_owned(),
+                            descriptor: descriptor.into_owned(),
+                            bootstrap,
+                        };
+                        (None, Some(dynamic), targets)
+                    } else {
+                        let target = method_id(&pool, index)?;
+                        let targets = match &dispatcher {
+                            Some(dispatcher) if kind.is_virtual() => dispatcher.targets(&target),
+                            _ => vec![target.clone()],
+                        };
+                        (Some(target), None, targets)
+                    };
+                    call_sites.push(CallSite {
+                        offset,
+                        line: line_at(&lines, offset),
+                        kind,
+                        target,
+                        dynamic,
+                        targets,
+                    });
+                }
+
+                let caller = MethodId::new(
+                    &this_class,
+                    &pool.get_utf8(method.name_index)?,
+                    &pool.get_utf8(method.descriptor_index)?,
+                );
+                graph.methods.insert(caller, call_sites);
+            }
+        }
+        Ok(graph)
+    }
+}
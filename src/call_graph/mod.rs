@@ -0,0 +1,6 @@
+//! Call graphs built from the invoke instructions of a set of classes.
+
+mod builder;
+mod types;
+
+pub use self::types::*;
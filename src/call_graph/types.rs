@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The instruction a call is made with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum InvokeKind {
+    Virtual,
+    Static,
+    Special,
+    Interface,
+    Dynamic,
+}
+
+impl InvokeKind {
+    /// Whether the method called depends on the class of the receiver.
+    pub fn is_virtual(self) -> bool {
+        matches!(self, InvokeKind::Virtual | InvokeKind::Interface)
+    }
+}
+
+/// A method, by the internal name of its class, its name and its descriptor.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct MethodId {
+    pub owner: String,
+    pub name: String,
+    pub descriptor: String,
+}
+
+impl MethodId {
+    pub fn new(owner: &str, name: &str, descriptor: &str) -> Self {
+        MethodId {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for MethodId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}{}", self.owner, self.name, self.descriptor)
+    }
+}
+
+/// The call site of an `invokedynamic` instruction, which names no method of its own.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DynamicCallSite {
+    pub name: String,
+    pub descriptor: String,
+    /// The bootstrap method that links the call site.
+    pub bootstrap: Option<MethodId>,
+}
+
+/// An invoke instruction in a method's bytecode.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallSite {
+    /// The bytecode offset of the instruction.
+    pub offset: usize,
+    /// The source line of the instruction, from the method's LineNumberTable.
+    pub line: Option<u16>,
+    pub kind: InvokeKind,
+    /// The method named by the instruction, `None` for `invokedynamic`.
+    pub target: Option<MethodId>,
+    /// For `invokedynamic`, the call site's name, descriptor and bootstrap method.
+    pub dynamic: Option<DynamicCallSite>,
+    /// The methods the call can reach. For non-virtual calls, and for virtual calls when
+    /// the graph was built without a class hierarchy, this is just `target`. For
+    /// `invokedynamic` it is the bootstrap method and, for lambdas and method references
+    /// made by `LambdaMetafactory`, the method implementing them.
+    pub targets: Vec<MethodId>,
+}
+
+/// The call sites of every method of a set of classes.
+///
+/// ```rust
+/// use classfile_parser::call_graph::{CallGraph, InvokeKind, MethodId};
+///
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+/// let graph = CallGraph::new(&[class_file], None).unwrap();
+///
+/// let factorial = MethodId::new("Factorial", "factorial", "(I)I");
+/// let calls = graph.call_sites(&factorial);
+/// assert_eq!(calls.len(), 1);
+/// assert_eq!(calls[0].kind, InvokeKind::Static);
+/// assert_eq!(calls[0].target, Some(factorial));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CallGraph {
+    /// The call sites of each method, in bytecode order. Methods without code, or that make
+    /// no calls, have no call sites.
+    pub methods: BTreeMap<MethodId, Vec<CallSite>>,
+}
+
+impl CallGraph {
+    /// The call sites in `method`.
+    pub fn call_sites(&self, method: &MethodId) -> &[CallSite] {
+        self.methods.get(method).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every `(caller, callee)` pair, with one entry per call site and target.
+    pub fn edges(&self) -> impl Iterator<Item = (&MethodId, &MethodId)> {
+        self.methods.iter().flat_map(|(caller, call_sites)| {
+            call_sites
+                .iter()
+                .flat_map(move |call_site| call_site.targets.iter().map(move |t| (caller, t)))
+        })
+    }
+
+    /// The methods with a call site that can reach `method`, with those call sites.
+    pub fn callers<'a>(
+        &'a self,
+        method: &'a MethodId,
+    ) -> impl Iterator<Item = (&'a MethodId, &'a CallSite)> {
+        self.methods.iter().flat_map(move |(caller, call_sites)| {
+            call_sites
+                .iter()
+                .filter(move |call_site| call_site.targets.contains(method))
+                .map(move |call_site| (caller, call_site))
+        })
+    }
+}
@@ -0,0 +1,6 @@
+//! Looking up classes by name across an ordered list of directories and JARs, as a Java
+//! class loader searches its classpath.
+
+mod types;
+
+pub use self::types::*;
@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "jar")]
+use std::{fs::File, io::BufReader};
+
+use crate::constant_info::ConstantPoolError;
+use crate::error::ClassParseError;
+#[cfg(feature = "jar")]
+use crate::jar::{JarError, JarFile};
+use crate::parse_class_from_bytes;
+use crate::types::ClassFile;
+
+/// Where a class was found on a classpath.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClassSource {
+    /// A `.class` file below a directory entry.
+    File(PathBuf),
+    /// An entry of a JAR.
+    Jar { jar: PathBuf, entry: String },
+}
+
+impl fmt::Display for ClassSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassSource::File(path) => write!(f, "{}", path.display()),
+            ClassSource::Jar { jar, entry } => write!(f, "{}!/{}", jar.display(), entry),
+        }
+    }
+}
+
+/// An error returned when a classpath entry cannot be read, or a class on it cannot be parsed.
+#[derive(Debug)]
+pub enum ClassPathError {
+    /// A file or directory could not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A classpath entry is neither a directory nor, with the `jar` feature, a JAR.
+    UnsupportedEntry(PathBuf),
+    /// A class could not be parsed.
+    Class {
+        source: ClassSource,
+        error: ClassParseError,
+    },
+    /// A JAR could not be read.
+    #[cfg(feature = "jar")]
+    Jar { path: PathBuf, error: JarError },
+}
+
+impl fmt::Display for ClassPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassPathError::Io { path, error } => {
+                write!(f, "unable to read {}: {}", path.display(), error)
+            }
+            ClassPathError::UnsupportedEntry(path) => {
+                write!(f, "unsupported classpath entry {}", path.display())
+            }
+            ClassPathError::Class { source, error } => write!(f, "{}: {}", source, error),
+            #[cfg(feature = "jar")]
+            ClassPathError::Jar { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for ClassPathError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClassPathError::Io { error, .. } => Some(error),
+            ClassPathError::UnsupportedEntry(_) => None,
+            ClassPathError::Class { error, .. } => Some(error),
+            #[cfg(feature = "jar")]
+            ClassPathError::Jar { error, .. } => Some(error),
+        }
+    }
+}
+
+enum Entry {
+    Directory(PathBuf),
+    #[cfg(feature = "jar")]
+    Jar(PathBuf, JarFile<BufReader<File>>),
+}
+
+impl Entry {
+    /// Where `name` is in this entry, without reading it.
+    fn find(&self, name: &str) -> Option<ClassSource> {
+        match self {
+            Entry::Directory(directory) => {
+                if !is_relative_class_name(name) {
+                    return None;
+                }
+                let path = directory.join(format!("{}.class", name));
+                path.is_file().then_some(ClassSource::File(path))
+            }
+            #[cfg(feature = "jar")]
+            Entry::Jar(path, jar) => jar.class_entry_name(name).map(|entry| ClassSource::Jar {
+                jar: path.clone(),
+                entry: entry.to_string(),
+            }),
+        }
+    }
+
+    fn read(&mut self, source: &ClassSource) -> Result<Vec<u8>, ClassPathError> {
+        match (self, source) {
+            (_, ClassSource::File(path)) => fs::read(path).map_err(|error| ClassPathError::Io {
+                path: path.clone(),
+                error,
+            }),
+            #[cfg(feature = "jar")]
+            (Entry::Jar(path, jar), ClassSource::Jar { entry, .. }) => {
+                jar.read_entry(entry).map_err(|error| ClassPathError::Jar {
+                    path: path.clone(),
+                    error,
+                })
+            }
+            _ => unreachable!("a class source always comes from its own entry"),
+        }
+    }
+
+    /// The internal names of every class in this entry.
+    fn class_names(&self) -> Result<Vec<String>, ClassPathError> {
+        match self {
+            Entry::Directory(directory) => {
+                let mut names = Vec::new();
+                walk(directory, "", &mut names)?;
+                Ok(names)
+            }
+            #[cfg(feature = "jar")]
+            Entry::Jar(_, jar) => Ok(jar.class_names().map(str::to_string).collect()),
+        }
+    }
+}
+
+/// Whether `name` stays below a directory it is joined to: not empty or absolute, without
+/// backslashes, and without empty, `.` or `..` segments.
+fn is_relative_class_name(name: &str) -> bool {
+    !name.contains('\\')
+        && Path::new(name)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        && name
+            .split('/')
+            .all(|segment| !matches!(segment, "" | "." | ".."))
+}
+
+/// Collect the internal names of the classes below `directory`, whose package is `prefix`.
+fn walk(directory: &Path, prefix: &str, names: &mut Vec<String>) -> Result<(), ClassPathError> {
+    let io_error = |error| ClassPathError::Io {
+        path: directory.to_path_buf(),
+        error,
+    };
+    for entry in fs::read_dir(directory).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        let file_type = entry.file_type().map_err(io_error)?;
+        if file_type.is_dir() {
+            walk(&entry.path(), &format!("{}{}/", prefix, file_name), names)?;
+        } else if let Some(class_name) = file_name.strip_suffix(".class") {
+            names.push(format!("{}{}", prefix, class_name));
+        }
+    }
+    Ok(())
+}
+
+/// The superclass and interfaces of `class_file`.
+fn direct_supertypes(class_file: &ClassFile) -> Result<Vec<String>, ConstantPoolError> {
+    let super_class = class_file.super_class_name()?;
+    let interfaces = class_file.interface_names()?;
+    Ok(super_class
+        .into_iter()
+        .chain(interfaces)
+        .map(|name| name.into_owned())
+        .collect())
+}
+
+/// An ordered list of directories and, with the `jar` feature, JARs to look classes up in.
+///
+/// Classes are looked up by binary name in internal form, such as `java/util/List`, and the
+/// first entry containing a class wins. Parsed classes are cached, so each is read at most
+/// once.
+///
+/// ```rust
+/// use classfile_parser::class_path::ClassPath;
+///
+/// let mut class_path = ClassPath::new();
+/// class_path.push("./java-assets/compiled-classes").unwrap();
+///
+/// let class_file = class_path.class("InnerClasses$HelloWorld").unwrap().unwrap();
+/// assert_eq!(class_file.interfaces_count, 0);
+/// assert!(class_path.class("java/lang/Object").unwrap().is_none());
+/// ```
+#[derive(Default)]
+pub struct ClassPath {
+    entries: Vec<Entry>,
+    /// Parsed classes by name with where they were read from, `None` for names that were
+    /// looked up and not found.
+    cache: HashMap<String, Option<(ClassSource, ClassFile)>>,
+}
+
+impl ClassPath {
+    pub fn new() -> Self {
+        ClassPath::default()
+    }
+
+    /// Build a classpath from a list of paths separated as in the `CLASSPATH` environment
+    /// variable: `:` on Unix and `;` on Windows. Empty elements are skipped.
+    pub fn parse(class_path: &str) -> Result<Self, ClassPathError> {
+        let mut result = ClassPath::new();
+        for path in std::env::split_paths(class_path) {
+            if !path.as_os_str().is_empty() {
+                result.push(path)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Append a directory, or with the `jar` feature a JAR, to the end of the classpath.
+    pub fn push<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ClassPathError> {
+        let path = path.as_ref().to_path_buf();
+        let metadata = fs::metadata(&path).map_err(|error| ClassPathError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        if metadata.is_dir() {
+            self.push_entry(Entry::Directory(path));
+            return Ok(());
+        }
+        #[cfg(feature = "jar")]
+        {
+            match JarFile::open(&path) {
+                Ok(jar) => self.push_jar(path, jar),
+                Err(error) => return Err(ClassPathError::Jar { path, error }),
+            }
+            Ok(())
+        }
+        #[cfg(not(feature = "jar"))]
+        Err(ClassPathError::UnsupportedEntry(path))
+    }
+
+    /// Append an already opened JAR, for example one set up with `JarFile::with_release`.
+    /// `path` is used to report where its classes came from.
+    #[cfg(feature = "jar")]
+    pub fn push_jar<P: AsRef<Path>>(&mut self, path: P, jar: JarFile<BufReader<File>>) {
+        self.push_entry(Entry::Jar(path.as_ref().to_path_buf(), jar));
+    }
+
+    fn push_entry(&mut self, entry: Entry) {
+        // A class that was missing before may be in the new entry
+        self.cache.retain(|_, class_file| class_file.is_some());
+        self.entries.push(entry);
+    }
+
+    /// The number of entries on the classpath.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Where the class `name` would be loaded from, without reading it.
+    pub fn find(&self, name: &str) -> Option<ClassSource> {
+        self.entries.iter().find_map(|entry| entry.find(name))
+    }
+
+    /// Every place the class `name` can be found, in classpath order. All but the first are
+    /// shadowed.
+    pub fn find_all(&self, name: &str) -> Vec<ClassSource> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.find(name))
+            .collect()
+    }
+
+    /// Parse the class `name` from the first entry that contains it, `None` if no entry does.
+    pub fn class(&mut self, name: &str) -> Result<Option<&ClassFile>, ClassPathError> {
+        Ok(self.loaded(name)?.map(|(_, class_file)| class_file))
+    }
+
+    /// The cached class `name` and where it was read from, loading it if needed.
+    fn loaded(&mut self, name: &str) -> Result<Option<&(ClassSource, ClassFile)>, ClassPathError> {
+        if !self.cache.contains_key(name) {
+            let loaded = self.load(name)?;
+            self.cache.insert(name.to_string(), loaded);
+        }
+        Ok(self.cache[name].as_ref())
+    }
+
+    fn load(&mut self, name: &str) -> Result<Option<(ClassSource, ClassFile)>, ClassPathError> {
+        for entry in &mut self.entries {
+            if let Some(source) = entry.find(name) {
+                let bytes = entry.read(&source)?;
+                return match parse_class_from_bytes(&bytes) {
+                    Ok(class_file) => Ok(Some((source, class_file))),
+                    Err(error) => Err(ClassPathError::Class { source, error }),
+                };
+            }
+        }
+        Ok(None)
+    }
+
+    /// Every class that can be found in more than one entry, with all of its locations in
+    /// classpath order. `module-info` is not a class on the classpath, so it is ignored.
+    pub fn shadowed(&self) -> Result<BTreeMap<String, Vec<ClassSource>>, ClassPathError> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in &self.entries {
+            for name in entry.class_names()? {
+                *counts.entry(name).or_default() += 1;
+            }
+        }
+        Ok(counts
+            .into_iter()
+            .filter(|(name, count)| *count > 1 && name != "module-info")
+            .map(|(name, _)| {
+                let sources = self.find_all(&name);
+                (name, sources)
+            })
+            .collect())
+    }
+
+    /// The transitive superclasses and interfaces of the class `name`, nearest first, and
+    /// the names that are not on the classpath, starting with `name` itself if it is
+    /// missing. Missing classes are listed as supertypes but their own supertypes cannot be
+    /// followed.
+    ///
+    /// ```rust
+    /// use classfile_parser::class_path::ClassPath;
+    ///
+    /// let mut class_path = ClassPath::parse("./java-assets/compiled-classes").unwrap();
+    /// let (supertypes, missing) = class_path.supertypes("Nestmates$Square").unwrap();
+    /// assert_eq!(supertypes, ["java/lang/Object", "Nestmates$Shape"]);
+    /// assert_eq!(missing, ["java/lang/Object"]);
+    /// ```
+    pub fn supertypes(&mut self, name: &str) -> Result<(Vec<String>, Vec<String>), ClassPathError> {
+        let mut supertypes = Vec::new();
+        let mut missing = Vec::new();
+        let mut seen = HashSet::from([name.to_string()]);
+        let mut index = 0;
+        let mut current = name.to_string();
+        loop {
+            match self.loaded(&current)? {
+                Some((source, class_file)) => {
+                    let direct =
+                        direct_supertypes(class_file).map_err(|e| ClassPathError::Class {
+                            source: source.clone(),
+                            error: e.into(),
+                        })?;
+                    for name in direct {
+                        if seen.insert(name.clone()) {
+                            supertypes.push(name);
+                        }
+                    }
+                }
+                None => missing.push(current),
+            }
+            let Some(next) = supertypes.get(index) else {
+                break;
+            };
+            current = next.clone();
+            index += 1;
+        }
+        Ok((supertypes, missing))
+    }
+}
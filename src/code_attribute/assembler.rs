@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::code_attribute::encoder::instruction_encoder;
+use crate::code_attribute::types::Instruction;
+
+/// The maximum size of a code array, see JVMS 4.7.3.
+const MAX_CODE_LENGTH: usize = 65535;
+
+/// A position in the code being assembled, used as a branch target.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Label(usize);
+
+/// The branch instructions that can target a `Label`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Branch {
+    IfAcmpeq,
+    IfAcmpne,
+    IfIcmpeq,
+    IfIcmpne,
+    IfIcmplt,
+    IfIcmpge,
+    IfIcmpgt,
+    IfIcmple,
+    Ifeq,
+    Ifne,
+    Iflt,
+    Ifge,
+    Ifgt,
+    Ifle,
+    Ifnonnull,
+    Ifnull,
+    Goto,
+    Jsr,
+}
+
+impl Branch {
+    fn instruction(self, offset: i16) -> Instruction {
+        match self {
+            Branch::IfAcmpeq => Instruction::IfAcmpeq(offset),
+            Branch::IfAcmpne => Instruction::IfAcmpne(offset),
+            Branch::IfIcmpeq => Instruction::IfIcmpeq(offset),
+            Branch::IfIcmpne => Instruction::IfIcmpne(offset),
+            Branch::IfIcmplt => Instruction::IfIcmplt(offset),
+            Branch::IfIcmpge => Instruction::IfIcmpge(offset),
+            Branch::IfIcmpgt => Instruction::IfIcmpgt(offset),
+            Branch::IfIcmple => Instruction::IfIcmple(offset),
+            Branch::Ifeq => Instruction::Ifeq(offset),
+            Branch::Ifne => Instruction::Ifne(offset),
+            Branch::Iflt => Instruction::Iflt(offset),
+            Branch::Ifge => Instruction::Ifge(offset),
+            Branch::Ifgt => Instruction::Ifgt(offset),
+            Branch::Ifle => Instruction::Ifle(offset),
+            Branch::Ifnonnull => Instruction::Ifnonnull(offset),
+            Branch::Ifnull => Instruction::Ifnull(offset),
+            Branch::Goto => Instruction::Goto(offset),
+            Branch::Jsr => Instruction::Jsr(offset),
+        }
+    }
+
+    /// The branch taken when this one is not, used to jump over a `goto_w` when a
+    /// conditional branch target is out of range. `None` for `goto` and `jsr`.
+    fn inverse(self) -> Option<Branch> {
+        let inverse = match self {
+            Branch::IfAcmpeq => Branch::IfAcmpne,
+            Branch::IfAcmpne => Branch::IfAcmpeq,
+            Branch::IfIcmpeq => Branch::IfIcmpne,
+            Branch::IfIcmpne => Branch::IfIcmpeq,
+            Branch::IfIcmplt => Branch::IfIcmpge,
+            Branch::IfIcmpge => Branch::IfIcmplt,
+            Branch::IfIcmpgt => Branch::IfIcmple,
+            Branch::IfIcmple => Branch::IfIcmpgt,
+            Branch::Ifeq => Branch::Ifne,
+            Branch::Ifne => Branch::Ifeq,
+            Branch::Iflt => Branch::Ifge,
+            Branch::Ifge => Branch::Iflt,
+            Branch::Ifgt => Branch::Ifle,
+            Branch::Ifle => Branch::Ifgt,
+            Branch::Ifnonnull => Branch::Ifnull,
+            Branch::Ifnull => Branch::Ifnonnull,
+            Branch::Goto | Branch::Jsr => return None,
+        };
+        Some(inverse)
+    }
+
+    pub(crate) fn from_instruction(instruction: &Instruction) -> Option<(Branch, i32)> {
+        let branch = match *instruction {
+            Instruction::IfAcmpeq(o) => (Branch::IfAcmpeq, o.into()),
+            Instruction::IfAcmpne(o) => (Branch::IfAcmpne, o.into()),
+            Instruction::IfIcmpeq(o) => (Branch::IfIcmpeq, o.into()),
+            Instruction::IfIcmpne(o) => (Branch::IfIcmpne, o.into()),
+            Instruction::IfIcmplt(o) => (Branch::IfIcmplt, o.into()),
+            Instruction::IfIcmpge(o) => (Branch::IfIcmpge, o.into()),
+            Instruction::IfIcmpgt(o) => (Branch::IfIcmpgt, o.into()),
+            Instruction::IfIcmple(o) => (Branch::IfIcmple, o.into()),
+            Instruction::Ifeq(o) => (Branch::Ifeq, o.into()),
+            Instruction::Ifne(o) => (Branch::Ifne, o.into()),
+            Instruction::Iflt(o) => (Branch::Iflt, o.into()),
+            Instruction::Ifge(o) => (Branch::Ifge, o.into()),
+            Instruction::Ifgt(o) => (Branch::Ifgt, o.into()),
+            Instruction::Ifle(o) => (Branch::Ifle, o.into()),
+            Instruction::Ifnonnull(o) => (Branch::Ifnonnull, o.into()),
+            Instruction::Ifnull(o) => (Branch::Ifnull, o.into()),
+            Instruction::Goto(o) => (Branch::Goto, o.into()),
+            Instruction::GotoW(o) => (Branch::Goto, o),
+            Instruction::Jsr(o) => (Branch::Jsr, o.into()),
+            Instruction::JsrW(o) => (Branch::Jsr, o),
+            _ => return None,
+        };
+        Some(branch)
+    }
+}
+
+/// An element of the code being assembled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CodeItem {
+    /// An instruction encoded as it is. Any branch offsets it carries are used unchanged.
+    Instruction(Instruction),
+    /// Marks the position of a label.
+    Label(Label),
+    /// A branch to a label, widened to `goto_w`/`jsr_w` when the target is out of range.
+    Branch { branch: Branch, target: Label },
+    Tableswitch {
+        default: Label,
+        low: i32,
+        targets: Vec<Label>,
+    },
+    Lookupswitch {
+        default: Label,
+        pairs: Vec<(i32, Label)>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssemblerError {
+    /// A label was used as a branch target but never placed.
+    UnplacedLabel(Label),
+    /// A label was placed more than once.
+    LabelPlacedTwice(Label),
+    /// A branch target in decoded code does not fall on an instruction boundary.
+    InvalidBranchTarget { address: usize, target: i64 },
+    /// The assembled code is larger than the 65535 bytes allowed by the JVM.
+    CodeTooLarge(usize),
+    /// A label was not created by this assembler's `new_label`.
+    UnknownLabel(Label),
+    /// A `tableswitch` has no targets, or its keys run past `i32::MAX`.
+    InvalidTableswitch { low: i32, targets: usize },
+    /// A `lookupswitch` has more than one pair with the same key.
+    DuplicateLookupswitchKey(i32),
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblerError::UnplacedLabel(label) => write!(f, "label {} was never placed", label.0),
+            AssemblerError::LabelPlacedTwice(label) => {
+                write!(f, "label {} was placed more than once", label.0)
+            }
+            AssemblerError::InvalidBranchTarget { address, target } => write!(
+                f,
+                "branch at {} targets {}, which is not an instruction",
+                address, target
+            ),
+            AssemblerError::CodeTooLarge(length) => {
+                write!(f, "code length {} exceeds {}", length, MAX_CODE_LENGTH)
+            }
+            AssemblerError::UnknownLabel(label) => {
+                write!(f, "label {} does not belong to this assembler", label.0)
+            }
+            AssemblerError::InvalidTableswitch { low, targets } => write!(
+                f,
+                "tableswitch from {} with {} targets has no valid key range",
+                low, targets
+            ),
+            AssemblerError::DuplicateLookupswitchKey(key) => {
+                write!(f, "lookupswitch has more than one pair for key {}", key)
+            }
+        }
+    }
+}
+
+impl Error for AssemblerError {}
+
+/// The result of `Assembler::assemble`.
+#[derive(Clone, Debug)]
+pub struct AssembledCode {
+    /// The encoded code array.
+    pub code: Vec<u8>,
+    label_offsets: Vec<Option<usize>>,
+}
+
+impl AssembledCode {
+    /// The address a label was placed at, `None` if it was never placed.
+    pub fn label_offset(&self, label: Label) -> Option<usize> {
+        self.label_offsets.get(label.0).copied().flatten()
+    }
+}
+
+/// Builds a code array from instructions and labels, resolving branch targets and choosing
+/// between the short and wide forms of branches as needed.
+///
+/// ```rust
+/// use classfile_parser::code_attribute::{Assembler, Branch, Instruction};
+///
+/// let mut assembler = Assembler::new();
+/// let done = assembler.new_label();
+/// assembler.emit(Instruction::Iload0);
+/// assembler.branch(Branch::Ifeq, done);
+/// assembler.emit(Instruction::Iinc { index: 0, value: -1 });
+/// assembler.place(done);
+/// assembler.emit(Instruction::Return);
+///
+/// let assembled = assembler.assemble().unwrap();
+/// assert_eq!(assembled.code, vec![0x1a, 0x99, 0x00, 0x06, 0x84, 0x00, 0xff, 0xb1]);
+/// assert_eq!(assembled.label_offset(done), Some(7));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Assembler {
+    items: Vec<CodeItem>,
+    label_count: usize,
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Lift decoded code, as returned by `code_parser`, into an assembler. Every branch
+    /// target becomes a label so instructions can be inserted or removed without breaking
+    /// jumps. The returned map holds a label for every instruction address and for the end
+    /// of the code, which can be used to remap exception tables and debug attributes.
+    pub fn from_instructions(
+        instructions: &[(usize, Instruction)],
+    ) -> Result<(Self, BTreeMap<usize, Label>), AssemblerError> {
+        let mut assembler = Assembler::new();
+        let mut labels = BTreeMap::new();
+        for (address, _) in instructions {
+            labels.insert(*address, assembler.new_label());
+        }
+        let end = match instructions.last() {
+            Some((address, instruction)) => {
+                let mut encoded = Vec::new();
+                instruction_encoder(instruction, *address, &mut encoded);
+                address + encoded.len()
+            }
+            None => 0,
+        };
+        labels.insert(end, assembler.new_label());
+
+        let target = |address: usize, offset: i32| {
+            let target = address as i64 + offset as i64;
+            usize::try_from(target)
+                .ok()
+                .and_then(|t| labels.get(&t).copied())
+                .ok_or(AssemblerError::InvalidBranchTarget { address, target })
+        };
+
+        for (address, instruction) in instructions {
+            assembler.place(labels[address]);
+            let item = if let Some((branch, offset)) = Branch::from_instruction(instruction) {
+                CodeItem::Branch {
+                    branch,
+                    target: target(*address, offset)?,
+                }
+            } else {
+                match instruction {
+                    Instruction::Tableswitch {
+                        default,
+                        low,
+                        offsets,
+                        ..
+                    } => CodeItem::Tableswitch {
+                        default: target(*address, *default)?,
+                        low: *low,
+                        targets: offsets
+                            .iter()
+                            .map(|o| target(*address, *o))
+                            .collect::<Result<_, _>>()?,
+                    },
+                    Instruction::Lookupswitch { default, pairs } => CodeItem::Lookupswitch {
+                        default: target(*address, *default)?,
+                        pairs: pairs
+                            .iter()
+                            .map(|(key, o)| Ok((*key, target(*address, *o)?)))
+                            .collect::<Result<_, _>>()?,
+                    },
+                    other => CodeItem::Instruction(other.clone()),
+                }
+            };
+            assembler.items.push(item);
+        }
+        assembler.place(labels[&end]);
+        Ok((assembler, labels))
+    }
+
+    /// Create a new label, which must be placed with `place` before assembling if any
+    /// branch targets it.
+    pub fn new_label(&mut self) -> Label {
+        self.label_count += 1;
+        Label(self.label_count - 1)
+    }
+
+    /// Place a label at the current position.
+    pub fn place(&mut self, label: Label) {
+        self.items.push(CodeItem::Label(label));
+    }
+
+    /// Append an instruction.
+    pub fn emit(&mut self, instruction: Instruction) {
+        self.items.push(CodeItem::Instruction(instruction));
+    }
+
+    /// Append a branch to `target`.
+    pub fn branch(&mut self, branch: Branch, target: Label) {
+        self.items.push(CodeItem::Branch { branch, target });
+    }
+
+    /// Append a `tableswitch` for the keys `low..low + targets.len()`.
+    pub fn tableswitch(&mut self, low: i32, default: Label, targets: Vec<Label>) {
+        self.items.push(CodeItem::Tableswitch {
+            default,
+            low,
+            targets,
+        });
+    }
+
+    /// Append a `lookupswitch`. The pairs are sorted by key when assembling, as the JVM
+    /// requires, and each key may only appear once.
+    pub fn lookupswitch(&mut self, default: Label, pairs: Vec<(i32, Label)>) {
+        self.items.push(CodeItem::Lookupswitch { default, pairs });
+    }
+
+    pub fn items(&self) -> &[CodeItem] {
+        &self.items
+    }
+
+    /// Mutable access to the items, for inserting or removing code. Labels created
+    /// elsewhere must come from `new_label` on this assembler.
+    pub fn items_mut(&mut self) -> &mut Vec<CodeItem> {
+        &mut self.items
+    }
+
+    /// Resolve all labels and encode the code array.
+    pub fn assemble(&self) -> Result<AssembledCode, AssemblerError> {
+        // Start with every branch in its short form and widen the ones that do not fit,
+        // repeating until the layout is stable. Widening only ever grows the code so this
+        // terminates.
+        let mut wide = vec![false; self.items.len()];
+        loop {
+            let label_offsets = self.layout(&wide)?;
+            let mut changed = false;
+            let mut address = 0;
+            for (i, item) in self.items.iter().enumerate() {
+                if let CodeItem::Branch { target, .. } = item
+                    && !wide[i]
+                {
+                    let offset = self.resolve(&label_offsets, *target)? as i64 - address as i64;
+                    if i16::try_from(offset).is_err() {
+                        wide[i] = true;
+                        changed = true;
+                    }
+                }
+                address += self.item_size(item, address, wide[i]);
+            }
+            if !changed {
+                return self.encode(&wide, label_offsets);
+            }
+        }
+    }
+
+    fn resolve(
+        &self,
+        label_offsets: &[Option<usize>],
+        label: Label,
+    ) -> Result<usize, AssemblerError> {
+        match label_offsets.get(label.0) {
+            Some(Some(offset)) => Ok(*offset),
+            Some(None) => Err(AssemblerError::UnplacedLabel(label)),
+            None => Err(AssemblerError::UnknownLabel(label)),
+        }
+    }
+
+    /// The highest key of a `tableswitch`, which must have at least one target.
+    fn tableswitch_high(low: i32, targets: &[Label]) -> Result<i32, AssemblerError> {
+        i32::try_from(targets.len())
+            .ok()
+            .and_then(|len| low.checked_add(len.checked_sub(1)?))
+            .filter(|_| !targets.is_empty())
+            .ok_or(AssemblerError::InvalidTableswitch {
+                low,
+                targets: targets.len(),
+            })
+    }
+
+    fn item_size(&self, item: &CodeItem, address: usize, wide: bool) -> usize {
+        let padding = (4 - (address + 1) % 4) % 4;
+        match item {
+            CodeItem::Instruction(instruction) => {
+                let mut encoded = Vec::new();
+                instruction_encoder(instruction, address, &mut encoded);
+                encoded.len()
+            }
+            CodeItem::Label(_) => 0,
+            CodeItem::Branch { branch, .. } => match (wide, branch.inverse()) {
+                (false, _) => 3,
+                (true, None) => 5,
+                (true, Some(_)) => 8,
+            },
+            CodeItem::Tableswitch { targets, .. } => 1 + padding + 12 + 4 * targets.len(),
+            CodeItem::Lookupswitch { pairs, .. } => 1 + padding + 8 + 8 * pairs.len(),
+        }
+    }
+
+    fn layout(&self, wide: &[bool]) -> Result<Vec<Option<usize>>, AssemblerError> {
+        let mut label_offsets = vec![None; self.label_count];
+        let mut address = 0;
+        for (i, item) in self.items.iter().enumerate() {
+            if let CodeItem::Tableswitch { low, targets, .. } = item {
+                Self::tableswitch_high(*low, targets)?;
+            }
+            if let CodeItem::Label(label) = item {
+                let slot = label_offsets
+                    .get_mut(label.0)
+                    .ok_or(AssemblerError::UnknownLabel(*label))?;
+                if slot.is_some() {
+                    return Err(AssemblerError::LabelPlacedTwice(*label));
+                }
+                *slot = Some(address);
+            }
+            address += self.item_size(item, address, wide[i]);
+        }
+        if address > MAX_CODE_LENGTH {
+            return Err(AssemblerError::CodeTooLarge(address));
+        }
+        Ok(label_offsets)
+    }
+
+    fn encode(
+        &self,
+        wide: &[bool],
+        label_offsets: Vec<Option<usize>>,
+    ) -> Result<AssembledCode, AssemblerError> {
+        let mut code = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            let address = code.len();
+            let offset_to = |label: Label| -> Result<i32, AssemblerError> {
+                Ok((self.resolve(&label_offsets, label)? as i64 - address as i64) as i32)
+            };
+            match item {
+                CodeItem::Instruction(instruction) => {
+                    instruction_encoder(instruction, address, &mut code)
+                }
+                CodeItem::Label(_) => {}
+                CodeItem::Branch { branch, target } => {
+                    let offset = offset_to(*target)?;
+                    let instruction = match (wide[i], branch.inverse()) {
+                        (false, _) => branch.instruction(offset as i16),
+                        (true, None) if *branch == Branch::Goto => Instruction::GotoW(offset),
+                        (true, None) => Instruction::JsrW(offset),
+                        (true, Some(inverse)) => {
+                            // Skip over the goto_w that follows when the condition fails
+                            instruction_encoder(&inverse.instruction(8), address, &mut code);
+                            Instruction::GotoW(offset - 3)
+                        }
+                    };
+                    instruction_encoder(&instruction, code.len(), &mut code);
+                }
+                CodeItem::Tableswitch {
+                    default,
+                    low,
+                    targets,
+                } => {
+                    let instruction = Instruction::Tableswitch {
+                        default: offset_to(*default)?,
+                        low: *low,
+                        high: Self::tableswitch_high(*low, targets)?,
+                        offsets: targets
+                            .iter()
+                            .map(|t| offset_to(*t))
+                            .collect::<Result<_, _>>()?,
+                    };
+                    instruction_encoder(&instruction, address, &mut code);
+                }
+                CodeItem::Lookupswitch { default, pairs } => {
+                    let mut pairs = pairs
+                        .iter()
+                        .map(|(key, t)| Ok((*key, offset_to(*t)?)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    pairs.sort_by_key(|(key, _)| *key);
+                    if let Some(pair) = pairs.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                        return Err(AssemblerError::DuplicateLookupswitchKey(pair[0].0));
+                    }
+                    let instruction = Instruction::Lookupswitch {
+                        default: offset_to(*default)?,
+                        pairs,
+                    };
+                    instruction_encoder(&instruction, address, &mut code);
+                }
+            }
+        }
+        Ok(AssembledCode {
+            code,
+            label_offsets,
+        })
+    }
+}
@@ -0,0 +1,297 @@
+use crate::code_attribute::types::Instruction;
+
+fn align(address: usize, out: &mut Vec<u8>) {
+    out.resize(out.len() + (4 - address % 4) % 4, 0);
+}
+
+fn u8_op(out: &mut Vec<u8>, opcode: u8, operand: u8) {
+    out.extend_from_slice(&[opcode, operand]);
+}
+
+fn u16_op(out: &mut Vec<u8>, opcode: u8, operand: u16) {
+    out.push(opcode);
+    out.extend_from_slice(&operand.to_be_bytes());
+}
+
+fn i16_op(out: &mut Vec<u8>, opcode: u8, operand: i16) {
+    out.push(opcode);
+    out.extend_from_slice(&operand.to_be_bytes());
+}
+
+fn i32_op(out: &mut Vec<u8>, opcode: u8, operand: i32) {
+    out.push(opcode);
+    out.extend_from_slice(&operand.to_be_bytes());
+}
+
+fn wide_op(out: &mut Vec<u8>, opcode: u8, index: u16) {
+    out.extend_from_slice(&[0xc4, opcode]);
+    out.extend_from_slice(&index.to_be_bytes());
+}
+
+/// Encode a single instruction located at `address` and append it to `out`. This is the
+/// inverse of `instruction_parser`; the address is needed to pad `tableswitch` and
+/// `lookupswitch` to a 4-byte boundary.
+///
+/// Branch offsets are written as they are, relative to `address`.
+pub fn instruction_encoder(instruction: &Instruction, address: usize, out: &mut Vec<u8>) {
+    match *instruction {
+        Instruction::Aaload => out.push(0x32),
+        Instruction::Aastore => out.push(0x53),
+        Instruction::Aconstnull => out.push(0x01),
+        Instruction::Aload(index) => u8_op(out, 0x19, index),
+        Instruction::AloadWide(index) => wide_op(out, 0x19, index),
+        Instruction::Aload0 => out.push(0x2a),
+        Instruction::Aload1 => out.push(0x2b),
+        Instruction::Aload2 => out.push(0x2c),
+        Instruction::Aload3 => out.push(0x2d),
+        Instruction::Anewarray(index) => u16_op(out, 0xbd, index),
+        Instruction::Areturn => out.push(0xb0),
+        Instruction::Arraylength => out.push(0xbe),
+        Instruction::Astore(index) => u8_op(out, 0x3a, index),
+        Instruction::AstoreWide(index) => wide_op(out, 0x3a, index),
+        Instruction::Astore0 => out.push(0x4b),
+        Instruction::Astore1 => out.push(0x4c),
+        Instruction::Astore2 => out.push(0x4d),
+        Instruction::Astore3 => out.push(0x4e),
+        Instruction::Athrow => out.push(0xbf),
+        Instruction::Baload => out.push(0x33),
+        Instruction::Bastore => out.push(0x54),
+        Instruction::Bipush(value) => u8_op(out, 0x10, value as u8),
+        Instruction::Caload => out.push(0x34),
+        Instruction::Castore => out.push(0x55),
+        Instruction::Checkcast(index) => u16_op(out, 0xc0, index),
+        Instruction::D2f => out.push(0x90),
+        Instruction::D2i => out.push(0x8e),
+        Instruction::D2l => out.push(0x8f),
+        Instruction::Dadd => out.push(0x63),
+        Instruction::Daload => out.push(0x31),
+        Instruction::Dastore => out.push(0x52),
+        Instruction::Dcmpg => out.push(0x98),
+        Instruction::Dcmpl => out.push(0x97),
+        Instruction::Dconst0 => out.push(0x0e),
+        Instruction::Dconst1 => out.push(0x0f),
+        Instruction::Ddiv => out.push(0x6f),
+        Instruction::Dload(index) => u8_op(out, 0x18, index),
+        Instruction::DloadWide(index) => wide_op(out, 0x18, index),
+        Instruction::Dload0 => out.push(0x26),
+        Instruction::Dload1 => out.push(0x27),
+        Instruction::Dload2 => out.push(0x28),
+        Instruction::Dload3 => out.push(0x29),
+        Instruction::Dmul => out.push(0x6b),
+        Instruction::Dneg => out.push(0x77),
+        Instruction::Drem => out.push(0x73),
+        Instruction::Dreturn => out.push(0xaf),
+        Instruction::Dstore(index) => u8_op(out, 0x39, index),
+        Instruction::DstoreWide(index) => wide_op(out, 0x39, index),
+        Instruction::Dstore0 => out.push(0x47),
+        Instruction::Dstore1 => out.push(0x48),
+        Instruction::Dstore2 => out.push(0x49),
+        Instruction::Dstore3 => out.push(0x4a),
+        Instruction::Dsub => out.push(0x67),
+        Instruction::Dup => out.push(0x59),
+        Instruction::Dupx1 => out.push(0x5a),
+        Instruction::Dupx2 => out.push(0x5b),
+        Instruction::Dup2 => out.push(0x5c),
+        Instruction::Dup2x1 => out.push(0x5d),
+        Instruction::Dup2x2 => out.push(0x5e),
+        Instruction::F2d => out.push(0x8d),
+        Instruction::F2i => out.push(0x8b),
+        Instruction::F2l => out.push(0x8c),
+        Instruction::Fadd => out.push(0x62),
+        Instruction::Faload => out.push(0x30),
+        Instruction::Fastore => out.push(0x51),
+        Instruction::Fcmpg => out.push(0x96),
+        Instruction::Fcmpl => out.push(0x95),
+        Instruction::Fconst0 => out.push(0x0b),
+        Instruction::Fconst1 => out.push(0x0c),
+        Instruction::Fconst2 => out.push(0x0d),
+        Instruction::Fdiv => out.push(0x6e),
+        Instruction::Fload(index) => u8_op(out, 0x17, index),
+        Instruction::FloadWide(index) => wide_op(out, 0x17, index),
+        Instruction::Fload0 => out.push(0x22),
+        Instruction::Fload1 => out.push(0x23),
+        Instruction::Fload2 => out.push(0x24),
+        Instruction::Fload3 => out.push(0x25),
+        Instruction::Fmul => out.push(0x6a),
+        Instruction::Fneg => out.push(0x76),
+        Instruction::Frem => out.push(0x72),
+        Instruction::Freturn => out.push(0xae),
+        Instruction::Fstore(index) => u8_op(out, 0x38, index),
+        Instruction::FstoreWide(index) => wide_op(out, 0x38, index),
+        Instruction::Fstore0 => out.push(0x43),
+        Instruction::Fstore1 => out.push(0x44),
+        Instruction::Fstore2 => out.push(0x45),
+        Instruction::Fstore3 => out.push(0x46),
+        Instruction::Fsub => out.push(0x66),
+        Instruction::Getfield(index) => u16_op(out, 0xb4, index),
+        Instruction::Getstatic(index) => u16_op(out, 0xb2, index),
+        Instruction::Goto(offset) => i16_op(out, 0xa7, offset),
+        Instruction::GotoW(offset) => i32_op(out, 0xc8, offset),
+        Instruction::I2b => out.push(0x91),
+        Instruction::I2c => out.push(0x92),
+        Instruction::I2d => out.push(0x87),
+        Instruction::I2f => out.push(0x86),
+        Instruction::I2l => out.push(0x85),
+        Instruction::I2s => out.push(0x93),
+        Instruction::Iadd => out.push(0x60),
+        Instruction::Iaload => out.push(0x2e),
+        Instruction::Iand => out.push(0x7e),
+        Instruction::Iastore => out.push(0x4f),
+        Instruction::Iconstm1 => out.push(0x02),
+        Instruction::Iconst0 => out.push(0x03),
+        Instruction::Iconst1 => out.push(0x04),
+        Instruction::Iconst2 => out.push(0x05),
+        Instruction::Iconst3 => out.push(0x06),
+        Instruction::Iconst4 => out.push(0x07),
+        Instruction::Iconst5 => out.push(0x08),
+        Instruction::Idiv => out.push(0x6c),
+        Instruction::IfAcmpeq(offset) => i16_op(out, 0xa5, offset),
+        Instruction::IfAcmpne(offset) => i16_op(out, 0xa6, offset),
+        Instruction::IfIcmpeq(offset) => i16_op(out, 0x9f, offset),
+        Instruction::IfIcmpne(offset) => i16_op(out, 0xa0, offset),
+        Instruction::IfIcmplt(offset) => i16_op(out, 0xa1, offset),
+        Instruction::IfIcmpge(offset) => i16_op(out, 0xa2, offset),
+        Instruction::IfIcmpgt(offset) => i16_op(out, 0xa3, offset),
+        Instruction::IfIcmple(offset) => i16_op(out, 0xa4, offset),
+        Instruction::Ifeq(offset) => i16_op(out, 0x99, offset),
+        Instruction::Ifne(offset) => i16_op(out, 0x9a, offset),
+        Instruction::Iflt(offset) => i16_op(out, 0x9b, offset),
+        Instruction::Ifge(offset) => i16_op(out, 0x9c, offset),
+        Instruction::Ifgt(offset) => i16_op(out, 0x9d, offset),
+        Instruction::Ifle(offset) => i16_op(out, 0x9e, offset),
+        Instruction::Ifnonnull(offset) => i16_op(out, 0xc7, offset),
+        Instruction::Ifnull(offset) => i16_op(out, 0xc6, offset),
+        Instruction::Iinc { index, value } => out.extend_from_slice(&[0x84, index, value as u8]),
+        Instruction::IincWide { index, value } => {
+            wide_op(out, 0x84, index);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        Instruction::Iload(index) => u8_op(out, 0x15, index),
+        Instruction::IloadWide(index) => wide_op(out, 0x15, index),
+        Instruction::Iload0 => out.push(0x1a),
+        Instruction::Iload1 => out.push(0x1b),
+        Instruction::Iload2 => out.push(0x1c),
+        Instruction::Iload3 => out.push(0x1d),
+        Instruction::Imul => out.push(0x68),
+        Instruction::Ineg => out.push(0x74),
+        Instruction::Instanceof(index) => u16_op(out, 0xc1, index),
+        Instruction::Invokedynamic(index) => {
+            u16_op(out, 0xba, index);
+            out.extend_from_slice(&[0, 0]);
+        }
+        Instruction::Invokeinterface { index, count } => {
+            u16_op(out, 0xb9, index);
+            out.extend_from_slice(&[count, 0]);
+        }
+        Instruction::Invokespecial(index) => u16_op(out, 0xb7, index),
+        Instruction::Invokestatic(index) => u16_op(out, 0xb8, index),
+        Instruction::Invokevirtual(index) => u16_op(out, 0xb6, index),
+        Instruction::Ior => out.push(0x80),
+        Instruction::Irem => out.push(0x70),
+        Instruction::Ireturn => out.push(0xac),
+        Instruction::Ishl => out.push(0x78),
+        Instruction::Ishr => out.push(0x7a),
+        Instruction::Istore(index) => u8_op(out, 0x36, index),
+        Instruction::IstoreWide(index) => wide_op(out, 0x36, index),
+        Instruction::Istore0 => out.push(0x3b),
+        Instruction::Istore1 => out.push(0x3c),
+        Instruction::Istore2 => out.push(0x3d),
+        Instruction::Istore3 => out.push(0x3e),
+        Instruction::Isub => out.push(0x64),
+        Instruction::Iushr => out.push(0x7c),
+        Instruction::Ixor => out.push(0x82),
+        Instruction::Jsr(offset) => i16_op(out, 0xa8, offset),
+        Instruction::JsrW(offset) => i32_op(out, 0xc9, offset),
+        Instruction::L2d => out.push(0x8a),
+        Instruction::L2f => out.push(0x89),
+        Instruction::L2i => out.push(0x88),
+        Instruction::Ladd => out.push(0x61),
+        Instruction::Laload => out.push(0x2f),
+        Instruction::Land => out.push(0x7f),
+        Instruction::Lastore => out.push(0x50),
+        Instruction::Lcmp => out.push(0x94),
+        Instruction::Lconst0 => out.push(0x09),
+        Instruction::Lconst1 => out.push(0x0a),
+        Instruction::Ldc(index) => u8_op(out, 0x12, index),
+        Instruction::LdcW(index) => u16_op(out, 0x13, index),
+        Instruction::Ldc2W(index) => u16_op(out, 0x14, index),
+        Instruction::Ldiv => out.push(0x6d),
+        Instruction::Lload(index) => u8_op(out, 0x16, index),
+        Instruction::LloadWide(index) => wide_op(out, 0x16, index),
+        Instruction::Lload0 => out.push(0x1e),
+        Instruction::Lload1 => out.push(0x1f),
+        Instruction::Lload2 => out.push(0x20),
+        Instruction::Lload3 => out.push(0x21),
+        Instruction::Lmul => out.push(0x69),
+        Instruction::Lneg => out.push(0x75),
+        Instruction::Lookupswitch { default, ref pairs } => {
+            out.push(0xab);
+            align(address + 1, out);
+            out.extend_from_slice(&default.to_be_bytes());
+            out.extend_from_slice(&(pairs.len() as u32).to_be_bytes());
+            for (key, offset) in pairs {
+                out.extend_from_slice(&key.to_be_bytes());
+                out.extend_from_slice(&offset.to_be_bytes());
+            }
+        }
+        Instruction::Lor => out.push(0x81),
+        Instruction::Lrem => out.push(0x71),
+        Instruction::Lreturn => out.push(0xad),
+        Instruction::Lshl => out.push(0x79),
+        Instruction::Lshr => out.push(0x7b),
+        Instruction::Lstore(index) => u8_op(out, 0x37, index),
+        Instruction::LstoreWide(index) => wide_op(out, 0x37, index),
+        Instruction::Lstore0 => out.push(0x3f),
+        Instruction::Lstore1 => out.push(0x40),
+        Instruction::Lstore2 => out.push(0x41),
+        Instruction::Lstore3 => out.push(0x42),
+        Instruction::Lsub => out.push(0x65),
+        Instruction::Lushr => out.push(0x7d),
+        Instruction::Lxor => out.push(0x83),
+        Instruction::Monitorenter => out.push(0xc2),
+        Instruction::Monitorexit => out.push(0xc3),
+        Instruction::Multianewarray { index, dimensions } => {
+            u16_op(out, 0xc5, index);
+            out.push(dimensions);
+        }
+        Instruction::New(index) => u16_op(out, 0xbb, index),
+        Instruction::Newarray(atype) => u8_op(out, 0xbc, atype),
+        Instruction::Nop => out.push(0x00),
+        Instruction::Pop => out.push(0x57),
+        Instruction::Pop2 => out.push(0x58),
+        Instruction::Putfield(index) => u16_op(out, 0xb5, index),
+        Instruction::Putstatic(index) => u16_op(out, 0xb3, index),
+        Instruction::Ret(index) => u8_op(out, 0xa9, index),
+        Instruction::RetWide(index) => wide_op(out, 0xa9, index),
+        Instruction::Return => out.push(0xb1),
+        Instruction::Saload => out.push(0x35),
+        Instruction::Sastore => out.push(0x56),
+        Instruction::Sipush(value) => i16_op(out, 0x11, value),
+        Instruction::Swap => out.push(0x5f),
+        Instruction::Tableswitch {
+            default,
+            low,
+            high,
+            ref offsets,
+        } => {
+            out.push(0xaa);
+            align(address + 1, out);
+            out.extend_from_slice(&default.to_be_bytes());
+            out.extend_from_slice(&low.to_be_bytes());
+            out.extend_from_slice(&high.to_be_bytes());
+            for offset in offsets {
+                out.extend_from_slice(&offset.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Encode a sequence of instructions into a code array, the inverse of `code_parser`.
+/// Each instruction is placed directly after the previous one, starting at address 0.
+pub fn code_encoder(instructions: &[Instruction]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for instruction in instructions {
+        instruction_encoder(instruction, out.len(), &mut out);
+    }
+    out
+}
@@ -1,8 +1,13 @@
+mod assembler;
+mod encoder;
 mod parser;
 mod types;
 
 pub use self::types::*;
 
+pub use self::assembler::{AssembledCode, Assembler, AssemblerError, Branch, CodeItem, Label};
+pub use self::encoder::code_encoder;
+pub use self::encoder::instruction_encoder;
 pub use self::parser::code_parser;
 pub use self::parser::instruction_parser;
 pub use self::parser::local_variable_table_parser;
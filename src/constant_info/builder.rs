@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use crate::types::ClassFile;
+
+use super::pool::{ConstantPool, ConstantPoolError, MAX_ENTRIES};
+use super::types::*;
+
+/// The identity of a constant, used to find an existing copy of it. Floating point values
+/// are compared by their bits, so `NaN` constants are deduplicated too.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum ConstantKey {
+    Utf8(Vec<u8>),
+    Integer(i32),
+    Float(u32),
+    Long(i64),
+    Double(u64),
+    Class(u16),
+    String(u16),
+    FieldRef(u16, u16),
+    MethodRef(u16, u16),
+    InterfaceMethodRef(u16, u16),
+    NameAndType(u16, u16),
+    MethodHandle(u8, u16),
+    MethodType(u16),
+    Dynamic(u16, u16),
+    InvokeDynamic(u16, u16),
+    Module(u16),
+    Package(u16),
+}
+
+impl ConstantKey {
+    fn new(constant: &ConstantInfo) -> Option<Self> {
+        Some(match constant {
+            ConstantInfo::Utf8(c) => ConstantKey::Utf8(c.bytes().into_owned()),
+            ConstantInfo::Integer(c) => ConstantKey::Integer(c.value),
+            ConstantInfo::Float(c) => ConstantKey::Float(c.value.to_bits()),
+            ConstantInfo::Long(c) => ConstantKey::Long(c.value),
+            ConstantInfo::Double(c) => ConstantKey::Double(c.value.to_bits()),
+            ConstantInfo::Class(c) => ConstantKey::Class(c.name_index),
+            ConstantInfo::String(c) => ConstantKey::String(c.string_index),
+            ConstantInfo::FieldRef(c) => {
+                ConstantKey::FieldRef(c.class_index, c.name_and_type_index)
+            }
+            ConstantInfo::MethodRef(c) => {
+                ConstantKey::MethodRef(c.class_index, c.name_and_type_index)
+            }
+            ConstantInfo::InterfaceMethodRef(c) => {
+                ConstantKey::InterfaceMethodRef(c.class_index, c.name_and_type_index)
+            }
+            ConstantInfo::NameAndType(c) => {
+                ConstantKey::NameAndType(c.name_index, c.descriptor_index)
+            }
+            ConstantInfo::MethodHandle(c) => {
+                ConstantKey::MethodHandle(c.reference_kind, c.reference_index)
+            }
+            ConstantInfo::MethodType(c) => ConstantKey::MethodType(c.descriptor_index),
+            ConstantInfo::Dynamic(c) => {
+                ConstantKey::Dynamic(c.bootstrap_method_attr_index, c.name_and_type_index)
+            }
+            ConstantInfo::InvokeDynamic(c) => {
+                ConstantKey::InvokeDynamic(c.bootstrap_method_attr_index, c.name_and_type_index)
+            }
+            ConstantInfo::Module(c) => ConstantKey::Module(c.name_index),
+            ConstantInfo::Package(c) => ConstantKey::Package(c.name_index),
+            ConstantInfo::Unusable => return None,
+        })
+    }
+}
+
+/// Builds a constant pool, reusing the existing entry when a constant is added twice and
+/// taking care of the unusable slot after each Long and Double.
+///
+/// A builder seeded from an existing pool keeps every original entry at its original index,
+/// so the rest of the class file stays valid; new constants are appended after them.
+///
+/// ```rust
+/// use classfile_parser::constant_info::ConstantPoolBuilder;
+///
+/// let mut class_file = classfile_parser::parse_class("./java-assets/compiled-classes/HelloWorld").unwrap();
+/// let mut builder = ConstantPoolBuilder::from_entries(&class_file.const_pool);
+///
+/// // HelloWorld already refers to System.out
+/// let out = builder.add_field_ref("java/lang/System", "out", "Ljava/io/PrintStream;").unwrap();
+/// assert_eq!(out, 7);
+///
+/// let greeting = builder.add_string("Hello again!").unwrap();
+/// assert_eq!(greeting as usize, class_file.const_pool.len() + 2);
+///
+/// builder.apply_to(&mut class_file);
+/// assert_eq!(class_file.constant_pool().get_string(greeting).unwrap(), "Hello again!");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConstantPoolBuilder {
+    entries: Vec<ConstantInfo>,
+    indexes: HashMap<ConstantKey, u16>,
+}
+
+impl ConstantPoolBuilder {
+    /// An empty constant pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A builder holding a copy of `entries`, such as a class file's `const_pool`. Where
+    /// the pool already holds duplicate constants, the first one is reused.
+    pub fn from_entries(entries: &[ConstantInfo]) -> Self {
+        let mut indexes = HashMap::new();
+        for (i, constant) in entries.iter().enumerate() {
+            if let Some(key) = ConstantKey::new(constant) {
+                indexes.entry(key).or_insert(i as u16 + 1);
+            }
+        }
+        ConstantPoolBuilder {
+            entries: entries.to_vec(),
+            indexes,
+        }
+    }
+
+    /// A view of the constants added so far.
+    pub fn pool(&self) -> ConstantPool<'_> {
+        ConstantPool::new(&self.entries)
+    }
+
+    /// The `const_pool_size` of a class file with this constant pool, one more than the
+    /// number of entries.
+    pub fn const_pool_size(&self) -> u16 {
+        self.entries.len() as u16 + 1
+    }
+
+    /// The entries of the constant pool, for a class file's `const_pool`.
+    pub fn build(self) -> Vec<ConstantInfo> {
+        self.entries
+    }
+
+    /// Replace the constant pool of `class_file`, keeping `const_pool_size` in step.
+    pub fn apply_to(self, class_file: &mut ClassFile) {
+        class_file.const_pool_size = self.const_pool_size();
+        class_file.const_pool = self.build();
+    }
+
+    /// Add `constant`, or find an equal one already in the pool, and return its index.
+    /// The indexes inside `constant` are taken as they are. `Unusable` slots are added
+    /// along with their Long or Double and cannot be added on their own.
+    pub fn add(&mut self, constant: ConstantInfo) -> Result<u16, ConstantPoolError> {
+        let Some(key) = ConstantKey::new(&constant) else {
+            return Err(ConstantPoolError::Unusable);
+        };
+        if let Some(&index) = self.indexes.get(&key) {
+            return Ok(index);
+        }
+        let wide = matches!(constant, ConstantInfo::Long(_) | ConstantInfo::Double(_));
+        let slots = if wide { 2 } else { 1 };
+        if self.entries.len() + slots > MAX_ENTRIES {
+            return Err(ConstantPoolError::Full);
+        }
+        self.entries.push(constant);
+        let index = self.entries.len() as u16;
+        if wide {
+            self.entries.push(ConstantInfo::Unusable);
+        }
+        self.indexes.insert(key, index);
+        Ok(index)
+    }
+
+    /// Overwrite the entry at `index`, which must hold a constant of the same width, so
+    /// that later additions of `constant` find it.
+    pub(crate) fn replace(&mut self, index: u16, constant: ConstantInfo) {
+        let slot = &mut self.entries[index as usize - 1];
+        if let Some(old) = ConstantKey::new(slot)
+            && self.indexes.get(&old) == Some(&index)
+        {
+            self.indexes.remove(&old);
+        }
+        if let Some(key) = ConstantKey::new(&constant) {
+            self.indexes.entry(key).or_insert(index);
+        }
+        *slot = constant;
+    }
+
+    pub fn add_utf8(&mut self, value: &str) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::Utf8(Utf8Constant::new(value)))
+    }
+
+    pub fn add_integer(&mut self, value: i32) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::Integer(IntegerConstant { value }))
+    }
+
+    pub fn add_float(&mut self, value: f32) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::Float(FloatConstant { value }))
+    }
+
+    /// Add a Long constant, which also takes up the following index.
+    pub fn add_long(&mut self, value: i64) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::Long(LongConstant { value }))
+    }
+
+    /// Add a Double constant, which also takes up the following index.
+    pub fn add_double(&mut self, value: f64) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::Double(DoubleConstant { value }))
+    }
+
+    /// Add a Class constant for an internal name such as `java/lang/String` or `[I`.
+    pub fn add_class(&mut self, name: &str) -> Result<u16, ConstantPoolError> {
+        let name_index = self.add_utf8(name)?;
+        self.add(ConstantInfo::Class(ClassConstant { name_index }))
+    }
+
+    pub fn add_string(&mut self, value: &str) -> Result<u16, ConstantPoolError> {
+        let string_index = self.add_utf8(value)?;
+        self.add(ConstantInfo::String(StringConstant { string_index }))
+    }
+
+    pub fn add_name_and_type(
+        &mut self,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let name_index = self.add_utf8(name)?;
+        let descriptor_index = self.add_utf8(descriptor)?;
+        self.add(ConstantInfo::NameAndType(NameAndTypeConstant {
+            name_index,
+            descriptor_index,
+        }))
+    }
+
+    /// The Class and NameAndType indexes of a member reference.
+    fn member(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<(u16, u16), ConstantPoolError> {
+        let class_index = self.add_class(class)?;
+        let name_and_type_index = self.add_name_and_type(name, descriptor)?;
+        Ok((class_index, name_and_type_index))
+    }
+
+    pub fn add_field_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let (class_index, name_and_type_index) = self.member(class, name, descriptor)?;
+        self.add(ConstantInfo::FieldRef(FieldRefConstant {
+            class_index,
+            name_and_type_index,
+        }))
+    }
+
+    pub fn add_method_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let (class_index, name_and_type_index) = self.member(class, name, descriptor)?;
+        self.add(ConstantInfo::MethodRef(MethodRefConstant {
+            class_index,
+            name_and_type_index,
+        }))
+    }
+
+    pub fn add_interface_method_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let (class_index, name_and_type_index) = self.member(class, name, descriptor)?;
+        self.add(ConstantInfo::InterfaceMethodRef(
+            InterfaceMethodRefConstant {
+                class_index,
+                name_and_type_index,
+            },
+        ))
+    }
+
+    /// Add a MethodHandle constant. `reference_kind` is one of the `REF_*` kinds from the
+    /// JVM specification, 1 (`REF_getField`) to 9 (`REF_invokeInterface`), and
+    /// `reference_index` the FieldRef, MethodRef or InterfaceMethodRef it refers to.
+    pub fn add_method_handle(
+        &mut self,
+        reference_kind: u8,
+        reference_index: u16,
+    ) -> Result<u16, ConstantPoolError> {
+        self.add(ConstantInfo::MethodHandle(MethodHandleConstant {
+            reference_kind,
+            reference_index,
+        }))
+    }
+
+    pub fn add_method_type(&mut self, descriptor: &str) -> Result<u16, ConstantPoolError> {
+        let descriptor_index = self.add_utf8(descriptor)?;
+        self.add(ConstantInfo::MethodType(MethodTypeConstant {
+            descriptor_index,
+        }))
+    }
+
+    /// Add a Dynamic constant computed by the bootstrap method at
+    /// `bootstrap_method_attr_index` in the BootstrapMethods attribute.
+    pub fn add_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let name_and_type_index = self.add_name_and_type(name, descriptor)?;
+        self.add(ConstantInfo::Dynamic(DynamicConstant {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }))
+    }
+
+    /// Add an InvokeDynamic constant linked by the bootstrap method at
+    /// `bootstrap_method_attr_index` in the BootstrapMethods attribute.
+    pub fn add_invoke_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<u16, ConstantPoolError> {
+        let name_and_type_index = self.add_name_and_type(name, descriptor)?;
+        self.add(ConstantInfo::InvokeDynamic(InvokeDynamicConstant {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }))
+    }
+
+    /// Add a Module constant for a module name such as `java.base`.
+    pub fn add_module(&mut self, name: &str) -> Result<u16, ConstantPoolError> {
+        let name_index = self.add_utf8(name)?;
+        self.add(ConstantInfo::Module(ModuleConstant { name_index }))
+    }
+
+    /// Add a Package constant for an internal package name such as `java/lang`.
+    pub fn add_package(&mut self, name: &str) -> Result<u16, ConstantPoolError> {
+        let name_index = self.add_utf8(name)?;
+        self.add(ConstantInfo::Package(PackageConstant { name_index }))
+    }
+}
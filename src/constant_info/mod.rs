@@ -1,5 +1,11 @@
+mod builder;
 mod parser;
+mod pool;
 mod types;
 
+pub use self::builder::ConstantPoolBuilder;
 pub use self::parser::constant_parser;
+pub(crate) use self::parser::{constant_pool_parser, single_constant_parser};
+pub(crate) use self::pool::wrong_kind;
+pub use self::pool::{ConstantPool, ConstantPoolError, MemberRef};
 pub use self::types::*;
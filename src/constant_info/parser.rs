@@ -1,4 +1,5 @@
 use crate::constant_info::*;
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
 use nom::{
     Err,
     bytes::complete::take,
@@ -7,17 +8,9 @@ use nom::{
     number::complete::{be_f32, be_f64, be_i32, be_i64, be_u8, be_u16},
 };
 
-fn utf8_constant(input: &[u8]) -> Utf8Constant {
-    let utf8_string =
-        cesu8::from_java_cesu8(input).unwrap_or_else(|_| String::from_utf8_lossy(input));
-    Utf8Constant {
-        utf8_string: utf8_string.to_string().into(),
-    }
-}
-
 fn const_utf8(input: &[u8]) -> ConstantInfoResult<'_> {
     let (input, length) = be_u16(input)?;
-    let (input, constant) = map(take(length), utf8_constant)(input)?;
+    let (input, constant) = map(take(length), Utf8Constant::from_raw)(input)?;
     Ok((input, ConstantInfo::Utf8(constant)))
 }
 
@@ -179,17 +172,32 @@ fn const_block_parser(input: &[u8], const_type: u8) -> ConstantInfoResult<'_> {
     }
 }
 
-fn single_constant_parser(input: &[u8]) -> ConstantInfoResult<'_> {
+pub(crate) fn single_constant_parser(input: &[u8]) -> ConstantInfoResult<'_> {
     let (input, const_type) = be_u8(input)?;
     let (input, const_block) = const_block_parser(input, const_type)?;
     Ok((input, const_block))
 }
 
 pub fn constant_parser(i: &[u8], const_pool_size: usize) -> ConstantInfoVecResult<'_> {
+    constant_pool_parser(i, const_pool_size).map_err(|e| {
+        Err::Error(error_position!(
+            &i[e.offset().unwrap_or(0)..],
+            ErrorKind::Alt
+        ))
+    })
+}
+
+/// Parse `const_pool_size` constant pool entries, reporting the index and tag of the entry
+/// that failed to parse. Offsets in the error are relative to `i`.
+pub(crate) fn constant_pool_parser(
+    i: &[u8],
+    const_pool_size: usize,
+) -> Result<(&[u8], Vec<ConstantInfo>), ClassParseError> {
     let mut index = 0;
     let mut input = i;
     let mut res = Vec::with_capacity(const_pool_size);
     while index < const_pool_size {
+        let context = ParseContext::Constant((index + 1) as u16);
         match single_constant_parser(input) {
             Ok((i, o)) => {
                 // Long and Double Entries have twice the size
@@ -205,8 +213,21 @@ pub fn constant_parser(i: &[u8], const_pool_size: usize) -> ConstantInfoVecResul
                 input = i;
                 index += 1;
             }
-            _ => return Result::Err(Err::Error(error_position!(input, ErrorKind::Alt))),
+            Err(e) => {
+                let offset = i.len() - input.len();
+                let error = match input.first() {
+                    Some(&tag) if !is_known_tag(tag) => {
+                        ClassParseError::malformed(offset, ParseErrorCause::UnknownConstantTag(tag))
+                    }
+                    _ => ClassParseError::from_nom(i, e),
+                };
+                return Result::Err(error.within(0, context));
+            }
         }
     }
     Ok((input, res))
 }
+
+fn is_known_tag(const_type: u8) -> bool {
+    matches!(const_type, 1 | 3..=12 | 15..=20)
+}
@@ -0,0 +1,215 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+use super::types::*;
+
+/// The largest number of entries a constant pool can have, since `const_pool_size` is one
+/// more than the number of entries and must fit in a u16.
+pub(crate) const MAX_ENTRIES: usize = u16::MAX as usize - 1;
+
+/// An error returned when a constant pool index cannot be resolved, or when a constant
+/// does not fit in the pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConstantPoolError {
+    /// The index is 0 or past the end of the constant pool.
+    OutOfRange(u16),
+    /// The entry at `index` is not of the expected kind.
+    WrongKind {
+        index: u16,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The constant pool has no room for another constant: a class file can have at most
+    /// 65534 entries, counting the two taken by each Long and Double.
+    Full,
+    /// An `Unusable` entry was added on its own rather than along with a Long or Double.
+    Unusable,
+}
+
+impl fmt::Display for ConstantPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstantPoolError::OutOfRange(index) => {
+                write!(f, "constant pool index {} is out of range", index)
+            }
+            ConstantPoolError::WrongKind {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "constant #{} is a {} constant, expected {}",
+                index, found, expected
+            ),
+            ConstantPoolError::Full => write!(
+                f,
+                "the constant pool is full, it can have at most {} entries",
+                MAX_ENTRIES
+            ),
+            ConstantPoolError::Unusable => write!(
+                f,
+                "an Unusable entry can only be added along with a Long or Double"
+            ),
+        }
+    }
+}
+
+impl Error for ConstantPoolError {}
+
+/// The class name, member name and descriptor of a resolved member reference.
+pub type MemberRef<'a> = (Cow<'a, str>, Cow<'a, str>, Cow<'a, str>);
+
+/// A borrowed view of a class file's constant pool with checked, 1-based accessors.
+///
+/// ```rust
+/// let classfile_bytes = include_bytes!("../../java-assets/compiled-classes/BasicClass.class");
+/// let class_file = classfile_parser::parse_class_from_bytes(classfile_bytes).unwrap();
+/// let pool = class_file.constant_pool();
+///
+/// assert_eq!(pool.get_class_name(class_file.this_class).unwrap(), "BasicClass");
+/// assert!(pool.get_utf8(0).is_err());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ConstantPool<'a> {
+    entries: &'a [ConstantInfo],
+}
+
+impl<'a> ConstantPool<'a> {
+    pub fn new(entries: &'a [ConstantInfo]) -> Self {
+        ConstantPool { entries }
+    }
+
+    /// The entries of the constant pool, where entry `i` has constant pool index `i + 1`.
+    pub fn entries(&self) -> &'a [ConstantInfo] {
+        self.entries
+    }
+
+    /// Iterate over `(index, constant)` pairs, skipping the unusable slots after Long and
+    /// Double constants.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &'a ConstantInfo)> + 'a {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !matches!(c, ConstantInfo::Unusable))
+            .map(|(i, c)| (i as u16 + 1, c))
+    }
+
+    pub fn get(&self, index: u16) -> Result<&'a ConstantInfo, ConstantPoolError> {
+        match index
+            .checked_sub(1)
+            .and_then(|i| self.entries.get(i as usize))
+        {
+            Some(ConstantInfo::Unusable) | None => Err(ConstantPoolError::OutOfRange(index)),
+            Some(constant) => Ok(constant),
+        }
+    }
+
+    pub fn get_utf8(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::Utf8(utf8) => Ok(utf8.to_str()),
+            other => Err(wrong_kind(index, "Utf8", other)),
+        }
+    }
+
+    /// The internal name of a Class constant, such as `java/lang/String` or `[I`.
+    pub fn get_class_name(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::Class(class) => self.get_utf8(class.name_index),
+            other => Err(wrong_kind(index, "Class", other)),
+        }
+    }
+
+    /// The value of a String constant.
+    pub fn get_string(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::String(string) => self.get_utf8(string.string_index),
+            other => Err(wrong_kind(index, "String", other)),
+        }
+    }
+
+    /// The name of a Module constant, such as `java.base`.
+    pub fn get_module_name(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::Module(module) => self.get_utf8(module.name_index),
+            other => Err(wrong_kind(index, "Module", other)),
+        }
+    }
+
+    /// The internal name of a Package constant, such as `java/lang`.
+    pub fn get_package_name(&self, index: u16) -> Result<Cow<'a, str>, ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::Package(package) => self.get_utf8(package.name_index),
+            other => Err(wrong_kind(index, "Package", other)),
+        }
+    }
+
+    /// The name and descriptor of a NameAndType constant.
+    pub fn get_name_and_type(
+        &self,
+        index: u16,
+    ) -> Result<(Cow<'a, str>, Cow<'a, str>), ConstantPoolError> {
+        match self.get(index)? {
+            ConstantInfo::NameAndType(nat) => Ok((
+                self.get_utf8(nat.name_index)?,
+                self.get_utf8(nat.descriptor_index)?,
+            )),
+            other => Err(wrong_kind(index, "NameAndType", other)),
+        }
+    }
+
+    /// The class name, member name and descriptor of a FieldRef, MethodRef or
+    /// InterfaceMethodRef constant.
+    pub fn resolve_member_ref(&self, index: u16) -> Result<MemberRef<'a>, ConstantPoolError> {
+        let (class_index, name_and_type_index) = match self.get(index)? {
+            ConstantInfo::FieldRef(r) => (r.class_index, r.name_and_type_index),
+            ConstantInfo::MethodRef(r) => (r.class_index, r.name_and_type_index),
+            ConstantInfo::InterfaceMethodRef(r) => (r.class_index, r.name_and_type_index),
+            other => {
+                return Err(wrong_kind(
+                    index,
+                    "FieldRef, MethodRef or InterfaceMethodRef",
+                    other,
+                ));
+            }
+        };
+        let class = self.get_class_name(class_index)?;
+        let (name, descriptor) = self.get_name_and_type(name_and_type_index)?;
+        Ok((class, name, descriptor))
+    }
+}
+
+pub(crate) fn wrong_kind(
+    index: u16,
+    expected: &'static str,
+    found: &ConstantInfo,
+) -> ConstantPoolError {
+    ConstantPoolError::WrongKind {
+        index,
+        expected,
+        found: kind_name(found),
+    }
+}
+
+fn kind_name(constant: &ConstantInfo) -> &'static str {
+    match constant {
+        ConstantInfo::Utf8(_) => "Utf8",
+        ConstantInfo::Integer(_) => "Integer",
+        ConstantInfo::Float(_) => "Float",
+        ConstantInfo::Long(_) => "Long",
+        ConstantInfo::Double(_) => "Double",
+        ConstantInfo::Class(_) => "Class",
+        ConstantInfo::String(_) => "String",
+        ConstantInfo::FieldRef(_) => "FieldRef",
+        ConstantInfo::MethodRef(_) => "MethodRef",
+        ConstantInfo::InterfaceMethodRef(_) => "InterfaceMethodRef",
+        ConstantInfo::NameAndType(_) => "NameAndType",
+        ConstantInfo::MethodHandle(_) => "MethodHandle",
+        ConstantInfo::MethodType(_) => "MethodType",
+        ConstantInfo::Dynamic(_) => "Dynamic",
+        ConstantInfo::InvokeDynamic(_) => "InvokeDynamic",
+        ConstantInfo::Module(_) => "Module",
+        ConstantInfo::Package(_) => "Package",
+        ConstantInfo::Unusable => "Unusable",
+    }
+}
@@ -1,33 +1,146 @@
-use binrw::{NullWideString, binrw};
+use std::borrow::Cow;
+use std::io::{Read, Seek, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, Endian, NullWideString, binrw};
 
 #[derive(Clone, Debug)]
 #[binrw]
 pub enum ConstantInfo {
+    #[brw(magic = 1u8)]
     Utf8(Utf8Constant),
+    #[brw(magic = 3u8)]
     Integer(IntegerConstant),
+    #[brw(magic = 4u8)]
     Float(FloatConstant),
+    #[brw(magic = 5u8)]
     Long(LongConstant),
+    #[brw(magic = 6u8)]
     Double(DoubleConstant),
+    #[brw(magic = 7u8)]
     Class(ClassConstant),
+    #[brw(magic = 8u8)]
     String(StringConstant),
+    #[brw(magic = 9u8)]
     FieldRef(FieldRefConstant),
+    #[brw(magic = 10u8)]
     MethodRef(MethodRefConstant),
+    #[brw(magic = 11u8)]
     InterfaceMethodRef(InterfaceMethodRefConstant),
+    #[brw(magic = 12u8)]
     NameAndType(NameAndTypeConstant),
+    #[brw(magic = 15u8)]
     MethodHandle(MethodHandleConstant),
+    #[brw(magic = 16u8)]
     MethodType(MethodTypeConstant),
+    #[brw(magic = 17u8)]
     Dynamic(DynamicConstant),
+    #[brw(magic = 18u8)]
     InvokeDynamic(InvokeDynamicConstant),
+    #[brw(magic = 19u8)]
     Module(ModuleConstant),
+    #[brw(magic = 20u8)]
     Package(PackageConstant),
+    /// The slot following a Long or Double constant, which takes up two entries in the
+    /// constant pool. It has no representation in the class file.
     Unusable,
 }
 
 #[derive(Clone, Debug)]
-#[binrw]
 pub struct Utf8Constant {
+    /// The modified UTF-8 bytes read from the class file, kept only when they are not the
+    /// encoding of `utf8_string` (such as byte sequences that had to be decoded lossily),
+    /// and written back as they are for as long as they still decode to `utf8_string`.
+    raw: Option<Vec<u8>>,
     pub utf8_string: NullWideString,
-    // pub bytes: Vec<u8>,
+}
+
+impl Utf8Constant {
+    /// Decode modified UTF-8 bytes, falling back to lossy UTF-8 decoding for byte sequences
+    /// that cannot be represented as a Rust string (such as unpaired surrogates).
+    pub(crate) fn decode(bytes: &[u8]) -> NullWideString {
+        cesu8::from_java_cesu8(bytes)
+            .unwrap_or_else(|_| String::from_utf8_lossy(bytes))
+            .to_string()
+            .into()
+    }
+
+    pub(crate) fn from_raw(raw: &[u8]) -> Self {
+        match cesu8::from_java_cesu8(raw) {
+            Ok(decoded) => Utf8Constant {
+                raw: (*cesu8::to_java_cesu8(&decoded) != *raw).then(|| raw.to_vec()),
+                utf8_string: decoded.as_ref().into(),
+            },
+            Err(_) => Utf8Constant {
+                raw: Some(raw.to_vec()),
+                utf8_string: String::from_utf8_lossy(raw).as_ref().into(),
+            },
+        }
+    }
+
+    /// Build a Utf8 constant from a string, encoding it as modified UTF-8.
+    pub fn new(s: &str) -> Self {
+        Utf8Constant {
+            raw: None,
+            utf8_string: s.into(),
+        }
+    }
+
+    /// The string.
+    pub fn to_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.utf8_string.to_string())
+    }
+
+    /// The modified UTF-8 encoding of `utf8_string`, as written to the class file.
+    pub fn bytes(&self) -> Cow<'_, [u8]> {
+        match &self.raw {
+            Some(raw) if Self::decode(raw) == self.utf8_string => Cow::Borrowed(raw),
+            _ => {
+                let string = self.utf8_string.to_string();
+                Cow::Owned(cesu8::to_java_cesu8(&string).into_owned())
+            }
+        }
+    }
+}
+
+impl BinRead for Utf8Constant {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let length = u16::read_options(reader, endian, args)?;
+        let mut raw = vec![0; length as usize];
+        reader.read_exact(&mut raw)?;
+        Ok(Utf8Constant::from_raw(&raw))
+    }
+}
+
+impl BinWrite for Utf8Constant {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let bytes = self.bytes();
+        let length = u16::try_from(bytes.len()).map_err(|_| binrw::Error::AssertFail {
+            pos: writer.stream_position().unwrap_or(0),
+            message: format!("Utf8 constant of {} bytes is too long", bytes.len()),
+        })?;
+        length.write_options(writer, endian, args)?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+impl From<&str> for Utf8Constant {
+    fn from(s: &str) -> Self {
+        Utf8Constant::new(s)
+    }
 }
 
 #[derive(Clone, Debug)]
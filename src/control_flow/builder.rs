@@ -0,0 +1,173 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::attribute_info::ExceptionEntry;
+use crate::code_attribute::{Branch, Instruction, instruction_encoder};
+
+use super::types::*;
+
+/// Where control can go after an instruction: the targets it jumps to, and whether it can
+/// also continue with the next instruction.
+pub(crate) fn flow(instruction: &Instruction, address: usize) -> (Vec<(i64, EdgeKind)>, bool) {
+    let target = |offset: i32| address as i64 + offset as i64;
+    match instruction {
+        Instruction::Tableswitch {
+            default, offsets, ..
+        } => {
+            let targets = std::iter::once(default)
+                .chain(offsets)
+                .map(|&offset| (target(offset), EdgeKind::Switch))
+                .collect();
+            (targets, false)
+        }
+        Instruction::Lookupswitch { default, pairs } => {
+            let targets = std::iter::once(*default)
+                .chain(pairs.iter().map(|&(_, offset)| offset))
+                .map(|offset| (target(offset), EdgeKind::Switch))
+                .collect();
+            (targets, false)
+        }
+        Instruction::Ireturn
+        | Instruction::Lreturn
+        | Instruction::Freturn
+        | Instruction::Dreturn
+        | Instruction::Areturn
+        | Instruction::Return
+        | Instruction::Athrow
+        | Instruction::Ret(_)
+        | Instruction::RetWide(_) => (Vec::new(), false),
+        _ => match Branch::from_instruction(instruction) {
+            Some((Branch::Goto, offset)) => (vec![(target(offset), EdgeKind::Branch)], false),
+            Some((Branch::Jsr, offset)) => (vec![(target(offset), EdgeKind::Subroutine)], true),
+            Some((_, offset)) => (vec![(target(offset), EdgeKind::Branch)], true),
+            None => (Vec::new(), true),
+        },
+    }
+}
+
+fn add_edge(blocks: &mut [BasicBlock], from: BlockId, to: BlockId, kind: EdgeKind) {
+    let edge = Edge { block: to, kind };
+    if !blocks[from].successors.contains(&edge) {
+        blocks[from].successors.push(edge);
+        blocks[to].predecessors.push(Edge { block: from, kind });
+    }
+}
+
+impl ControlFlowGraph {
+    /// Split decoded code, as returned by `CodeAttribute::instructions`, into basic blocks
+    /// linked by the branches, switches and exception handlers of the method.
+    ///
+    /// Every block covered by an exception table entry gets an `Exception` edge to the
+    /// handler. Blocks ending in a return, `athrow` or `ret` have no other successors.
+    pub fn new(
+        instructions: &[(usize, Instruction)],
+        exception_table: &[ExceptionEntry],
+    ) -> Result<Self, ControlFlowError> {
+        let Some((last_address, last)) = instructions.last() else {
+            return Ok(ControlFlowGraph::default());
+        };
+        let mut last_bytes = Vec::new();
+        instruction_encoder(last, *last_address, &mut last_bytes);
+        let code_length = last_address + last_bytes.len();
+
+        let addresses: BTreeSet<usize> = instructions.iter().map(|&(address, _)| address).collect();
+        let is_instruction =
+            |target: i64| usize::try_from(target).is_ok_and(|target| addresses.contains(&target));
+
+        // Collect the addresses that start a block
+        let mut leaders = BTreeSet::from([instructions[0].0]);
+        for (i, (address, instruction)) in instructions.iter().enumerate() {
+            let (targets, falls_through) = flow(instruction, *address);
+            for &(target, _) in &targets {
+                if !is_instruction(target) {
+                    return Err(ControlFlowError::InvalidBranchTarget {
+                        address: *address,
+                        target,
+                    });
+                }
+                leaders.insert(target as usize);
+            }
+            if (!targets.is_empty() || !falls_through)
+                && let Some((next, _)) = instructions.get(i + 1)
+            {
+                leaders.insert(*next);
+            }
+        }
+        for (index, entry) in exception_table.iter().enumerate() {
+            let (start, end, handler) = (
+                entry.start_pc as usize,
+                entry.end_pc as usize,
+                entry.handler_pc as usize,
+            );
+            let end_ok = end == code_length || addresses.contains(&end);
+            if start >= end
+                || !addresses.contains(&start)
+                || !end_ok
+                || !addresses.contains(&handler)
+            {
+                return Err(ControlFlowError::InvalidExceptionEntry(index));
+            }
+            leaders.insert(start);
+            leaders.insert(handler);
+            if end < code_length {
+                leaders.insert(end);
+            }
+        }
+
+        // Split the instructions at the leaders
+        let mut blocks: Vec<BasicBlock> = Vec::with_capacity(leaders.len());
+        let mut block_ids = HashMap::with_capacity(leaders.len());
+        for (address, instruction) in instructions {
+            if leaders.contains(address) {
+                if let Some(block) = blocks.last_mut() {
+                    block.end = *address;
+                }
+                block_ids.insert(*address, blocks.len());
+                blocks.push(BasicBlock {
+                    start: *address,
+                    end: code_length,
+                    instructions: Vec::new(),
+                    successors: Vec::new(),
+                    predecessors: Vec::new(),
+                });
+            }
+            blocks
+                .last_mut()
+                .unwrap()
+                .instructions
+                .push((*address, instruction.clone()));
+        }
+
+        // Link the blocks by the flow of the last instruction of each
+        for id in 0..blocks.len() {
+            let (address, instruction) = blocks[id].instructions.last().unwrap();
+            let (targets, falls_through) = flow(instruction, *address);
+            for (target, kind) in targets {
+                add_edge(&mut blocks, id, block_ids[&(target as usize)], kind);
+            }
+            if falls_through && id + 1 < blocks.len() {
+                add_edge(&mut blocks, id, id + 1, EdgeKind::Fallthrough);
+            }
+        }
+        for entry in exception_table {
+            let handler = block_ids[&(entry.handler_pc as usize)];
+            let covered = blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| {
+                    block.start >= entry.start_pc as usize && block.start < entry.end_pc as usize
+                })
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            for id in covered {
+                add_edge(
+                    &mut blocks,
+                    id,
+                    handler,
+                    EdgeKind::Exception(entry.catch_type),
+                );
+            }
+        }
+
+        Ok(ControlFlowGraph { blocks })
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Write;
+
+use crate::disassembler::mnemonic;
+
+use super::types::*;
+
+impl ControlFlowGraph {
+    /// Render the graph in Graphviz DOT format, with one box per block listing the address
+    /// and mnemonic of each instruction. Branches and switches are drawn bold, `jsr` dotted
+    /// and exception edges dashed and labelled with the caught class index.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph cfg {\n");
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        for (id, block) in self.blocks.iter().enumerate() {
+            let mut label = String::new();
+            for (address, instruction) in &block.instructions {
+                let _ = write!(label, "{}: {}\\l", address, mnemonic(instruction, *address));
+            }
+            let _ = writeln!(dot, "    b{} [label=\"{}\"];", id, label);
+        }
+        for (id, block) in self.blocks.iter().enumerate() {
+            for edge in &block.successors {
+                let style = match edge.kind {
+                    EdgeKind::Fallthrough => String::new(),
+                    EdgeKind::Branch | EdgeKind::Switch => " [style=bold]".to_string(),
+                    EdgeKind::Subroutine => " [style=dotted]".to_string(),
+                    EdgeKind::Exception(0) => " [style=dashed, label=\"any\"]".to_string(),
+                    EdgeKind::Exception(catch_type) => {
+                        format!(" [style=dashed, label=\"#{}\"]", catch_type)
+                    }
+                };
+                let _ = writeln!(dot, "    b{} -> b{}{};", id, edge.block, style);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,9 @@
+//! Basic blocks and control-flow edges of a method's bytecode.
+
+mod builder;
+mod dot;
+mod types;
+
+pub use self::types::*;
+
+pub(crate) use self::builder::flow;
@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::code_attribute::Instruction;
+
+/// The index of a block in `ControlFlowGraph::blocks`.
+pub type BlockId = usize;
+
+/// How control passes from one block to another.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum EdgeKind {
+    /// Execution continues with the next instruction, either because the block ends at a
+    /// branch target or a conditional branch is not taken.
+    Fallthrough,
+    /// A `goto` or a taken conditional branch.
+    Branch,
+    /// A case or the default of a `tableswitch` or `lookupswitch`.
+    Switch,
+    /// A `jsr` to a subroutine. The `ret` that ends the subroutine returns to the instruction
+    /// after the `jsr`, which is reached by a `Fallthrough` edge.
+    Subroutine,
+    /// An exception raised in the block is caught by a handler, with the constant pool index
+    /// of the caught class or 0 for a handler that catches everything.
+    Exception(u16),
+}
+
+/// One end of an edge, as seen from the block holding it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Edge {
+    pub block: BlockId,
+    pub kind: EdgeKind,
+}
+
+/// A straight-line run of instructions, entered only at the first and left only after the
+/// last one (or by an exception).
+#[derive(Clone, Debug)]
+pub struct BasicBlock {
+    /// The address of the first instruction.
+    pub start: usize,
+    /// The address just past the last instruction.
+    pub end: usize,
+    pub instructions: Vec<(usize, Instruction)>,
+    /// The blocks control can pass to from this one.
+    pub successors: Vec<Edge>,
+    /// The blocks control can reach this one from.
+    pub predecessors: Vec<Edge>,
+}
+
+impl BasicBlock {
+    /// The instruction that ends the block.
+    pub fn last_instruction(&self) -> &Instruction {
+        &self.instructions[self.instructions.len() - 1].1
+    }
+}
+
+/// The basic blocks of a method, ordered by address, with the entry block first.
+///
+/// ```rust
+/// use classfile_parser::attribute_info::TypedAttribute;
+/// use classfile_parser::control_flow::{ControlFlowGraph, EdgeKind};
+///
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+/// let method = &class_file.methods[1];
+/// let code = method
+///     .typed_attributes(&class_file.const_pool)
+///     .unwrap()
+///     .into_iter()
+///     .find_map(|attribute| match attribute {
+///         TypedAttribute::Code(code) => Some(code),
+///         _ => None,
+///     })
+///     .unwrap();
+///
+/// // return i < 1 ? 1 : i * factorial(i - 1);
+/// let cfg = ControlFlowGraph::new(&code.instructions().unwrap(), &code.exception_table).unwrap();
+/// assert_eq!(cfg.blocks.len(), 4);
+/// let kinds: Vec<_> = cfg.blocks[0].successors.iter().map(|edge| edge.kind).collect();
+/// assert_eq!(kinds, [EdgeKind::Branch, EdgeKind::Fallthrough]);
+/// assert!(cfg.to_dot().starts_with("digraph cfg {"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    /// The block containing the instruction at `address`.
+    pub fn block_at(&self, address: usize) -> Option<BlockId> {
+        let id = self
+            .blocks
+            .partition_point(|block| block.start <= address)
+            .checked_sub(1)?;
+        (address < self.blocks[id].end).then_some(id)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ControlFlowError {
+    /// A branch or switch at `address` jumps to `target`, which is not an instruction.
+    InvalidBranchTarget { address: usize, target: i64 },
+    /// An exception table entry, by its position in the table, covers a range or names a
+    /// handler that does not fall on instruction boundaries.
+    InvalidExceptionEntry(usize),
+}
+
+impl fmt::Display for ControlFlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlFlowError::InvalidBranchTarget { address, target } => write!(
+                f,
+                "branch at {} targets {}, which is not an instruction",
+                address, target
+            ),
+            ControlFlowError::InvalidExceptionEntry(index) => write!(
+                f,
+                "exception table entry {} does not fall on instruction boundaries",
+                index
+            ),
+        }
+    }
+}
+
+impl Error for ControlFlowError {}
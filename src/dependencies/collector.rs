@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+
+use crate::attribute_info::{
+    AttributeInfo, ElementValue, RuntimeAnnotation, TypedAttribute, typed_attributes_parser,
+};
+use crate::constant_info::{ConstantInfo, ConstantPool};
+use crate::descriptor::{FieldType, MethodDescriptor, ReturnType};
+use crate::error::ClassParseError;
+use crate::field_info::FieldAccessFlags;
+use crate::method_info::MethodAccessFlags;
+use crate::signature::{
+    ClassSignature, ClassTypeSignature, FieldSignature, JavaTypeSignature, MethodSignature,
+    ReferenceTypeSignature, ResultSignature, TypeArgument, TypeParameter,
+};
+use crate::types::ClassFile;
+
+use super::types::DependencyKind;
+
+/// Which kind of generic signature a Signature attribute holds.
+#[derive(Copy, Clone)]
+enum SignatureKind {
+    Class,
+    Method,
+    Field,
+}
+
+struct Collector<'a> {
+    pool: ConstantPool<'a>,
+    const_pool: &'a [ConstantInfo],
+    this_class: String,
+    classes: BTreeMap<String, DependencyKind>,
+}
+
+impl Collector<'_> {
+    /// Record a class by internal name or array descriptor.
+    fn add(&mut self, name: &str, kind: DependencyKind) -> Result<(), ClassParseError> {
+        if name.starts_with('[') {
+            return self.add_field_type(&name.parse()?, kind);
+        }
+        if name == self.this_class {
+            return Ok(());
+        }
+        match kind {
+            DependencyKind::Api => {
+                self.classes.insert(name.to_string(), kind);
+            }
+            DependencyKind::Implementation => {
+                self.classes.entry(name.to_string()).or_insert(kind);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_class_constant(
+        &mut self,
+        index: u16,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        let name = self.pool.get_class_name(index)?;
+        self.add(&name, kind)
+    }
+
+    fn add_field_type(
+        &mut self,
+        field_type: &FieldType,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        match field_type {
+            FieldType::Base(_) => Ok(()),
+            FieldType::Object(name) => self.add(name, kind),
+            FieldType::Array { element, .. } => self.add_field_type(element, kind),
+        }
+    }
+
+    /// Record the classes in the field or method descriptor at `index`.
+    fn add_descriptor(&mut self, index: u16, kind: DependencyKind) -> Result<(), ClassParseError> {
+        let descriptor = self.pool.get_utf8(index)?;
+        if descriptor.starts_with('(') {
+            let descriptor: MethodDescriptor = descriptor.parse()?;
+            for param in &descriptor.params {
+                self.add_field_type(param, kind)?;
+            }
+            if let ReturnType::Type(return_type) = &descriptor.return_type {
+                self.add_field_type(return_type, kind)?;
+            }
+            Ok(())
+        } else {
+            self.add_field_type(&descriptor.parse()?, kind)
+        }
+    }
+
+    fn add_class_type_signature(
+        &mut self,
+        signature: &ClassTypeSignature,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        let mut name = signature.package.clone();
+        if !name.is_empty() {
+            name.push('/');
+        }
+        name.push_str(&signature.class.name);
+        for inner in &signature.suffix {
+            name.push('$');
+            name.push_str(&inner.name);
+        }
+        self.add(&name, kind)?;
+        for class in std::iter::once(&signature.class).chain(&signature.suffix) {
+            for argument in &class.type_arguments {
+                match argument {
+                    TypeArgument::Any => {}
+                    TypeArgument::Exact(reference)
+                    | TypeArgument::Extends(reference)
+                    | TypeArgument::Super(reference) => {
+                        self.add_reference_signature(reference, kind)?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_reference_signature(
+        &mut self,
+        signature: &ReferenceTypeSignature,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        match signature {
+            ReferenceTypeSignature::Class(class) => self.add_class_type_signature(class, kind),
+            ReferenceTypeSignature::TypeVariable(_) => Ok(()),
+            ReferenceTypeSignature::Array(component) => self.add_type_signature(component, kind),
+        }
+    }
+
+    fn add_type_signature(
+        &mut self,
+        signature: &JavaTypeSignature,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        match signature {
+            JavaTypeSignature::Base(_) => Ok(()),
+            JavaTypeSignature::Reference(reference) => {
+                self.add_reference_signature(reference, kind)
+            }
+        }
+    }
+
+    fn add_type_parameters(
+        &mut self,
+        type_parameters: &[TypeParameter],
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        for type_parameter in type_parameters {
+            for bound in type_parameter
+                .class_bound
+                .iter()
+                .chain(&type_parameter.interface_bounds)
+            {
+                self.add_reference_signature(bound, kind)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the classes in the generic signature at `index`.
+    fn add_signature(
+        &mut self,
+        index: u16,
+        signature_kind: SignatureKind,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        let signature = self.pool.get_utf8(index)?;
+        match signature_kind {
+            SignatureKind::Class => {
+                let signature: ClassSignature = signature.parse()?;
+                self.add_type_parameters(&signature.type_parameters, kind)?;
+                self.add_class_type_signature(&signature.superclass, kind)?;
+                for interface in &signature.interfaces {
+                    self.add_class_type_signature(interface, kind)?;
+                }
+            }
+            SignatureKind::Method => {
+                let signature: MethodSignature = signature.parse()?;
+                self.add_type_parameters(&signature.type_parameters, kind)?;
+                for param in &signature.params {
+                    self.add_type_signature(param, kind)?;
+                }
+                if let ResultSignature::Type(result) = &signature.result {
+                    self.add_type_signature(result, kind)?;
+                }
+                for thrown in &signature.throws {
+                    self.add_reference_signature(thrown, kind)?;
+                }
+            }
+            SignatureKind::Field => {
+                let signature: FieldSignature = signature.parse()?;
+                self.add_reference_signature(&signature.0, kind)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_annotation(
+        &mut self,
+        annotation: &RuntimeAnnotation,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        self.add_descriptor(annotation.type_index, kind)?;
+        for pair in &annotation.element_value_pairs {
+            self.add_element_value(&pair.value, kind)?;
+        }
+        Ok(())
+    }
+
+    fn add_element_value(
+        &mut self,
+        value: &ElementValue,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        match value {
+            ElementValue::ConstValueIndex { .. } => Ok(()),
+            ElementValue::EnumConst(value) => self.add_descriptor(value.type_name_index, kind),
+            // A return descriptor, which may be `V` for `void.class`
+            ElementValue::ClassInfoIndex(index) => match self.pool.get_utf8(*index)?.as_ref() {
+                "V" => Ok(()),
+                _ => self.add_descriptor(*index, kind),
+            },
+            ElementValue::AnnotationValue(annotation) => self.add_annotation(annotation, kind),
+            ElementValue::ElementArray(array) => {
+                for value in &array.values {
+                    self.add_element_value(value, kind)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Record the classes named in `attributes` other than through Class constants, which
+    /// are all recorded by `add_constant_pool`.
+    fn add_attributes(
+        &mut self,
+        attributes: &[AttributeInfo],
+        signature_kind: SignatureKind,
+        kind: DependencyKind,
+    ) -> Result<(), ClassParseError> {
+        use TypedAttribute::*;
+        let attributes = typed_attributes_parser(attributes, self.const_pool)?;
+        for attribute in attributes {
+            match attribute {
+                Signature(signature) => {
+                    self.add_signature(signature.signature_index, signature_kind, kind)?
+                }
+                Exceptions(exceptions) => {
+                    for index in exceptions.exception_table {
+                        self.add_class_constant(index, kind)?;
+                    }
+                }
+                PermittedSubclasses(permitted) => {
+                    for index in permitted.classes {
+                        self.add_class_constant(index, kind)?;
+                    }
+                }
+                RuntimeVisibleAnnotations(attribute) => {
+                    for annotation in &attribute.annotations {
+                        self.add_annotation(annotation, kind)?;
+                    }
+                }
+                RuntimeInvisibleAnnotations(attribute) => {
+                    for annotation in &attribute.annotations {
+                        self.add_annotation(annotation, kind)?;
+                    }
+                }
+                RuntimeVisibleParameterAnnotations(attribute) => {
+                    for parameter in &attribute.parameter_annotations {
+                        for annotation in &parameter.annotations {
+                            self.add_annotation(annotation, kind)?;
+                        }
+                    }
+                }
+                RuntimeInvisibleParameterAnnotations(attribute) => {
+                    for parameter in &attribute.parameter_annotations {
+                        for annotation in &parameter.annotations {
+                            self.add_annotation(annotation, kind)?;
+                        }
+                    }
+                }
+                RuntimeVisibleTypeAnnotations(attribute) => {
+                    for annotation in &attribute.type_annotations {
+                        self.add_descriptor(annotation.type_index, kind)?;
+                        for pair in &annotation.element_value_pairs {
+                            self.add_element_value(&pair.value, kind)?;
+                        }
+                    }
+                }
+                RuntimeInvisibleTypeAnnotations(attribute) => {
+                    for annotation in &attribute.type_annotations {
+                        self.add_descriptor(annotation.type_index, kind)?;
+                        for pair in &annotation.element_value_pairs {
+                            self.add_element_value(&pair.value, kind)?;
+                        }
+                    }
+                }
+                AnnotationDefault(value) => self.add_element_value(&value, kind)?,
+                Record(record) => {
+                    for component in &record.components {
+                        self.add_descriptor(component.descriptor_index, kind)?;
+                        self.add_attributes(&component.attributes, SignatureKind::Field, kind)?;
+                    }
+                }
+                LocalVariableTable(table) => {
+                    for item in &table.items {
+                        self.add_descriptor(item.descriptor_index, kind)?;
+                    }
+                }
+                LocalVariableTypeTable(table) => {
+                    for item in &table.local_variable_type_table {
+                        self.add_signature(item.signature_index, SignatureKind::Field, kind)?;
+                    }
+                }
+                Code(code) => self.add_attributes(
+                    &code.attributes,
+                    signature_kind,
+                    DependencyKind::Implementation,
+                )?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Record every class named by a Class constant, and the classes in the descriptors
+    /// of the fields, methods and method types the bytecode refers to.
+    fn add_constant_pool(&mut self) -> Result<(), ClassParseError> {
+        let kind = DependencyKind::Implementation;
+        for (index, constant) in self.pool.iter() {
+            match constant {
+                ConstantInfo::Class(_) => self.add_class_constant(index, kind)?,
+                ConstantInfo::NameAndType(name_and_type) => {
+                    self.add_descriptor(name_and_type.descriptor_index, kind)?
+                }
+                ConstantInfo::MethodType(method_type) => {
+                    self.add_descriptor(method_type.descriptor_index, kind)?
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn referenced_classes(
+    class_file: &ClassFile,
+) -> Result<BTreeMap<String, DependencyKind>, ClassParseError> {
+    let mut collector = Collector {
+        pool: class_file.constant_pool(),
+        const_pool: &class_file.const_pool,
+        this_class: class_file.this_class_name()?.into_owned(),
+        classes: BTreeMap::new(),
+    };
+    let api = DependencyKind::Api;
+    if let Some(super_class) = class_file.super_class_name()? {
+        collector.add(&super_class, api)?;
+    }
+    for interface in class_file.interface_names()? {
+        collector.add(&interface, api)?;
+    }
+    collector.add_attributes(&class_file.attributes, SignatureKind::Class, api)?;
+
+    for field in &class_file.fields {
+        let kind = if field.access_flags.contains(FieldAccessFlags::PRIVATE) {
+            DependencyKind::Implementation
+        } else {
+            api
+        };
+        collector.add_descriptor(field.descriptor_index, kind)?;
+        collector.add_attributes(&field.attributes, SignatureKind::Field, kind)?;
+    }
+    for method in &class_file.methods {
+        let kind = if method.access_flags.contains(MethodAccessFlags::PRIVATE) {
+            DependencyKind::Implementation
+        } else {
+            api
+        };
+        collector.add_descriptor(method.descriptor_index, kind)?;
+        collector.add_attributes(&method.attributes, SignatureKind::Method, kind)?;
+    }
+
+    collector.add_constant_pool()?;
+    Ok(collector.classes)
+}
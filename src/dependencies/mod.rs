@@ -0,0 +1,8 @@
+//! The classes a class file depends on, for build tools that prune dependencies.
+
+mod collector;
+mod types;
+
+pub use self::types::*;
+
+pub(crate) use self::collector::referenced_classes;
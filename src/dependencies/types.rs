@@ -0,0 +1,11 @@
+/// How a class is depended on.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DependencyKind {
+    /// The class is part of the depending class's API: its superclass, an interface, or a
+    /// type in the signature, throws clause or annotations of the class or of a non-private
+    /// field, method or record component. Changing it can break code compiled against the
+    /// depending class.
+    Api,
+    /// The class is only used by private members or by the bytecode.
+    Implementation,
+}
@@ -0,0 +1,6 @@
+mod parser;
+mod types;
+
+pub(crate) use self::parser::base_type_parser;
+pub use self::parser::{field_type_parser, method_descriptor_parser};
+pub use self::types::*;
@@ -0,0 +1,101 @@
+use nom::{
+    Err as BaseErr,
+    bytes::complete::{tag, take_till1},
+    character::complete::{anychar, char},
+    error::{Error, ErrorKind},
+    multi::{many0, many0_count},
+    sequence::{delimited, terminated},
+};
+
+use crate::descriptor::types::*;
+
+// Using a type alias here evades a Clippy warning about complex types.
+type Err<E> = BaseErr<Error<E>>;
+
+pub(crate) fn base_type_parser(input: &str) -> Result<(&str, BaseType), Err<&str>> {
+    let (rest, c) = anychar(input)?;
+    let base_type = match c {
+        'B' => BaseType::Byte,
+        'C' => BaseType::Char,
+        'D' => BaseType::Double,
+        'F' => BaseType::Float,
+        'I' => BaseType::Int,
+        'J' => BaseType::Long,
+        'S' => BaseType::Short,
+        'Z' => BaseType::Boolean,
+        _ => return Err(BaseErr::Error(Error::new(input, ErrorKind::Tag))),
+    };
+    Ok((rest, base_type))
+}
+
+fn element_type_parser(input: &str) -> Result<(&str, FieldType), Err<&str>> {
+    if input.starts_with('L') {
+        let (input, class_name) = delimited(
+            char('L'),
+            take_till1(|c| c == ';' || c == '[' || c == '.'),
+            char(';'),
+        )(input)?;
+        Ok((input, FieldType::Object(class_name.to_string())))
+    } else {
+        let (input, base_type) = base_type_parser(input)?;
+        Ok((input, FieldType::Base(base_type)))
+    }
+}
+
+/// Parse a field descriptor such as `I`, `Ljava/lang/String;` or `[[J`.
+pub fn field_type_parser(input: &str) -> Result<(&str, FieldType), Err<&str>> {
+    let (rest, dimensions) = many0_count(char('['))(input)?;
+    if dimensions > 255 {
+        return Err(BaseErr::Error(Error::new(input, ErrorKind::TooLarge)));
+    }
+    let (rest, element) = element_type_parser(rest)?;
+    if dimensions == 0 {
+        Ok((rest, element))
+    } else {
+        Ok((
+            rest,
+            FieldType::Array {
+                dimensions: dimensions as u8,
+                element: Box::new(element),
+            },
+        ))
+    }
+}
+
+fn return_type_parser(input: &str) -> Result<(&str, ReturnType), Err<&str>> {
+    if let Ok((rest, _)) = tag::<_, _, Error<&str>>("V")(input) {
+        return Ok((rest, ReturnType::Void));
+    }
+    let (rest, field_type) = field_type_parser(input)?;
+    Ok((rest, ReturnType::Type(field_type)))
+}
+
+/// Parse a method descriptor such as `(ILjava/lang/String;[J)V`.
+pub fn method_descriptor_parser(input: &str) -> Result<(&str, MethodDescriptor), Err<&str>> {
+    let (input, params) = delimited(char('('), many0(field_type_parser), char(')'))(input)?;
+    let (input, return_type) = return_type_parser(input)?;
+    Ok((
+        input,
+        MethodDescriptor {
+            params,
+            return_type,
+        },
+    ))
+}
+
+/// Run `parser` over the whole of `descriptor`, reporting the offset of the first byte that
+/// could not be consumed.
+pub(crate) fn parse_complete<T>(
+    descriptor: &str,
+    parser: impl Fn(&str) -> Result<(&str, T), Err<&str>>,
+) -> Result<T, DescriptorError> {
+    let error = |rest: &str| DescriptorError {
+        descriptor: descriptor.to_string(),
+        offset: descriptor.len() - rest.len(),
+    };
+    match terminated(parser, nom::combinator::eof)(descriptor) {
+        Ok((_, value)) => Ok(value),
+        Err(BaseErr::Error(e)) | Err(BaseErr::Failure(e)) => Err(error(e.input)),
+        Err(BaseErr::Incomplete(_)) => Err(error("")),
+    }
+}
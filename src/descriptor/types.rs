@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::descriptor::parser::{field_type_parser, method_descriptor_parser, parse_complete};
+
+/// A primitive type in a descriptor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum BaseType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+}
+
+impl BaseType {
+    /// The descriptor character of this type, such as `I` for `int`.
+    pub fn descriptor_char(self) -> char {
+        match self {
+            BaseType::Byte => 'B',
+            BaseType::Char => 'C',
+            BaseType::Double => 'D',
+            BaseType::Float => 'F',
+            BaseType::Int => 'I',
+            BaseType::Long => 'J',
+            BaseType::Short => 'S',
+            BaseType::Boolean => 'Z',
+        }
+    }
+
+    /// The Java keyword for this type, such as `int`.
+    pub fn java_name(self) -> &'static str {
+        match self {
+            BaseType::Byte => "byte",
+            BaseType::Char => "char",
+            BaseType::Double => "double",
+            BaseType::Float => "float",
+            BaseType::Int => "int",
+            BaseType::Long => "long",
+            BaseType::Short => "short",
+            BaseType::Boolean => "boolean",
+        }
+    }
+}
+
+/// The type of a field, parameter or local variable, as described by a field descriptor.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FieldType {
+    Base(BaseType),
+    /// A class or interface, by its internal name such as `java/lang/String`.
+    Object(String),
+    /// An array type. `element` is never itself an array.
+    Array {
+        dimensions: u8,
+        element: Box<FieldType>,
+    },
+}
+
+impl FieldType {
+    /// The number of local variable or operand stack slots a value of this type takes up.
+    pub fn slot_size(&self) -> u16 {
+        match self {
+            FieldType::Base(BaseType::Long) | FieldType::Base(BaseType::Double) => 2,
+            _ => 1,
+        }
+    }
+
+    /// The type as it would be written in Java source, such as `java.lang.String[]`.
+    pub fn to_java(&self) -> String {
+        match self {
+            FieldType::Base(base_type) => base_type.java_name().to_string(),
+            FieldType::Object(class_name) => class_name.replace('/', "."),
+            FieldType::Array {
+                dimensions,
+                element,
+            } => element.to_java() + &"[]".repeat(*dimensions as usize),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    /// Format the type as a field descriptor.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::Base(base_type) => write!(f, "{}", base_type.descriptor_char()),
+            FieldType::Object(class_name) => write!(f, "L{};", class_name),
+            FieldType::Array {
+                dimensions,
+                element,
+            } => write!(f, "{}{}", "[".repeat(*dimensions as usize), element),
+        }
+    }
+}
+
+impl FromStr for FieldType {
+    type Err = DescriptorError;
+
+    fn from_str(descriptor: &str) -> Result<Self, Self::Err> {
+        parse_complete(descriptor, field_type_parser)
+    }
+}
+
+/// The return type of a method descriptor.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ReturnType {
+    Void,
+    Type(FieldType),
+}
+
+impl ReturnType {
+    /// The number of operand stack slots the returned value takes up.
+    pub fn slot_size(&self) -> u16 {
+        match self {
+            ReturnType::Void => 0,
+            ReturnType::Type(field_type) => field_type.slot_size(),
+        }
+    }
+
+    /// The type as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        match self {
+            ReturnType::Void => "void".to_string(),
+            ReturnType::Type(field_type) => field_type.to_java(),
+        }
+    }
+}
+
+impl fmt::Display for ReturnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReturnType::Void => write!(f, "V"),
+            ReturnType::Type(field_type) => write!(f, "{}", field_type),
+        }
+    }
+}
+
+/// A parsed method descriptor such as `(ILjava/lang/String;[J)V`.
+///
+/// ```rust
+/// use classfile_parser::descriptor::MethodDescriptor;
+///
+/// let descriptor: MethodDescriptor = "(ILjava/lang/String;[J)V".parse().unwrap();
+/// assert_eq!(descriptor.params.len(), 3);
+/// assert_eq!(descriptor.param_slots(), 3);
+/// assert_eq!(
+///     descriptor.to_java("run"),
+///     "void run(int, java.lang.String, long[])"
+/// );
+/// assert_eq!(descriptor.to_string(), "(ILjava/lang/String;[J)V");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MethodDescriptor {
+    pub params: Vec<FieldType>,
+    pub return_type: ReturnType,
+}
+
+impl MethodDescriptor {
+    /// The number of local variable slots taken by the parameters, not counting `this`.
+    pub fn param_slots(&self) -> u16 {
+        self.params.iter().map(FieldType::slot_size).sum()
+    }
+
+    /// The method signature as it would be written in Java source, using `name` as the
+    /// method name.
+    pub fn to_java(&self, name: &str) -> String {
+        let params: Vec<_> = self.params.iter().map(FieldType::to_java).collect();
+        format!(
+            "{} {}({})",
+            self.return_type.to_java(),
+            name,
+            params.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for MethodDescriptor {
+    /// Format as a method descriptor.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for param in &self.params {
+            write!(f, "{}", param)?;
+        }
+        write!(f, "){}", self.return_type)
+    }
+}
+
+impl FromStr for MethodDescriptor {
+    type Err = DescriptorError;
+
+    fn from_str(descriptor: &str) -> Result<Self, Self::Err> {
+        parse_complete(descriptor, method_descriptor_parser)
+    }
+}
+
+/// An error returned when a descriptor string is malformed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DescriptorError {
+    /// The descriptor that failed to parse.
+    pub descriptor: String,
+    /// The byte offset within `descriptor` where parsing failed.
+    pub offset: usize,
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid descriptor {:?} at offset {}",
+            self.descriptor, self.offset
+        )
+    }
+}
+
+impl Error for DescriptorError {}
@@ -0,0 +1,792 @@
+use crate::attribute_info::{
+    AttributeInfo, BootstrapMethodsAttribute, ElementValue, InnerClassAccessFlags,
+    InnerClassesAttribute, ModuleAttribute, RecordAttribute, RuntimeAnnotation, TargetInfo,
+    TypeAnnotation, TypedAttribute, typed_attribute_parser,
+};
+use crate::descriptor::FieldType;
+use crate::disassembler::constants::{escape, java_double, java_float};
+use crate::disassembler::{ClassWriter, INNER_CLASS_MODIFIERS, java_name, modifiers};
+
+const ACC_OPEN: u16 = 0x0020;
+const ACC_TRANSITIVE: u16 = 0x0020;
+const ACC_STATIC_PHASE: u16 = 0x0040;
+const ACC_SYNTHETIC: u16 = 0x1000;
+const ACC_MANDATED: u16 = 0x8000;
+
+fn target_type_name(target_type: u8) -> String {
+    match target_type {
+        0x00 => "CLASS_TYPE_PARAMETER".to_string(),
+        0x01 => "METHOD_TYPE_PARAMETER".to_string(),
+        0x10 => "CLASS_EXTENDS".to_string(),
+        0x11 => "CLASS_TYPE_PARAMETER_BOUND".to_string(),
+        0x12 => "METHOD_TYPE_PARAMETER_BOUND".to_string(),
+        0x13 => "FIELD".to_string(),
+        0x14 => "METHOD_RETURN".to_string(),
+        0x15 => "METHOD_RECEIVER".to_string(),
+        0x16 => "METHOD_FORMAL_PARAMETER".to_string(),
+        0x17 => "THROWS".to_string(),
+        0x40 => "LOCAL_VARIABLE".to_string(),
+        0x41 => "RESOURCE_VARIABLE".to_string(),
+        0x42 => "EXCEPTION_PARAMETER".to_string(),
+        0x43 => "INSTANCEOF".to_string(),
+        0x44 => "NEW".to_string(),
+        0x45 => "CONSTRUCTOR_REFERENCE".to_string(),
+        0x46 => "METHOD_REFERENCE".to_string(),
+        0x47 => "CAST".to_string(),
+        0x48 => "CONSTRUCTOR_INVOCATION_TYPE_ARGUMENT".to_string(),
+        0x49 => "METHOD_INVOCATION_TYPE_ARGUMENT".to_string(),
+        0x4a => "CONSTRUCTOR_REFERENCE_TYPE_ARGUMENT".to_string(),
+        0x4b => "METHOD_REFERENCE_TYPE_ARGUMENT".to_string(),
+        _ => format!("UNKNOWN({:#x})", target_type),
+    }
+}
+
+impl ClassWriter<'_> {
+    pub(super) fn write_attributes(&mut self, attributes: &[AttributeInfo]) {
+        for attribute in attributes {
+            match typed_attribute_parser(attribute, &self.class_file.const_pool) {
+                Ok(typed) => self.write_attribute(attribute, typed),
+                Err(_) => self.write_unknown(attribute),
+            }
+        }
+    }
+
+    fn write_attribute(&mut self, attribute: &AttributeInfo, typed: TypedAttribute) {
+        match typed {
+            TypedAttribute::ConstantValue(constant_value) => {
+                let constant = self.constant(constant_value.constant_value_index);
+                self.p.println(format!("ConstantValue: {}", constant));
+            }
+            TypedAttribute::Code(code) => self.write_code(&code),
+            TypedAttribute::StackMapTable(stack_map_table) => {
+                self.p.println(format!(
+                    "StackMapTable: number_of_entries = {}",
+                    stack_map_table.entries.len()
+                ));
+                self.p.indent(1);
+                for frame in &stack_map_table.entries {
+                    self.write_stack_map_frame(frame);
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::Exceptions(exceptions) => {
+                let thrown: Vec<_> = exceptions
+                    .exception_table
+                    .iter()
+                    .map(|&index| java_name(&self.class_name(index)))
+                    .collect();
+                self.p.println("Exceptions:");
+                self.p.indent(1);
+                self.p.println(format!("throws {}", thrown.join(", ")));
+                self.p.indent(-1);
+            }
+            TypedAttribute::InnerClasses(inner_classes) => self.write_inner_classes(&inner_classes),
+            TypedAttribute::EnclosingMethod(enclosing_method) => {
+                self.p.print(format!(
+                    "EnclosingMethod: #{}.#{}",
+                    enclosing_method.class_index, enclosing_method.method_index
+                ));
+                self.p.tab();
+                let class = java_name(&self.class_name(enclosing_method.class_index));
+                self.p.print(format!("// {}", class));
+                if enclosing_method.method_index != 0 {
+                    let method = self
+                        .pool
+                        .get_name_and_type(enclosing_method.method_index)
+                        .map(|(name, _)| name.into_owned())
+                        .unwrap_or_else(|_| format!("#{}", enclosing_method.method_index));
+                    self.p.print(format!(".{}", method));
+                }
+                self.p.println("");
+            }
+            TypedAttribute::Synthetic(_) => self.p.println("Synthetic: true"),
+            TypedAttribute::Deprecated(_) => self.p.println("Deprecated: true"),
+            TypedAttribute::Signature(signature) => {
+                self.p
+                    .print(format!("Signature: #{}", signature.signature_index));
+                self.p.tab();
+                let value = self.utf8(signature.signature_index);
+                self.p.println(format!("// {}", value));
+            }
+            TypedAttribute::SourceFile(source_file) => {
+                let name = self.utf8(source_file.sourcefile_index);
+                self.p.println(format!("SourceFile: \"{}\"", name));
+            }
+            TypedAttribute::SourceDebugExtension(extension) => {
+                let value = cesu8::from_java_cesu8(&extension.debug_extension)
+                    .map(|value| value.into_owned())
+                    .unwrap_or_else(|_| {
+                        String::from_utf8_lossy(&extension.debug_extension).into_owned()
+                    });
+                self.p.println("SourceDebugExtension:");
+                self.p.indent(1);
+                for line in value.split(['\r', '\n']).filter(|line| !line.is_empty()) {
+                    self.p.println(line);
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::LineNumberTable(line_numbers) => {
+                self.p.println("LineNumberTable:");
+                self.p.indent(1);
+                for entry in &line_numbers.line_number_table {
+                    self.p
+                        .println(format!("line {}: {}", entry.line_number, entry.start_pc));
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::LocalVariableTable(table) => {
+                self.p.println("LocalVariableTable:");
+                self.p.indent(1);
+                self.p.println("Start  Length  Slot  Name   Signature");
+                for item in &table.items {
+                    let row = self.local_variable_row(
+                        item.start_pc,
+                        item.length,
+                        item.index,
+                        item.name_index,
+                        item.descriptor_index,
+                    );
+                    self.p.println(row);
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::LocalVariableTypeTable(table) => {
+                self.p.println("LocalVariableTypeTable:");
+                self.p.indent(1);
+                self.p.println("Start  Length  Slot  Name   Signature");
+                for item in &table.local_variable_type_table {
+                    let row = self.local_variable_row(
+                        item.start_pc,
+                        item.length,
+                        item.index,
+                        item.name_index,
+                        item.signature_index,
+                    );
+                    self.p.println(row);
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::RuntimeVisibleAnnotations(annotations) => {
+                self.write_annotations("RuntimeVisibleAnnotations", &annotations.annotations)
+            }
+            TypedAttribute::RuntimeInvisibleAnnotations(annotations) => {
+                self.write_annotations("RuntimeInvisibleAnnotations", &annotations.annotations)
+            }
+            TypedAttribute::RuntimeVisibleParameterAnnotations(parameters) => {
+                let parameters: Vec<_> = parameters
+                    .parameter_annotations
+                    .iter()
+                    .map(|parameter| &parameter.annotations[..])
+                    .collect();
+                self.write_parameter_annotations("RuntimeVisibleParameterAnnotations", &parameters)
+            }
+            TypedAttribute::RuntimeInvisibleParameterAnnotations(parameters) => {
+                let parameters: Vec<_> = parameters
+                    .parameter_annotations
+                    .iter()
+                    .map(|parameter| &parameter.annotations[..])
+                    .collect();
+                self.write_parameter_annotations(
+                    "RuntimeInvisibleParameterAnnotations",
+                    &parameters,
+                )
+            }
+            TypedAttribute::RuntimeVisibleTypeAnnotations(annotations) => self
+                .write_type_annotations(
+                    "RuntimeVisibleTypeAnnotations",
+                    &annotations.type_annotations,
+                ),
+            TypedAttribute::RuntimeInvisibleTypeAnnotations(annotations) => self
+                .write_type_annotations(
+                    "RuntimeInvisibleTypeAnnotations",
+                    &annotations.type_annotations,
+                ),
+            TypedAttribute::AnnotationDefault(value) => {
+                self.p.println("AnnotationDefault:");
+                self.p.indent(1);
+                self.p.print("default_value: ");
+                let unresolved = self.element_value(&value, false);
+                self.p.println(unresolved);
+                self.p.indent(1);
+                let resolved = self.element_value(&value, true);
+                self.p.println(resolved);
+                self.p.indent(-2);
+            }
+            TypedAttribute::BootstrapMethods(bootstrap_methods) => {
+                self.write_bootstrap_methods(&bootstrap_methods)
+            }
+            TypedAttribute::MethodParameters(parameters) => {
+                self.p.println("MethodParameters:");
+                self.p.indent(1);
+                self.p.println(format!("{:<31}{}", "Name", "Flags"));
+                for parameter in &parameters.parameters {
+                    let name = match parameter.name_index {
+                        0 => "<no name>".to_string(),
+                        index => self.string_value(index),
+                    };
+                    let flags = parameter.access_flags;
+                    let flags = format!(
+                        "{}{}{}",
+                        if flags & 0x0010 != 0 { "final " } else { "" },
+                        if flags & ACC_MANDATED != 0 {
+                            "mandated "
+                        } else {
+                            ""
+                        },
+                        if flags & ACC_SYNTHETIC != 0 {
+                            "synthetic"
+                        } else {
+                            ""
+                        }
+                    );
+                    self.p.println(format!("{:<31}{}", name, flags));
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::Module(module) => self.write_module(&module),
+            TypedAttribute::ModulePackages(packages) => {
+                self.p.println("ModulePackages:");
+                self.p.indent(1);
+                for &index in &packages.package_index {
+                    self.p.print(format!("#{}", index));
+                    self.p.tab();
+                    let package = self
+                        .pool
+                        .get_package_name(index)
+                        .map(|name| java_name(&name))
+                        .unwrap_or_else(|_| format!("#{}", index));
+                    self.p.println(format!("// {}", package));
+                }
+                self.p.indent(-1);
+            }
+            TypedAttribute::ModuleMainClass(main_class) => {
+                self.p
+                    .print(format!("ModuleMainClass: #{}", main_class.main_class_index));
+                self.p.tab();
+                let class = java_name(&self.class_name(main_class.main_class_index));
+                self.p.println(format!("// {}", class));
+            }
+            TypedAttribute::Record(record) => self.write_record(&record),
+            TypedAttribute::NestHost(nest_host) => {
+                let host = self.constant(nest_host.host_class_index);
+                self.p.println(format!("NestHost: {}", host));
+            }
+            TypedAttribute::NestMembers(nest_members) => {
+                self.write_class_list("NestMembers", &nest_members.classes)
+            }
+            TypedAttribute::PermittedSubclasses(permitted) => {
+                self.write_class_list("PermittedSubclasses", &permitted.classes)
+            }
+            TypedAttribute::Unknown { .. } => self.write_unknown(attribute),
+        }
+    }
+
+    fn write_unknown(&mut self, attribute: &AttributeInfo) {
+        let name = self
+            .pool
+            .get_utf8(attribute.attribute_name_index)
+            .map(|name| name.into_owned())
+            .unwrap_or_else(|_| format!("attribute name = #{}", attribute.attribute_name_index));
+        self.p
+            .println(format!("  {}: length = 0x{:X}", name, attribute.info.len()));
+        self.p.print("   ");
+        for (i, byte) in attribute.info.iter().enumerate() {
+            self.p.print(format!("{:02X}", byte));
+            if i % 16 == 15 {
+                self.p.println("");
+                self.p.print("   ");
+            } else {
+                self.p.print(" ");
+            }
+        }
+        self.p.println("");
+    }
+
+    fn local_variable_row(
+        &self,
+        start_pc: u16,
+        length: u16,
+        slot: u16,
+        name_index: u16,
+        signature_index: u16,
+    ) -> String {
+        format!(
+            "{:5} {:7} {:5} {:>5}   {}",
+            start_pc,
+            length,
+            slot,
+            self.string_value(name_index),
+            self.string_value(signature_index)
+        )
+    }
+
+    fn write_class_list(&mut self, name: &str, classes: &[u16]) {
+        self.p.println(format!("{}:", name));
+        self.p.indent(1);
+        for &class in classes {
+            let class = self.string_value(class);
+            self.p.println(class);
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_inner_classes(&mut self, inner_classes: &InnerClassesAttribute) {
+        if inner_classes.classes.is_empty() {
+            return;
+        }
+        self.p.println("InnerClasses:");
+        self.p.indent(1);
+        for class in &inner_classes.classes {
+            let mut flags = class.inner_class_access_flags;
+            if flags & InnerClassAccessFlags::INTERFACE.bits() != 0 {
+                flags &= !InnerClassAccessFlags::ABSTRACT.bits();
+            }
+            self.p.print(modifiers(flags, INNER_CLASS_MODIFIERS));
+            if class.inner_name_index != 0 {
+                self.p.print(format!("#{}= ", class.inner_name_index));
+            }
+            self.p.print(format!("#{}", class.inner_class_info_index));
+            if class.outer_class_info_index != 0 {
+                self.p
+                    .print(format!(" of #{}", class.outer_class_info_index));
+            }
+            self.p.print(";");
+            self.p.tab();
+            self.p.print("// ");
+            if class.inner_name_index != 0 {
+                let name = self.utf8(class.inner_name_index);
+                self.p.print(format!("{}=", name));
+            }
+            let inner = self.constant(class.inner_class_info_index);
+            self.p.print(inner);
+            if class.outer_class_info_index != 0 {
+                let outer = self.constant(class.outer_class_info_index);
+                self.p.print(format!(" of {}", outer));
+            }
+            self.p.println("");
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_record(&mut self, record: &RecordAttribute) {
+        self.p.println("Record:");
+        self.p.indent(1);
+        for component in &record.components {
+            let descriptor = self.utf8(component.descriptor_index);
+            let component_type = self.field_type(&descriptor, &component.attributes);
+            let name = self.utf8(component.name_index);
+            self.p.println(format!("{} {};", component_type, name));
+            self.p.indent(1);
+            self.p.println(format!("descriptor: {}", descriptor));
+            self.write_attributes(&component.attributes);
+            self.p.indent(-1);
+            self.p.println("");
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_bootstrap_methods(&mut self, bootstrap_methods: &BootstrapMethodsAttribute) {
+        self.p.println("BootstrapMethods:");
+        self.p.indent(1);
+        for (i, method) in bootstrap_methods.bootstrap_methods.iter().enumerate() {
+            let reference = self.string_value(method.bootstrap_method_ref);
+            self.p.println(format!(
+                "{}: #{} {}",
+                i, method.bootstrap_method_ref, reference
+            ));
+            self.p.indent(1);
+            self.p.println("Method arguments:");
+            self.p.indent(1);
+            for &argument in &method.bootstrap_arguments {
+                let value = self.string_value(argument);
+                self.p.println(format!("#{} {}", argument, value));
+            }
+            self.p.indent(-2);
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_annotations(&mut self, name: &str, annotations: &[RuntimeAnnotation]) {
+        self.p.println(format!("{}:", name));
+        self.p.indent(1);
+        for (i, annotation) in annotations.iter().enumerate() {
+            self.p.print(format!("{}: ", i));
+            self.write_annotation(annotation);
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_parameter_annotations(&mut self, name: &str, parameters: &[&[RuntimeAnnotation]]) {
+        self.p.println(format!("{}:", name));
+        self.p.indent(1);
+        for (parameter, annotations) in parameters.iter().enumerate() {
+            self.p.println(format!("parameter {}:", parameter));
+            self.p.indent(1);
+            for (i, annotation) in annotations.iter().enumerate() {
+                self.p.print(format!("{}: ", i));
+                self.write_annotation(annotation);
+            }
+            self.p.indent(-1);
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_type_annotations(&mut self, name: &str, annotations: &[TypeAnnotation]) {
+        self.p.println(format!("{}:", name));
+        self.p.indent(1);
+        for (i, annotation) in annotations.iter().enumerate() {
+            let unresolved = self.annotation(
+                annotation.type_index,
+                &annotation.element_value_pairs,
+                false,
+            );
+            self.p.println(format!(
+                "{}: {}: {}",
+                i,
+                unresolved,
+                self.type_annotation_position(annotation)
+            ));
+            self.p.indent(1);
+            let resolved =
+                self.annotation(annotation.type_index, &annotation.element_value_pairs, true);
+            self.p.println(resolved);
+            self.p.indent(-1);
+        }
+        self.p.indent(-1);
+    }
+
+    /// Write an annotation in its raw form, followed by its resolved form on the next lines.
+    fn write_annotation(&mut self, annotation: &RuntimeAnnotation) {
+        let unresolved = self.annotation(
+            annotation.type_index,
+            &annotation.element_value_pairs,
+            false,
+        );
+        self.p.println(unresolved);
+        self.p.indent(1);
+        let resolved =
+            self.annotation(annotation.type_index, &annotation.element_value_pairs, true);
+        self.p.println(resolved);
+        self.p.indent(-1);
+    }
+
+    /// An annotation either with constant pool indices (`#12(#13=I#14)`) or resolved into
+    /// names and values, one element per line.
+    fn annotation(
+        &self,
+        type_index: u16,
+        pairs: &[crate::attribute_info::ElementValuePair],
+        resolve: bool,
+    ) -> String {
+        if !resolve {
+            let pairs: Vec<_> = pairs
+                .iter()
+                .map(|pair| {
+                    format!(
+                        "#{}={}",
+                        pair.element_name_index,
+                        self.element_value(&pair.value, false)
+                    )
+                })
+                .collect();
+            return format!("#{}({})", type_index, pairs.join(","));
+        }
+        let mut annotation = self.descriptor_java_name(type_index);
+        if !pairs.is_empty() {
+            annotation.push_str("(\n");
+            for pair in pairs {
+                let value = self.element_value(&pair.value, true);
+                let element = format!("{}={}", self.utf8(pair.element_name_index), value);
+                // Nested annotations span several lines, all indented by one level
+                for line in element.split('\n') {
+                    annotation.push_str("  ");
+                    annotation.push_str(line);
+                    annotation.push('\n');
+                }
+            }
+            annotation.push(')');
+        }
+        annotation
+    }
+
+    fn element_value(&self, value: &ElementValue, resolve: bool) -> String {
+        match value {
+            ElementValue::ConstValueIndex { tag, value } if !resolve => {
+                format!("{}#{}", tag, value)
+            }
+            ElementValue::ConstValueIndex { tag, value } => self.const_element_value(*tag, *value),
+            ElementValue::EnumConst(enum_const) if !resolve => format!(
+                "e#{}.#{}",
+                enum_const.type_name_index, enum_const.const_name_index
+            ),
+            ElementValue::EnumConst(enum_const) => format!(
+                "{}.{}",
+                self.utf8(enum_const.type_name_index),
+                self.utf8(enum_const.const_name_index)
+            ),
+            ElementValue::ClassInfoIndex(index) if !resolve => format!("c#{}", index),
+            ElementValue::ClassInfoIndex(index) => format!("class {}", self.utf8(*index)),
+            ElementValue::AnnotationValue(annotation) => format!(
+                "@{}",
+                self.annotation(
+                    annotation.type_index,
+                    &annotation.element_value_pairs,
+                    resolve
+                )
+            ),
+            ElementValue::ElementArray(array) => {
+                let values: Vec<_> = array
+                    .values
+                    .iter()
+                    .map(|value| self.element_value(value, resolve))
+                    .collect();
+                format!("[{}]", values.join(","))
+            }
+        }
+    }
+
+    fn const_element_value(&self, tag: char, index: u16) -> String {
+        use crate::constant_info::ConstantInfo;
+
+        let constant = match self.pool.get(index) {
+            Ok(constant) => constant,
+            Err(_) => return format!("#{}", index),
+        };
+        match (tag, constant) {
+            ('B', ConstantInfo::Integer(i)) => format!("(byte) {}", i.value),
+            ('C', ConstantInfo::Integer(i)) => format!(
+                "'{}'",
+                char::from_u32(i.value as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+            ),
+            ('S', ConstantInfo::Integer(i)) => format!("(short) {}", i.value),
+            ('Z', ConstantInfo::Integer(i)) => (i.value != 0).to_string(),
+            ('I', ConstantInfo::Integer(i)) => i.value.to_string(),
+            ('J', ConstantInfo::Long(l)) => format!("{}l", l.value),
+            ('F', ConstantInfo::Float(f)) => format!("{}f", java_float(f.value)),
+            ('D', ConstantInfo::Double(d)) => format!("{}d", java_double(d.value)),
+            ('s', ConstantInfo::Utf8(_)) => format!("\"{}\"", escape(&self.utf8(index))),
+            _ => self.string_value(index),
+        }
+    }
+
+    /// The Java name of the type described by a field descriptor in the constant pool.
+    fn descriptor_java_name(&self, index: u16) -> String {
+        let descriptor = self.utf8(index);
+        descriptor
+            .parse::<FieldType>()
+            .map(|field_type| field_type.to_java())
+            .unwrap_or(descriptor)
+    }
+
+    fn type_annotation_position(&self, annotation: &TypeAnnotation) -> String {
+        let mut position = target_type_name(annotation.target_type);
+        match &annotation.target_info {
+            TargetInfo::TypeParameter {
+                type_parameter_index,
+            } => position.push_str(&format!(", param_index={}", type_parameter_index)),
+            TargetInfo::SuperType { supertype_index } => {
+                let index = match supertype_index {
+                    0xffff => -1,
+                    &index => index as i32,
+                };
+                position.push_str(&format!(", type_index={}", index));
+            }
+            TargetInfo::TypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => position.push_str(&format!(
+                ", param_index={}, bound_index={}",
+                type_parameter_index, bound_index
+            )),
+            TargetInfo::Empty => {}
+            TargetInfo::FormalParameter {
+                formal_parameter_index,
+            } => position.push_str(&format!(", param_index={}", formal_parameter_index)),
+            TargetInfo::Throws { throws_type_index } => {
+                position.push_str(&format!(", type_index={}", throws_type_index))
+            }
+            TargetInfo::LocalVar { tables, .. } => {
+                let tables: Vec<_> = tables
+                    .iter()
+                    .map(|table| {
+                        format!(
+                            "start_pc={}, length={}, index={}",
+                            table.start_pc, table.length, table.index
+                        )
+                    })
+                    .collect();
+                position.push_str(&format!(", {{{}}}", tables.join("; ")));
+            }
+            TargetInfo::Catch {
+                exception_table_index,
+            } => position.push_str(&format!(", exception_index={}", exception_table_index)),
+            TargetInfo::Offset { offset } => position.push_str(&format!(", offset={}", offset)),
+            TargetInfo::TypeArgument {
+                offset,
+                type_argument_index,
+            } => position.push_str(&format!(
+                ", offset={}, type_index={}",
+                offset, type_argument_index
+            )),
+        }
+        if !annotation.target_path.paths.is_empty() {
+            let location: Vec<_> = annotation
+                .target_path
+                .paths
+                .iter()
+                .map(|entry| match entry.type_path_kind {
+                    0 => "ARRAY".to_string(),
+                    1 => "INNER_TYPE".to_string(),
+                    2 => "WILDCARD".to_string(),
+                    3 => format!("TYPE_ARGUMENT({})", entry.type_argument_index),
+                    kind => format!("UNKNOWN({})", kind),
+                })
+                .collect();
+            position.push_str(&format!(", location=[{}]", location.join(", ")));
+        }
+        position
+    }
+
+    fn write_module(&mut self, module: &ModuleAttribute) {
+        self.p.println("Module:");
+        self.p.indent(1);
+        self.p.print(format!(
+            "#{},{:x}",
+            module.module_name_index, module.module_flags
+        ));
+        self.p.tab();
+        let name = self.string_value(module.module_name_index);
+        self.p.print(format!("// {}", name));
+        for (flag, flag_name) in [
+            (ACC_OPEN, "ACC_OPEN"),
+            (ACC_MANDATED, "ACC_MANDATED"),
+            (ACC_SYNTHETIC, "ACC_SYNTHETIC"),
+        ] {
+            if module.module_flags & flag != 0 {
+                self.p.print(format!(" {}", flag_name));
+            }
+        }
+        self.p.println("");
+        self.write_optional_index(module.module_version_index);
+
+        self.write_table_header(module.requires.len(), "requires");
+        for requires in &module.requires {
+            self.p.print(format!(
+                "#{},{:x}",
+                requires.requires_index, requires.requires_flags
+            ));
+            self.p.tab();
+            let name = self.string_value(requires.requires_index);
+            self.p.print(format!("// {}", name));
+            for (flag, flag_name) in [
+                (ACC_TRANSITIVE, "ACC_TRANSITIVE"),
+                (ACC_STATIC_PHASE, "ACC_STATIC_PHASE"),
+                (ACC_SYNTHETIC, "ACC_SYNTHETIC"),
+                (ACC_MANDATED, "ACC_MANDATED"),
+            ] {
+                if requires.requires_flags & flag != 0 {
+                    self.p.print(format!(" {}", flag_name));
+                }
+            }
+            self.p.println("");
+            self.write_optional_index(requires.requires_version_index);
+        }
+        self.p.indent(-1);
+
+        self.write_table_header(module.exports.len(), "exports");
+        for exports in &module.exports {
+            self.write_exports_or_opens(
+                exports.exports_index,
+                exports.exports_flags,
+                &exports.exports_to_index,
+            );
+        }
+        self.p.indent(-1);
+
+        self.write_table_header(module.opens.len(), "opens");
+        for opens in &module.opens {
+            self.write_exports_or_opens(
+                opens.opens_index,
+                opens.opens_flags,
+                &opens.opens_to_index,
+            );
+        }
+        self.p.indent(-1);
+
+        self.write_table_header(module.uses.len(), "uses");
+        for &uses in &module.uses {
+            self.p.print(format!("#{}", uses));
+            self.p.tab();
+            let value = self.string_value(uses);
+            self.p.println(format!("// {}", value));
+        }
+        self.p.indent(-1);
+
+        self.write_table_header(module.provides.len(), "provides");
+        for provides in &module.provides {
+            self.p.print(format!("#{}", provides.provides_index));
+            self.p.tab();
+            let value = self.string_value(provides.provides_index);
+            self.p.println(format!(
+                "// {} with ... {}",
+                value,
+                provides.provides_with_index.len()
+            ));
+            self.p.indent(1);
+            for &with in &provides.provides_with_index {
+                self.p.print(format!("#{}", with));
+                self.p.tab();
+                let value = self.string_value(with);
+                self.p.println(format!("// ... with {}", value));
+            }
+            self.p.indent(-1);
+        }
+        self.p.indent(-1);
+
+        self.p.indent(-1);
+    }
+
+    /// Print the size of a module table and indent for its entries.
+    fn write_table_header(&mut self, len: usize, name: &str) {
+        self.p.print(len.to_string());
+        self.p.tab();
+        self.p.println(format!("// {}", name));
+        self.p.indent(1);
+    }
+
+    fn write_optional_index(&mut self, index: u16) {
+        self.p.print(format!("#{}", index));
+        if index != 0 {
+            self.p.tab();
+            let value = self.string_value(index);
+            self.p.print(format!("// {}", value));
+        }
+        self.p.println("");
+    }
+
+    fn write_exports_or_opens(&mut self, index: u16, flags: u16, to: &[u16]) {
+        self.p.print(format!("#{},{:x}", index, flags));
+        self.p.tab();
+        let value = self.string_value(index);
+        self.p.print(format!("// {}", value));
+        if flags & ACC_MANDATED != 0 {
+            self.p.print(" ACC_MANDATED");
+        }
+        if flags & ACC_SYNTHETIC != 0 {
+            self.p.print(" ACC_SYNTHETIC");
+        }
+        if to.is_empty() {
+            self.p.println("");
+            return;
+        }
+        self.p.println(format!(" to ... {}", to.len()));
+        self.p.indent(1);
+        for &to in to {
+            self.p.print(format!("#{}", to));
+            self.p.tab();
+            let value = self.string_value(to);
+            self.p.println(format!("// ... to {}", value));
+        }
+        self.p.indent(-1);
+    }
+}
@@ -0,0 +1,515 @@
+use crate::attribute_info::{CodeAttribute, StackMapFrame, VerificationTypeInfo};
+use crate::code_attribute::{Instruction, code_parser, instruction_encoder};
+use crate::descriptor::MethodDescriptor;
+use crate::disassembler::ClassWriter;
+use crate::method_info::MethodAccessFlags;
+
+/// Mnemonics indexed by opcode.
+const MNEMONICS: [&str; 202] = [
+    "nop",
+    "aconst_null",
+    "iconst_m1",
+    "iconst_0",
+    "iconst_1",
+    "iconst_2",
+    "iconst_3",
+    "iconst_4",
+    "iconst_5",
+    "lconst_0",
+    "lconst_1",
+    "fconst_0",
+    "fconst_1",
+    "fconst_2",
+    "dconst_0",
+    "dconst_1",
+    "bipush",
+    "sipush",
+    "ldc",
+    "ldc_w",
+    "ldc2_w",
+    "iload",
+    "lload",
+    "fload",
+    "dload",
+    "aload",
+    "iload_0",
+    "iload_1",
+    "iload_2",
+    "iload_3",
+    "lload_0",
+    "lload_1",
+    "lload_2",
+    "lload_3",
+    "fload_0",
+    "fload_1",
+    "fload_2",
+    "fload_3",
+    "dload_0",
+    "dload_1",
+    "dload_2",
+    "dload_3",
+    "aload_0",
+    "aload_1",
+    "aload_2",
+    "aload_3",
+    "iaload",
+    "laload",
+    "faload",
+    "daload",
+    "aaload",
+    "baload",
+    "caload",
+    "saload",
+    "istore",
+    "lstore",
+    "fstore",
+    "dstore",
+    "astore",
+    "istore_0",
+    "istore_1",
+    "istore_2",
+    "istore_3",
+    "lstore_0",
+    "lstore_1",
+    "lstore_2",
+    "lstore_3",
+    "fstore_0",
+    "fstore_1",
+    "fstore_2",
+    "fstore_3",
+    "dstore_0",
+    "dstore_1",
+    "dstore_2",
+    "dstore_3",
+    "astore_0",
+    "astore_1",
+    "astore_2",
+    "astore_3",
+    "iastore",
+    "lastore",
+    "fastore",
+    "dastore",
+    "aastore",
+    "bastore",
+    "castore",
+    "sastore",
+    "pop",
+    "pop2",
+    "dup",
+    "dup_x1",
+    "dup_x2",
+    "dup2",
+    "dup2_x1",
+    "dup2_x2",
+    "swap",
+    "iadd",
+    "ladd",
+    "fadd",
+    "dadd",
+    "isub",
+    "lsub",
+    "fsub",
+    "dsub",
+    "imul",
+    "lmul",
+    "fmul",
+    "dmul",
+    "idiv",
+    "ldiv",
+    "fdiv",
+    "ddiv",
+    "irem",
+    "lrem",
+    "frem",
+    "drem",
+    "ineg",
+    "lneg",
+    "fneg",
+    "dneg",
+    "ishl",
+    "lshl",
+    "ishr",
+    "lshr",
+    "iushr",
+    "lushr",
+    "iand",
+    "land",
+    "ior",
+    "lor",
+    "ixor",
+    "lxor",
+    "iinc",
+    "i2l",
+    "i2f",
+    "i2d",
+    "l2i",
+    "l2f",
+    "l2d",
+    "f2i",
+    "f2l",
+    "f2d",
+    "d2i",
+    "d2l",
+    "d2f",
+    "i2b",
+    "i2c",
+    "i2s",
+    "lcmp",
+    "fcmpl",
+    "fcmpg",
+    "dcmpl",
+    "dcmpg",
+    "ifeq",
+    "ifne",
+    "iflt",
+    "ifge",
+    "ifgt",
+    "ifle",
+    "if_icmpeq",
+    "if_icmpne",
+    "if_icmplt",
+    "if_icmpge",
+    "if_icmpgt",
+    "if_icmple",
+    "if_acmpeq",
+    "if_acmpne",
+    "goto",
+    "jsr",
+    "ret",
+    "tableswitch",
+    "lookupswitch",
+    "ireturn",
+    "lreturn",
+    "freturn",
+    "dreturn",
+    "areturn",
+    "return",
+    "getstatic",
+    "putstatic",
+    "getfield",
+    "putfield",
+    "invokevirtual",
+    "invokespecial",
+    "invokestatic",
+    "invokeinterface",
+    "invokedynamic",
+    "new",
+    "newarray",
+    "anewarray",
+    "arraylength",
+    "athrow",
+    "checkcast",
+    "instanceof",
+    "monitorenter",
+    "monitorexit",
+    "wide",
+    "multianewarray",
+    "ifnull",
+    "ifnonnull",
+    "goto_w",
+    "jsr_w",
+];
+
+/// How many levels the entries of a switch are indented, enough to clear the `%4d: ` prefix.
+const SWITCH_INDENT: isize = 3;
+
+/// The mnemonic javap uses for an instruction; wide forms get a `_w` suffix.
+pub(crate) fn mnemonic(instruction: &Instruction, address: usize) -> String {
+    let mut bytes = Vec::new();
+    instruction_encoder(instruction, address, &mut bytes);
+    match bytes[..] {
+        [0xc4, opcode, ..] => format!("{}_w", MNEMONICS[opcode as usize]),
+        [opcode, ..] => MNEMONICS[opcode as usize].to_string(),
+        [] => String::new(),
+    }
+}
+
+fn array_type_name(atype: u8) -> String {
+    match atype {
+        4 => "boolean".to_string(),
+        5 => "char".to_string(),
+        6 => "float".to_string(),
+        7 => "double".to_string(),
+        8 => "byte".to_string(),
+        9 => "short".to_string(),
+        10 => "int".to_string(),
+        11 => "long".to_string(),
+        _ => format!("BOGUS TYPE:{}", atype),
+    }
+}
+
+fn branch_target(address: usize, offset: i32) -> i64 {
+    address as i64 + offset as i64
+}
+
+impl ClassWriter<'_> {
+    pub(super) fn write_code(&mut self, code: &CodeAttribute) {
+        self.p.println("Code:");
+        self.p.indent(1);
+        let args_size = self
+            .method
+            .map(|method| {
+                let descriptor = self.utf8(method.descriptor_index);
+                let params = descriptor
+                    .parse::<MethodDescriptor>()
+                    .map(|descriptor| descriptor.params.len())
+                    .unwrap_or(0);
+                if method.access_flags.contains(MethodAccessFlags::STATIC) {
+                    params
+                } else {
+                    params + 1
+                }
+            })
+            .unwrap_or(0);
+        self.p.println(format!(
+            "stack={}, locals={}, args_size={}",
+            code.max_stack, code.max_locals, args_size
+        ));
+
+        let (rest, instructions) = match code_parser(&code.code) {
+            Ok(result) => result,
+            Err(_) => (&code.code[..], Vec::new()),
+        };
+        for (address, instruction) in &instructions {
+            self.write_instruction(*address, instruction);
+        }
+        if !rest.is_empty() {
+            let address = code.code.len() - rest.len();
+            self.p.println(format!(
+                "{:4}: error: invalid opcode 0x{:02x}",
+                address, rest[0]
+            ));
+        }
+
+        if !code.exception_table.is_empty() {
+            self.p.println("Exception table:");
+            self.p.indent(1);
+            self.p.println(" from    to  target type");
+            for entry in &code.exception_table {
+                self.p.print(format!(
+                    " {:5} {:5} {:5}   ",
+                    entry.start_pc, entry.end_pc, entry.handler_pc
+                ));
+                if entry.catch_type == 0 {
+                    self.p.println("any");
+                } else {
+                    let class = self.string_value(entry.catch_type);
+                    self.p.println(format!("Class {}", class));
+                }
+            }
+            self.p.indent(-1);
+        }
+
+        self.write_attributes(&code.attributes);
+        self.p.indent(-1);
+    }
+
+    fn write_instruction(&mut self, address: usize, instruction: &Instruction) {
+        self.p.print(format!(
+            "{:4}: {:<13} ",
+            address,
+            mnemonic(instruction, address)
+        ));
+        match *instruction {
+            Instruction::Aload(index)
+            | Instruction::Astore(index)
+            | Instruction::Dload(index)
+            | Instruction::Dstore(index)
+            | Instruction::Fload(index)
+            | Instruction::Fstore(index)
+            | Instruction::Iload(index)
+            | Instruction::Istore(index)
+            | Instruction::Lload(index)
+            | Instruction::Lstore(index)
+            | Instruction::Ret(index) => self.p.print(index.to_string()),
+            Instruction::AloadWide(index)
+            | Instruction::AstoreWide(index)
+            | Instruction::DloadWide(index)
+            | Instruction::DstoreWide(index)
+            | Instruction::FloadWide(index)
+            | Instruction::FstoreWide(index)
+            | Instruction::IloadWide(index)
+            | Instruction::IstoreWide(index)
+            | Instruction::LloadWide(index)
+            | Instruction::LstoreWide(index)
+            | Instruction::RetWide(index) => self.p.print(index.to_string()),
+            Instruction::Iinc { index, value } => self.p.print(format!("{}, {}", index, value)),
+            Instruction::IincWide { index, value } => self.p.print(format!("{}, {}", index, value)),
+            Instruction::Bipush(value) => self.p.print(value.to_string()),
+            Instruction::Sipush(value) => self.p.print(value.to_string()),
+            Instruction::Newarray(atype) => self.p.print(format!(" {}", array_type_name(atype))),
+            Instruction::Goto(offset)
+            | Instruction::IfAcmpeq(offset)
+            | Instruction::IfAcmpne(offset)
+            | Instruction::IfIcmpeq(offset)
+            | Instruction::IfIcmpne(offset)
+            | Instruction::IfIcmplt(offset)
+            | Instruction::IfIcmpge(offset)
+            | Instruction::IfIcmpgt(offset)
+            | Instruction::IfIcmple(offset)
+            | Instruction::Ifeq(offset)
+            | Instruction::Ifne(offset)
+            | Instruction::Iflt(offset)
+            | Instruction::Ifge(offset)
+            | Instruction::Ifgt(offset)
+            | Instruction::Ifle(offset)
+            | Instruction::Ifnonnull(offset)
+            | Instruction::Ifnull(offset)
+            | Instruction::Jsr(offset) => self
+                .p
+                .print(branch_target(address, offset.into()).to_string()),
+            Instruction::GotoW(offset) | Instruction::JsrW(offset) => {
+                self.p.print(branch_target(address, offset).to_string())
+            }
+            Instruction::Ldc(index) => self.write_constant_ref(index.into(), None),
+            Instruction::Anewarray(index)
+            | Instruction::Checkcast(index)
+            | Instruction::Getfield(index)
+            | Instruction::Getstatic(index)
+            | Instruction::Instanceof(index)
+            | Instruction::Invokespecial(index)
+            | Instruction::Invokestatic(index)
+            | Instruction::Invokevirtual(index)
+            | Instruction::LdcW(index)
+            | Instruction::Ldc2W(index)
+            | Instruction::New(index)
+            | Instruction::Putfield(index)
+            | Instruction::Putstatic(index) => self.write_constant_ref(index, None),
+            Instruction::Invokedynamic(index) => self.write_constant_ref(index, Some(0)),
+            Instruction::Invokeinterface { index, count } => {
+                self.write_constant_ref(index, Some(count))
+            }
+            Instruction::Multianewarray { index, dimensions } => {
+                self.write_constant_ref(index, Some(dimensions))
+            }
+            Instruction::Tableswitch {
+                default,
+                low,
+                high,
+                ref offsets,
+            } => {
+                self.p.print(format!("{{ // {} to {}", low, high));
+                self.p.indent(SWITCH_INDENT);
+                for (key, &offset) in (low as i64..).zip(offsets) {
+                    self.p
+                        .print(format!("\n{:12}: {}", key, branch_target(address, offset)));
+                }
+                self.write_switch_default(address, default);
+            }
+            Instruction::Lookupswitch { default, ref pairs } => {
+                self.p.print(format!("{{ // {}", pairs.len()));
+                self.p.indent(SWITCH_INDENT);
+                for &(key, offset) in pairs {
+                    self.p
+                        .print(format!("\n{:12}: {}", key, branch_target(address, offset)));
+                }
+                self.write_switch_default(address, default);
+            }
+            _ => {}
+        }
+        self.p.println("");
+    }
+
+    fn write_constant_ref(&mut self, index: u16, value: Option<u8>) {
+        match value {
+            Some(value) => self.p.print(format!("#{},  {}", index, value)),
+            None => self.p.print(format!("#{}", index)),
+        }
+        self.p.tab();
+        let constant = self.constant(index);
+        self.p.print(format!("// {}", constant));
+    }
+
+    fn write_switch_default(&mut self, address: usize, default: i32) {
+        self.p.print(format!(
+            "\n     default: {}\n}}",
+            branch_target(address, default)
+        ));
+        self.p.indent(-SWITCH_INDENT);
+    }
+
+    pub(super) fn write_stack_map_frame(&mut self, frame: &StackMapFrame) {
+        let (frame_type, kind) = match frame {
+            StackMapFrame::SameFrame { frame_type } => (frame_type, "same"),
+            StackMapFrame::SameLocals1StackItemFrame { frame_type, .. } => {
+                (frame_type, "same_locals_1_stack_item")
+            }
+            StackMapFrame::SameLocals1StackItemFrameExtended { frame_type, .. } => {
+                (frame_type, "same_locals_1_stack_item_frame_extended")
+            }
+            StackMapFrame::ChopFrame { frame_type, .. } => (frame_type, "chop"),
+            StackMapFrame::SameFrameExtended { frame_type, .. } => {
+                (frame_type, "same_frame_extended")
+            }
+            StackMapFrame::AppendFrame { frame_type, .. } => (frame_type, "append"),
+            StackMapFrame::FullFrame { frame_type, .. } => (frame_type, "full_frame"),
+        };
+        self.p
+            .println(format!("frame_type = {} /* {} */", frame_type, kind));
+        self.p.indent(1);
+        match frame {
+            StackMapFrame::SameFrame { .. } => {}
+            StackMapFrame::SameLocals1StackItemFrame { stack, .. } => {
+                self.write_verification_types("stack", std::slice::from_ref(stack));
+            }
+            StackMapFrame::SameLocals1StackItemFrameExtended {
+                offset_delta,
+                stack,
+                ..
+            } => {
+                self.p.println(format!("offset_delta = {}", offset_delta));
+                self.write_verification_types("stack", std::slice::from_ref(stack));
+            }
+            StackMapFrame::ChopFrame { offset_delta, .. }
+            | StackMapFrame::SameFrameExtended { offset_delta, .. } => {
+                self.p.println(format!("offset_delta = {}", offset_delta));
+            }
+            StackMapFrame::AppendFrame {
+                offset_delta,
+                locals,
+                ..
+            } => {
+                self.p.println(format!("offset_delta = {}", offset_delta));
+                self.write_verification_types("locals", locals);
+            }
+            StackMapFrame::FullFrame {
+                offset_delta,
+                locals,
+                stack,
+                ..
+            } => {
+                self.p.println(format!("offset_delta = {}", offset_delta));
+                self.write_verification_types("locals", locals);
+                self.write_verification_types("stack", stack);
+            }
+        }
+        self.p.indent(-1);
+    }
+
+    fn write_verification_types(&mut self, name: &str, types: &[VerificationTypeInfo]) {
+        let types: Vec<_> = types
+            .iter()
+            .map(|verification_type| match verification_type {
+                VerificationTypeInfo::Top => "top".to_string(),
+                VerificationTypeInfo::Integer => "int".to_string(),
+                VerificationTypeInfo::Float => "float".to_string(),
+                VerificationTypeInfo::Long => "long".to_string(),
+                VerificationTypeInfo::Double => "double".to_string(),
+                VerificationTypeInfo::Null => "null".to_string(),
+                VerificationTypeInfo::UninitializedThis => "this".to_string(),
+                VerificationTypeInfo::Object { class } => {
+                    format!("class {}", self.string_value(*class))
+                }
+                VerificationTypeInfo::Uninitialized { offset } => {
+                    format!("uninitialized {}", offset)
+                }
+            })
+            .collect();
+        if types.is_empty() {
+            self.p.println(format!("{} = []", name));
+        } else {
+            self.p
+                .println(format!("{} = [ {} ]", name, types.join(", ")));
+        }
+    }
+}
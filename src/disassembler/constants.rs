@@ -0,0 +1,306 @@
+use crate::constant_info::ConstantInfo;
+use crate::disassembler::ClassWriter;
+
+impl ClassWriter<'_> {
+    pub(super) fn write_constant_pool(&mut self) {
+        self.p.println("Constant pool:");
+        self.p.indent(1);
+        let width = (self.class_file.const_pool_size.to_string().len() + 1).max(2);
+        for (index, constant) in self.pool.iter() {
+            self.p.print(format!(
+                "{:>width$} = {:<18} ",
+                format!("#{}", index),
+                pool_tag_name(constant),
+                width = width
+            ));
+            match constant {
+                ConstantInfo::Utf8(_)
+                | ConstantInfo::Integer(_)
+                | ConstantInfo::Float(_)
+                | ConstantInfo::Long(_)
+                | ConstantInfo::Double(_) => {
+                    let value = self.string_value(index);
+                    self.p.println(value);
+                    continue;
+                }
+                ConstantInfo::Class(c) => self.p.print(format!("#{}", c.name_index)),
+                ConstantInfo::String(s) => self.p.print(format!("#{}", s.string_index)),
+                ConstantInfo::FieldRef(r) => self
+                    .p
+                    .print(format!("#{}.#{}", r.class_index, r.name_and_type_index)),
+                ConstantInfo::MethodRef(r) => self
+                    .p
+                    .print(format!("#{}.#{}", r.class_index, r.name_and_type_index)),
+                ConstantInfo::InterfaceMethodRef(r) => self
+                    .p
+                    .print(format!("#{}.#{}", r.class_index, r.name_and_type_index)),
+                ConstantInfo::NameAndType(n) => self
+                    .p
+                    .print(format!("#{}:#{}", n.name_index, n.descriptor_index)),
+                ConstantInfo::MethodHandle(h) => self
+                    .p
+                    .print(format!("{}:#{}", h.reference_kind, h.reference_index)),
+                ConstantInfo::MethodType(t) => self.p.print(format!("#{}", t.descriptor_index)),
+                ConstantInfo::Dynamic(d) => self.p.print(format!(
+                    "#{}:#{}",
+                    d.bootstrap_method_attr_index, d.name_and_type_index
+                )),
+                ConstantInfo::InvokeDynamic(d) => self.p.print(format!(
+                    "#{}:#{}",
+                    d.bootstrap_method_attr_index, d.name_and_type_index
+                )),
+                ConstantInfo::Module(m) => self.p.print(format!("#{}", m.name_index)),
+                ConstantInfo::Package(p) => self.p.print(format!("#{}", p.name_index)),
+                ConstantInfo::Unusable => {}
+            }
+            self.p.tab();
+            // javap puts an extra space before method type descriptors
+            let separator = match constant {
+                ConstantInfo::MethodType(_) => "//  ",
+                _ => "// ",
+            };
+            let value = self.string_value(index);
+            self.p.println(format!("{}{}", separator, value));
+        }
+        self.p.indent(-1);
+    }
+
+    /// The raw, unescaped value of a Utf8 constant, or `#index` if it cannot be resolved.
+    pub(super) fn utf8(&self, index: u16) -> String {
+        self.pool
+            .get_utf8(index)
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| format!("#{}", index))
+    }
+
+    /// The value of a constant as javap shows it in comments.
+    pub(super) fn string_value(&self, index: u16) -> String {
+        let constant = match self.pool.get(index) {
+            Ok(constant) => constant,
+            Err(_) => return format!("#{}", index),
+        };
+        match constant {
+            ConstantInfo::Utf8(_) => escape(&self.utf8(index)),
+            ConstantInfo::Integer(i) => i.value.to_string(),
+            ConstantInfo::Float(f) => format!("{}f", java_float(f.value)),
+            ConstantInfo::Long(l) => format!("{}l", l.value),
+            ConstantInfo::Double(d) => format!("{}d", java_double(d.value)),
+            ConstantInfo::Class(c) => check_name(&self.utf8(c.name_index)),
+            ConstantInfo::String(s) => self.string_value(s.string_index),
+            ConstantInfo::FieldRef(r) => self.ref_value(r.class_index, r.name_and_type_index),
+            ConstantInfo::MethodRef(r) => self.ref_value(r.class_index, r.name_and_type_index),
+            ConstantInfo::InterfaceMethodRef(r) => {
+                self.ref_value(r.class_index, r.name_and_type_index)
+            }
+            ConstantInfo::NameAndType(n) => format!(
+                "{}:{}",
+                check_name(&self.utf8(n.name_index)),
+                self.utf8(n.descriptor_index)
+            ),
+            ConstantInfo::MethodHandle(h) => format!(
+                "{} {}",
+                reference_kind_name(h.reference_kind),
+                self.string_value(h.reference_index)
+            ),
+            ConstantInfo::MethodType(t) => self.string_value(t.descriptor_index),
+            ConstantInfo::Dynamic(d) => format!(
+                "#{}:{}",
+                d.bootstrap_method_attr_index,
+                self.string_value(d.name_and_type_index)
+            ),
+            ConstantInfo::InvokeDynamic(d) => format!(
+                "#{}:{}",
+                d.bootstrap_method_attr_index,
+                self.string_value(d.name_and_type_index)
+            ),
+            ConstantInfo::Module(m) => check_name(&self.utf8(m.name_index)),
+            ConstantInfo::Package(p) => check_name(&self.utf8(p.name_index)),
+            ConstantInfo::Unusable => format!("#{}", index),
+        }
+    }
+
+    fn ref_value(&self, class_index: u16, name_and_type_index: u16) -> String {
+        format!(
+            "{}.{}",
+            self.string_value(class_index),
+            self.string_value(name_and_type_index)
+        )
+    }
+
+    /// A constant with its kind, such as `String Hello` or `Method java/lang/Object.hashCode:()I`.
+    /// Member references to the class being disassembled leave out the class name.
+    pub(super) fn constant(&self, index: u16) -> String {
+        let constant = match self.pool.get(index) {
+            Ok(constant) => constant,
+            Err(_) => return format!("#{}", index),
+        };
+        let value_index = match constant {
+            ConstantInfo::FieldRef(r) if r.class_index == self.class_file.this_class => {
+                r.name_and_type_index
+            }
+            ConstantInfo::MethodRef(r) if r.class_index == self.class_file.this_class => {
+                r.name_and_type_index
+            }
+            ConstantInfo::InterfaceMethodRef(r) if r.class_index == self.class_file.this_class => {
+                r.name_and_type_index
+            }
+            _ => index,
+        };
+        format!("{} {}", tag_name(constant), self.string_value(value_index))
+    }
+}
+
+/// The constant kind as shown in the constant pool listing.
+fn pool_tag_name(constant: &ConstantInfo) -> &'static str {
+    match constant {
+        ConstantInfo::Utf8(_) => "Utf8",
+        ConstantInfo::Integer(_) => "Integer",
+        ConstantInfo::Float(_) => "Float",
+        ConstantInfo::Long(_) => "Long",
+        ConstantInfo::Double(_) => "Double",
+        ConstantInfo::Class(_) => "Class",
+        ConstantInfo::String(_) => "String",
+        ConstantInfo::FieldRef(_) => "Fieldref",
+        ConstantInfo::MethodRef(_) => "Methodref",
+        ConstantInfo::InterfaceMethodRef(_) => "InterfaceMethodref",
+        ConstantInfo::NameAndType(_) => "NameAndType",
+        ConstantInfo::MethodHandle(_) => "MethodHandle",
+        ConstantInfo::MethodType(_) => "MethodType",
+        ConstantInfo::Dynamic(_) => "Dynamic",
+        ConstantInfo::InvokeDynamic(_) => "InvokeDynamic",
+        ConstantInfo::Module(_) => "Module",
+        ConstantInfo::Package(_) => "Package",
+        ConstantInfo::Unusable => "Unusable",
+    }
+}
+
+/// The constant kind as shown in comments.
+fn tag_name(constant: &ConstantInfo) -> &'static str {
+    match constant {
+        ConstantInfo::Utf8(_) => "Utf8",
+        ConstantInfo::Integer(_) => "int",
+        ConstantInfo::Float(_) => "float",
+        ConstantInfo::Long(_) => "long",
+        ConstantInfo::Double(_) => "double",
+        ConstantInfo::Class(_) => "class",
+        ConstantInfo::String(_) => "String",
+        ConstantInfo::FieldRef(_) => "Field",
+        ConstantInfo::MethodRef(_) => "Method",
+        ConstantInfo::InterfaceMethodRef(_) => "InterfaceMethod",
+        ConstantInfo::NameAndType(_) => "NameAndType",
+        ConstantInfo::MethodHandle(_) => "MethodHandle",
+        ConstantInfo::MethodType(_) => "MethodType",
+        ConstantInfo::Dynamic(_) => "Dynamic",
+        ConstantInfo::InvokeDynamic(_) => "InvokeDynamic",
+        ConstantInfo::Module(_) => "Module",
+        ConstantInfo::Package(_) => "Package",
+        ConstantInfo::Unusable => "Unusable",
+    }
+}
+
+pub(super) fn reference_kind_name(kind: u8) -> String {
+    match kind {
+        1 => "REF_getField".to_string(),
+        2 => "REF_getStatic".to_string(),
+        3 => "REF_putField".to_string(),
+        4 => "REF_putStatic".to_string(),
+        5 => "REF_invokeVirtual".to_string(),
+        6 => "REF_invokeStatic".to_string(),
+        7 => "REF_invokeSpecial".to_string(),
+        8 => "REF_newInvokeSpecial".to_string(),
+        9 => "REF_invokeInterface".to_string(),
+        _ => format!("{}", kind),
+    }
+}
+
+/// Escape a string the way javap shows Utf8 constants.
+pub(super) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote names that are not made of Java identifiers separated by `/`, such as `<init>` or
+/// array class names.
+pub(super) fn check_name(name: &str) -> String {
+    let mut previous = '/';
+    for c in name.chars() {
+        let valid = if previous == '/' {
+            c.is_alphabetic() || c == '$' || c == '_'
+        } else {
+            c == '/' || c.is_alphanumeric() || c == '$' || c == '_'
+        };
+        if !valid {
+            return format!("\"{}\"", add_escapes(name));
+        }
+        previous = c;
+    }
+    if name.is_empty() {
+        "\"\"".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn add_escapes(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Format a double the way `Double.toString` does.
+pub(super) fn java_double(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        format!("{:?}", value)
+    } else {
+        java_scientific(format!("{:e}", value))
+    }
+}
+
+/// Format a float the way `Float.toString` does.
+pub(super) fn java_float(value: f32) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        format!("{:?}", value)
+    } else {
+        java_scientific(format!("{:e}", value))
+    }
+}
+
+/// Turn Rust's `1.5e-7` into Java's `1.5E-7`, and `1e10` into `1.0E10`.
+fn java_scientific(formatted: String) -> String {
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    if mantissa.contains('.') {
+        format!("{}E{}", mantissa, exponent)
+    } else {
+        format!("{}.0E{}", mantissa, exponent)
+    }
+}
@@ -0,0 +1,542 @@
+//! A textual dump of a class file in the layout of `javap -v -p`.
+//!
+//! The output is meant to be diffed against javap: apart from the first lines describing the
+//! file on disk (path, modification time and checksum) it prints the same header, constant
+//! pool, members and attributes.
+
+mod attributes;
+mod code;
+mod constants;
+mod printer;
+
+use std::fmt;
+
+use crate::attribute_info::{
+    AttributeInfo, InnerClassAccessFlags, TypedAttribute, typed_attribute_parser,
+};
+use crate::constant_info::ConstantPool;
+use crate::descriptor::{FieldType, MethodDescriptor};
+use crate::field_info::{FieldAccessFlags, FieldInfo};
+use crate::method_info::{MethodAccessFlags, MethodInfo};
+use crate::signature::{
+    ClassSignature, FieldSignature, JavaTypeSignature, MethodSignature, ReferenceTypeSignature,
+    SimpleClassTypeSignature, TypeArgument, TypeParameter,
+};
+use crate::types::{ClassAccessFlags, ClassFile};
+
+use self::printer::Printer;
+
+pub(crate) use self::code::mnemonic;
+
+/// Disassemble a class file into the text `javap -v -p` would print for it.
+///
+/// ```rust
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/HelloWorld").unwrap();
+/// let text = classfile_parser::disassembler::disassemble(&class_file);
+/// assert!(text.contains("public static void main(java.lang.String[]);"));
+/// assert!(text.contains("invokevirtual #15                 // Method java/io/PrintStream.println:(Ljava/lang/String;)V"));
+/// ```
+pub fn disassemble(class_file: &ClassFile) -> String {
+    let mut writer = ClassWriter {
+        class_file,
+        pool: class_file.constant_pool(),
+        method: None,
+        p: Printer::default(),
+    };
+    writer.write_class();
+    writer.p.finish()
+}
+
+/// Formats the class file as [`disassemble`] does.
+impl fmt::Display for ClassFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&disassemble(self))
+    }
+}
+
+const CLASS_FLAGS: &[(u16, &str)] = &[
+    (ClassAccessFlags::PUBLIC.bits(), "ACC_PUBLIC"),
+    (ClassAccessFlags::FINAL.bits(), "ACC_FINAL"),
+    (ClassAccessFlags::SUPER.bits(), "ACC_SUPER"),
+    (ClassAccessFlags::INTERFACE.bits(), "ACC_INTERFACE"),
+    (ClassAccessFlags::ABSTRACT.bits(), "ACC_ABSTRACT"),
+    (ClassAccessFlags::SYNTHETIC.bits(), "ACC_SYNTHETIC"),
+    (ClassAccessFlags::ANNOTATION.bits(), "ACC_ANNOTATION"),
+    (ClassAccessFlags::ENUM.bits(), "ACC_ENUM"),
+    (ClassAccessFlags::MODULE.bits(), "ACC_MODULE"),
+];
+
+const FIELD_FLAGS: &[(u16, &str)] = &[
+    (FieldAccessFlags::PUBLIC.bits(), "ACC_PUBLIC"),
+    (FieldAccessFlags::PRIVATE.bits(), "ACC_PRIVATE"),
+    (FieldAccessFlags::PROTECTED.bits(), "ACC_PROTECTED"),
+    (FieldAccessFlags::STATIC.bits(), "ACC_STATIC"),
+    (FieldAccessFlags::FINAL.bits(), "ACC_FINAL"),
+    (FieldAccessFlags::VOLATILE.bits(), "ACC_VOLATILE"),
+    (FieldAccessFlags::TRANSIENT.bits(), "ACC_TRANSIENT"),
+    (FieldAccessFlags::SYNTHETIC.bits(), "ACC_SYNTHETIC"),
+    (FieldAccessFlags::ENUM.bits(), "ACC_ENUM"),
+];
+
+const METHOD_FLAGS: &[(u16, &str)] = &[
+    (MethodAccessFlags::PUBLIC.bits(), "ACC_PUBLIC"),
+    (MethodAccessFlags::PRIVATE.bits(), "ACC_PRIVATE"),
+    (MethodAccessFlags::PROTECTED.bits(), "ACC_PROTECTED"),
+    (MethodAccessFlags::STATIC.bits(), "ACC_STATIC"),
+    (MethodAccessFlags::FINAL.bits(), "ACC_FINAL"),
+    (MethodAccessFlags::SYNCHRONIZED.bits(), "ACC_SYNCHRONIZED"),
+    (MethodAccessFlags::BRIDGE.bits(), "ACC_BRIDGE"),
+    (MethodAccessFlags::VARARGS.bits(), "ACC_VARARGS"),
+    (MethodAccessFlags::NATIVE.bits(), "ACC_NATIVE"),
+    (MethodAccessFlags::ABSTRACT.bits(), "ACC_ABSTRACT"),
+    (MethodAccessFlags::STRICT.bits(), "ACC_STRICT"),
+    (MethodAccessFlags::SYNTHETIC.bits(), "ACC_SYNTHETIC"),
+];
+
+const CLASS_MODIFIERS: &[(u16, &str)] = &[
+    (ClassAccessFlags::PUBLIC.bits(), "public"),
+    (ClassAccessFlags::FINAL.bits(), "final"),
+    (ClassAccessFlags::ABSTRACT.bits(), "abstract"),
+];
+
+const INNER_CLASS_MODIFIERS: &[(u16, &str)] = &[
+    (InnerClassAccessFlags::PUBLIC.bits(), "public"),
+    (InnerClassAccessFlags::PRIVATE.bits(), "private"),
+    (InnerClassAccessFlags::PROTECTED.bits(), "protected"),
+    (InnerClassAccessFlags::STATIC.bits(), "static"),
+    (InnerClassAccessFlags::FINAL.bits(), "final"),
+    (InnerClassAccessFlags::ABSTRACT.bits(), "abstract"),
+];
+
+const FIELD_MODIFIERS: &[(u16, &str)] = &[
+    (FieldAccessFlags::PUBLIC.bits(), "public"),
+    (FieldAccessFlags::PRIVATE.bits(), "private"),
+    (FieldAccessFlags::PROTECTED.bits(), "protected"),
+    (FieldAccessFlags::STATIC.bits(), "static"),
+    (FieldAccessFlags::FINAL.bits(), "final"),
+    (FieldAccessFlags::VOLATILE.bits(), "volatile"),
+    (FieldAccessFlags::TRANSIENT.bits(), "transient"),
+];
+
+const METHOD_MODIFIERS: &[(u16, &str)] = &[
+    (MethodAccessFlags::PUBLIC.bits(), "public"),
+    (MethodAccessFlags::PRIVATE.bits(), "private"),
+    (MethodAccessFlags::PROTECTED.bits(), "protected"),
+    (MethodAccessFlags::STATIC.bits(), "static"),
+    (MethodAccessFlags::FINAL.bits(), "final"),
+    (MethodAccessFlags::SYNCHRONIZED.bits(), "synchronized"),
+    (MethodAccessFlags::NATIVE.bits(), "native"),
+    (MethodAccessFlags::ABSTRACT.bits(), "abstract"),
+    (MethodAccessFlags::STRICT.bits(), "strictfp"),
+];
+
+/// The names from `table` whose bits are set in `flags`, in table order.
+fn names(flags: u16, table: &[(u16, &'static str)]) -> Vec<&'static str> {
+    table
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+/// Modifiers followed by a space each, as they prefix a declaration.
+fn modifiers(flags: u16, table: &[(u16, &'static str)]) -> String {
+    names(flags, table)
+        .iter()
+        .map(|name| format!("{} ", name))
+        .collect()
+}
+
+fn java_name(internal_name: &str) -> String {
+    internal_name.replace('/', ".")
+}
+
+/// Type parameters as javap prints them; `verbose` keeps `java.lang.Object` class bounds.
+fn type_parameters(parameters: &[TypeParameter], verbose: bool) -> String {
+    if parameters.is_empty() {
+        return String::new();
+    }
+    let parameters: Vec<_> = parameters
+        .iter()
+        .map(|parameter| {
+            let mut java = parameter.name.clone();
+            let mut separator = " extends ";
+            if let Some(bound) = &parameter.class_bound {
+                let bound = bound.to_java();
+                if verbose || bound != "java.lang.Object" {
+                    java.push_str(separator);
+                    java.push_str(&bound);
+                    separator = " & ";
+                }
+            }
+            for bound in &parameter.interface_bounds {
+                java.push_str(separator);
+                java.push_str(&bound.to_java());
+                separator = " & ";
+            }
+            java
+        })
+        .collect();
+    format!("<{}>", parameters.join(", "))
+}
+
+/// A reference type with internal class names, the way javap prints generic throws clauses.
+fn internal_type_name(signature: &ReferenceTypeSignature) -> String {
+    fn simple(class: &SimpleClassTypeSignature) -> String {
+        if class.type_arguments.is_empty() {
+            return class.name.clone();
+        }
+        let arguments: Vec<_> = class
+            .type_arguments
+            .iter()
+            .map(|argument| match argument {
+                TypeArgument::Any => "?".to_string(),
+                TypeArgument::Exact(bound) => internal_type_name(bound),
+                TypeArgument::Extends(bound) => format!("? extends {}", internal_type_name(bound)),
+                TypeArgument::Super(bound) => format!("? super {}", internal_type_name(bound)),
+            })
+            .collect();
+        format!("{}<{}>", class.name, arguments.join(", "))
+    }
+    match signature {
+        ReferenceTypeSignature::Class(class) => {
+            let mut name = String::new();
+            if !class.package.is_empty() {
+                name.push_str(&class.package);
+                name.push('/');
+            }
+            name.push_str(&simple(&class.class));
+            for inner in &class.suffix {
+                name.push('.');
+                name.push_str(&simple(inner));
+            }
+            name
+        }
+        ReferenceTypeSignature::TypeVariable(name) => name.clone(),
+        ReferenceTypeSignature::Array(component) => match component.as_ref() {
+            JavaTypeSignature::Base(base) => format!("{}[]", base.java_name()),
+            JavaTypeSignature::Reference(reference) => {
+                format!("{}[]", internal_type_name(reference))
+            }
+        },
+    }
+}
+
+struct ClassWriter<'a> {
+    class_file: &'a ClassFile,
+    pool: ConstantPool<'a>,
+    /// The method whose attributes are being written, if any.
+    method: Option<&'a MethodInfo>,
+    p: Printer,
+}
+
+impl<'a> ClassWriter<'a> {
+    fn is_interface(&self) -> bool {
+        self.class_file
+            .access_flags
+            .contains(ClassAccessFlags::INTERFACE)
+    }
+
+    fn typed_attributes(&self, attributes: &[AttributeInfo]) -> Vec<Option<TypedAttribute>> {
+        attributes
+            .iter()
+            .map(|attribute| typed_attribute_parser(attribute, &self.class_file.const_pool).ok())
+            .collect()
+    }
+
+    fn signature(&self, attributes: &[AttributeInfo]) -> Option<String> {
+        self.typed_attributes(attributes)
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                Some(TypedAttribute::Signature(signature)) => {
+                    Some(self.utf8(signature.signature_index))
+                }
+                _ => None,
+            })
+    }
+
+    fn write_class(&mut self) {
+        let class_file = self.class_file;
+        let attributes = self.typed_attributes(&class_file.attributes);
+        for attribute in &attributes {
+            if let Some(TypedAttribute::SourceFile(source_file)) = attribute {
+                let name = self.utf8(source_file.sourcefile_index);
+                self.p.println(format!("  Compiled from \"{}\"", name));
+            }
+        }
+
+        self.write_class_declaration(&attributes);
+
+        let flags = class_file.access_flags.bits();
+        self.p.indent(1);
+        self.p
+            .println(format!("minor version: {}", class_file.minor_version));
+        self.p
+            .println(format!("major version: {}", class_file.major_version));
+        self.write_flags(flags, CLASS_FLAGS);
+        self.p
+            .print(format!("this_class: #{}", class_file.this_class));
+        if class_file.this_class != 0 {
+            self.p.tab();
+            self.p
+                .print(format!("// {}", self.string_value(class_file.this_class)));
+        }
+        self.p.println("");
+        self.p
+            .print(format!("super_class: #{}", class_file.super_class));
+        if class_file.super_class != 0 {
+            self.p.tab();
+            self.p
+                .print(format!("// {}", self.string_value(class_file.super_class)));
+        }
+        self.p.println("");
+        self.p.println(format!(
+            "interfaces: {}, fields: {}, methods: {}, attributes: {}",
+            class_file.interfaces.len(),
+            class_file.fields.len(),
+            class_file.methods.len(),
+            class_file.attributes.len()
+        ));
+        self.p.indent(-1);
+        self.write_constant_pool();
+
+        self.p.println("{");
+        self.p.indent(1);
+        for field in &class_file.fields {
+            self.write_field(field);
+        }
+        for (i, method) in class_file.methods.iter().enumerate() {
+            if i > 0 {
+                self.p.println("");
+            }
+            self.write_method(method);
+        }
+        self.p.indent(-1);
+        self.p.println("}");
+        self.write_attributes(&class_file.attributes);
+    }
+
+    fn write_class_declaration(&mut self, attributes: &[Option<TypedAttribute>]) {
+        let class_file = self.class_file;
+        let mut flags = class_file.access_flags.bits();
+        if self.is_interface() {
+            flags &= !ClassAccessFlags::ABSTRACT.bits();
+        }
+        self.p.print(modifiers(flags, CLASS_MODIFIERS));
+
+        let name = java_name(&self.class_name(class_file.this_class));
+        if class_file.access_flags.contains(ClassAccessFlags::MODULE) {
+            let module = attributes.iter().find_map(|attribute| match attribute {
+                Some(TypedAttribute::Module(module)) => Some(module),
+                _ => None,
+            });
+            match module {
+                Some(module) => {
+                    if module.module_flags & 0x0020 != 0 {
+                        self.p.print("open ");
+                    }
+                    let name = self.module_name(module.module_name_index);
+                    self.p.print(format!("module {}", java_name(&name)));
+                    if module.module_version_index != 0 {
+                        let version = self.utf8(module.module_version_index);
+                        self.p.print(format!("@{}", version));
+                    }
+                }
+                None => self.p.print(format!("class {}", name)),
+            }
+        } else if self.is_interface() {
+            self.p.print(format!("interface {}", name));
+        } else {
+            self.p.print(format!("class {}", name));
+        }
+
+        let signature = self
+            .signature(&class_file.attributes)
+            .and_then(|signature| signature.parse::<ClassSignature>().ok());
+        match signature {
+            None => {
+                if !self.is_interface() && class_file.super_class != 0 {
+                    let super_name = java_name(&self.class_name(class_file.super_class));
+                    if super_name != "java.lang.Object" {
+                        self.p.print(format!(" extends {}", super_name));
+                    }
+                }
+                for (i, &interface) in class_file.interfaces.iter().enumerate() {
+                    let prefix = match (i, self.is_interface()) {
+                        (0, false) => " implements ",
+                        (0, true) => " extends ",
+                        _ => ",",
+                    };
+                    let interface = java_name(&self.class_name(interface));
+                    self.p.print(format!("{}{}", prefix, interface));
+                }
+            }
+            Some(signature) => {
+                self.p
+                    .print(type_parameters(&signature.type_parameters, true));
+                let interfaces: Vec<_> = signature
+                    .interfaces
+                    .iter()
+                    .map(|interface| interface.to_java())
+                    .collect();
+                if self.is_interface() {
+                    if !interfaces.is_empty() {
+                        self.p.print(format!(" extends {}", interfaces.join(", ")));
+                    }
+                } else {
+                    self.p
+                        .print(format!(" extends {}", signature.superclass.to_java()));
+                    if !interfaces.is_empty() {
+                        self.p
+                            .print(format!(" implements {}", interfaces.join(", ")));
+                    }
+                }
+            }
+        }
+        self.p.println("");
+    }
+
+    fn class_name(&self, index: u16) -> String {
+        self.pool
+            .get_class_name(index)
+            .map(|name| name.into_owned())
+            .unwrap_or_else(|_| format!("#{}", index))
+    }
+
+    fn module_name(&self, index: u16) -> String {
+        self.pool
+            .get_module_name(index)
+            .map(|name| name.into_owned())
+            .unwrap_or_else(|_| format!("#{}", index))
+    }
+
+    fn write_flags(&mut self, flags: u16, table: &[(u16, &'static str)]) {
+        self.p.println(format!(
+            "flags: (0x{:04x}) {}",
+            flags,
+            names(flags, table).join(", ")
+        ));
+    }
+
+    /// The Java type of a field or record component, generic if it has a Signature.
+    fn field_type(&self, descriptor: &str, attributes: &[AttributeInfo]) -> String {
+        self.signature(attributes)
+            .and_then(|signature| signature.parse::<FieldSignature>().ok())
+            .map(|signature| signature.to_java())
+            .or_else(|| {
+                descriptor
+                    .parse::<FieldType>()
+                    .ok()
+                    .map(|field_type| field_type.to_java())
+            })
+            .unwrap_or_else(|| descriptor.to_string())
+    }
+
+    fn write_field(&mut self, field: &'a FieldInfo) {
+        let flags = field.access_flags.bits();
+        self.p.print(modifiers(flags, FIELD_MODIFIERS));
+        let descriptor = self.utf8(field.descriptor_index);
+        let field_type = self.field_type(&descriptor, &field.attributes);
+        let name = self.utf8(field.name_index);
+        self.p.println(format!("{} {};", field_type, name));
+
+        self.p.indent(1);
+        self.p.println(format!("descriptor: {}", descriptor));
+        self.write_flags(flags, FIELD_FLAGS);
+        self.write_attributes(&field.attributes);
+        self.p.indent(-1);
+        self.p.println("");
+    }
+
+    fn write_method(&mut self, method: &'a MethodInfo) {
+        let class_file = self.class_file;
+        let flags = method.access_flags.bits();
+        let name = self.utf8(method.name_index);
+        let descriptor = self.utf8(method.descriptor_index);
+
+        let mut declaration = modifiers(flags, METHOD_MODIFIERS);
+        // Non-abstract instance methods of interfaces are default methods from Java 8 on
+        if self.is_interface()
+            && !method.access_flags.contains(MethodAccessFlags::ABSTRACT)
+            && name != "<clinit>"
+            && class_file.major_version >= 52
+            && !method
+                .access_flags
+                .intersects(MethodAccessFlags::STATIC | MethodAccessFlags::PRIVATE)
+        {
+            declaration.push_str("default ");
+        }
+
+        let signature = self
+            .signature(&method.attributes)
+            .and_then(|signature| signature.parse::<MethodSignature>().ok());
+        let (params, return_type) = match &signature {
+            Some(signature) => {
+                declaration.push_str(&type_parameters(&signature.type_parameters, false));
+                if !signature.type_parameters.is_empty() {
+                    declaration.push(' ');
+                }
+                let params: Vec<_> = signature
+                    .params
+                    .iter()
+                    .map(JavaTypeSignature::to_java)
+                    .collect();
+                (params.join(", "), signature.result.to_java())
+            }
+            None => match descriptor.parse::<MethodDescriptor>() {
+                Ok(descriptor) => {
+                    let params: Vec<_> = descriptor.params.iter().map(FieldType::to_java).collect();
+                    (params.join(", "), descriptor.return_type.to_java())
+                }
+                Err(_) => (descriptor.clone(), String::new()),
+            },
+        };
+        let mut params = format!("({})", params);
+        if method.access_flags.contains(MethodAccessFlags::VARARGS)
+            && let Some(i) = params.rfind("[]").filter(|&i| i > 0)
+        {
+            params.replace_range(i..i + 2, "...");
+        }
+
+        match name.as_str() {
+            "<init>" => {
+                let class_name = java_name(&self.class_name(class_file.this_class));
+                declaration.push_str(&class_name);
+                declaration.push_str(&params);
+            }
+            "<clinit>" => declaration.push_str("{}"),
+            _ => declaration.push_str(&format!("{} {}{}", return_type, name, params)),
+        }
+
+        let exceptions = self
+            .typed_attributes(&method.attributes)
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                Some(TypedAttribute::Exceptions(exceptions)) => Some(exceptions),
+                _ => None,
+            });
+        if let Some(exceptions) = exceptions {
+            let thrown: Vec<_> = match &signature {
+                Some(signature) if !signature.throws.is_empty() => {
+                    signature.throws.iter().map(internal_type_name).collect()
+                }
+                _ => exceptions
+                    .exception_table
+                    .iter()
+                    .map(|&index| java_name(&self.class_name(index)))
+                    .collect(),
+            };
+            declaration.push_str(" throws ");
+            declaration.push_str(&thrown.join(", "));
+        }
+        self.p.println(format!("{};", declaration));
+
+        self.p.indent(1);
+        self.p.println(format!("descriptor: {}", descriptor));
+        self.write_flags(flags, METHOD_FLAGS);
+        self.method = Some(method);
+        self.write_attributes(&method.attributes);
+        self.method = None;
+        self.p.indent(-1);
+    }
+}
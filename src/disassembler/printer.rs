@@ -0,0 +1,62 @@
+/// Width of one indentation level.
+const INDENT_WIDTH: usize = 2;
+/// Column that `tab` pads to, not counting indentation.
+const TAB_COLUMN: usize = 40;
+
+/// A line based writer with javap's indentation and comment alignment rules. A line is
+/// indented by the level in effect when its first text was printed, and trailing
+/// whitespace is trimmed.
+#[derive(Default)]
+pub(crate) struct Printer {
+    out: String,
+    line: String,
+    line_indent: usize,
+    indent: usize,
+}
+
+impl Printer {
+    pub(crate) fn print(&mut self, s: impl AsRef<str>) {
+        for (i, part) in s.as_ref().split('\n').enumerate() {
+            if i > 0 {
+                self.end_line();
+            }
+            if self.line.is_empty() {
+                self.line_indent = self.indent;
+            }
+            self.line.push_str(part);
+        }
+    }
+
+    pub(crate) fn println(&mut self, s: impl AsRef<str>) {
+        self.print(s);
+        self.end_line();
+    }
+
+    /// Pad the current line so the next output starts at the comment column.
+    pub(crate) fn tab(&mut self) {
+        let padding = TAB_COLUMN.saturating_sub(self.line.chars().count()).max(1);
+        self.print(" ".repeat(padding));
+    }
+
+    pub(crate) fn indent(&mut self, delta: isize) {
+        self.indent = self.indent.saturating_add_signed(delta);
+    }
+
+    fn end_line(&mut self) {
+        let line = self.line.trim_end();
+        if !line.is_empty() {
+            self.out
+                .push_str(&" ".repeat(self.line_indent * INDENT_WIDTH));
+            self.out.push_str(line);
+        }
+        self.out.push('\n');
+        self.line.clear();
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        if !self.line.is_empty() {
+            self.end_line();
+        }
+        self.out
+    }
+}
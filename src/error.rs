@@ -0,0 +1,254 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::constant_info::ConstantPoolError;
+use crate::descriptor::DescriptorError;
+use crate::signature::SignatureError;
+
+/// A structure within the class file, used to describe where parsing failed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseContext {
+    /// The magic number and version information.
+    Header,
+    /// A constant pool entry, by its 1-based constant pool index.
+    Constant(u16),
+    /// The access flags, this/super class and interfaces table.
+    ClassInfo,
+    /// A field, by its position in the fields table.
+    Field(u16),
+    /// A method, by its position in the methods table.
+    Method(u16),
+    /// An attribute, by name if it could be resolved from the constant pool, otherwise by
+    /// its name index such as `#12`.
+    Attribute(String),
+    /// The bytecode array of a Code attribute.
+    Code,
+}
+
+impl fmt::Display for ParseContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseContext::Header => write!(f, "header"),
+            ParseContext::Constant(index) => write!(f, "constant #{}", index),
+            ParseContext::ClassInfo => write!(f, "class info"),
+            ParseContext::Field(index) => write!(f, "field #{}", index),
+            ParseContext::Method(index) => write!(f, "method #{}", index),
+            ParseContext::Attribute(name) => write!(f, "attribute {}", name),
+            ParseContext::Code => write!(f, "code"),
+        }
+    }
+}
+
+/// What went wrong while parsing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseErrorCause {
+    /// The file does not start with `0xCAFEBABE`.
+    BadMagic,
+    /// The input ended before the structure being parsed was complete.
+    Truncated,
+    /// A constant pool entry has a tag this parser does not know.
+    UnknownConstantTag(u8),
+    /// An attribute name index does not point to a Utf8 constant.
+    BadAttributeName(u16),
+    /// The bytecode contains a byte that is not a valid opcode.
+    BadOpcode(u8),
+    /// Any other structural problem, such as an unknown tag inside an attribute.
+    Invalid,
+}
+
+impl fmt::Display for ParseErrorCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorCause::BadMagic => write!(f, "bad magic number"),
+            ParseErrorCause::Truncated => write!(f, "unexpected end of input"),
+            ParseErrorCause::UnknownConstantTag(tag) => {
+                write!(f, "unknown constant pool tag {}", tag)
+            }
+            ParseErrorCause::BadAttributeName(index) => {
+                write!(f, "attribute name index {} is not a Utf8 constant", index)
+            }
+            ParseErrorCause::BadOpcode(opcode) => write!(f, "invalid opcode 0x{:02x}", opcode),
+            ParseErrorCause::Invalid => write!(f, "invalid data"),
+        }
+    }
+}
+
+/// An error returned when a class file cannot be read or parsed.
+#[derive(Debug)]
+pub enum ClassParseError {
+    /// The class file could not be read.
+    Io(io::Error),
+    /// The class file bytes are not a valid class file.
+    Malformed {
+        /// Byte offset of the failure, relative to the start of the class file, or to the
+        /// start of the attribute body when decoding a single attribute.
+        offset: usize,
+        /// The structures being parsed, outermost first.
+        context: Vec<ParseContext>,
+        cause: ParseErrorCause,
+    },
+    /// The class file parsed, but a constant pool index it refers to could not be resolved.
+    ConstantPool(ConstantPoolError),
+    /// The class file parsed, but a descriptor in its constant pool is malformed.
+    Descriptor(DescriptorError),
+    /// The class file parsed, but a generic signature in its constant pool is malformed.
+    Signature(SignatureError),
+}
+
+impl ClassParseError {
+    pub(crate) fn malformed(offset: usize, cause: ParseErrorCause) -> Self {
+        ClassParseError::Malformed {
+            offset,
+            context: Vec::new(),
+            cause,
+        }
+    }
+
+    /// Build an error from a nom error, given the complete input the parser was started on.
+    pub(crate) fn from_nom(whole: &[u8], error: nom::Err<nom::error::Error<&[u8]>>) -> Self {
+        use nom::error::ErrorKind;
+        match error {
+            nom::Err::Incomplete(_) => Self::malformed(whole.len(), ParseErrorCause::Truncated),
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                let offset = whole.len().saturating_sub(e.input.len());
+                let cause = match e.code {
+                    ErrorKind::Eof => ParseErrorCause::Truncated,
+                    _ if e.input.is_empty() => ParseErrorCause::Truncated,
+                    _ => ParseErrorCause::Invalid,
+                };
+                Self::malformed(offset, cause)
+            }
+        }
+    }
+
+    /// Push an outer structure onto the context of a `Malformed` error, shifting the
+    /// offset by `base` bytes.
+    pub(crate) fn within(self, base: usize, outer: ParseContext) -> Self {
+        match self.offset_by(base) {
+            ClassParseError::Malformed {
+                offset,
+                mut context,
+                cause,
+            } => {
+                context.insert(0, outer);
+                ClassParseError::Malformed {
+                    offset,
+                    context,
+                    cause,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Shift the offset of a `Malformed` error by `base` bytes.
+    pub(crate) fn offset_by(self, base: usize) -> Self {
+        match self {
+            ClassParseError::Malformed {
+                offset,
+                context,
+                cause,
+            } => ClassParseError::Malformed {
+                offset: base + offset,
+                context,
+                cause,
+            },
+            other => other,
+        }
+    }
+
+    /// The byte offset of the failure, `None` unless the error is `Malformed`.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ClassParseError::Malformed { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// The structures being parsed when the failure occurred, outermost first.
+    pub fn context(&self) -> &[ParseContext] {
+        match self {
+            ClassParseError::Malformed { context, .. } => context,
+            _ => &[],
+        }
+    }
+
+    /// The cause of the failure, `None` unless the error is `Malformed`.
+    pub fn cause(&self) -> Option<&ParseErrorCause> {
+        match self {
+            ClassParseError::Malformed { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ClassParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassParseError::Io(e) => write!(f, "unable to read class file: {}", e),
+            ClassParseError::Malformed {
+                offset,
+                context,
+                cause,
+            } => {
+                write!(f, "{} at offset {}", cause, offset)?;
+                if !context.is_empty() {
+                    write!(f, " in ")?;
+                    for (i, c) in context.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, " > ")?;
+                        }
+                        write!(f, "{}", c)?;
+                    }
+                }
+                Ok(())
+            }
+            ClassParseError::ConstantPool(e) => write!(f, "{}", e),
+            ClassParseError::Descriptor(e) => write!(f, "{}", e),
+            ClassParseError::Signature(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ClassParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClassParseError::Io(e) => Some(e),
+            ClassParseError::Malformed { .. } => None,
+            ClassParseError::ConstantPool(e) => Some(e),
+            ClassParseError::Descriptor(e) => Some(e),
+            ClassParseError::Signature(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ClassParseError {
+    fn from(e: io::Error) -> Self {
+        ClassParseError::Io(e)
+    }
+}
+
+impl From<ConstantPoolError> for ClassParseError {
+    fn from(e: ConstantPoolError) -> Self {
+        ClassParseError::ConstantPool(e)
+    }
+}
+
+impl From<DescriptorError> for ClassParseError {
+    fn from(e: DescriptorError) -> Self {
+        ClassParseError::Descriptor(e)
+    }
+}
+
+impl From<SignatureError> for ClassParseError {
+    fn from(e: SignatureError) -> Self {
+        ClassParseError::Signature(e)
+    }
+}
+
+impl From<ClassParseError> for String {
+    fn from(e: ClassParseError) -> Self {
+        e.to_string()
+    }
+}
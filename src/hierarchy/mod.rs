@@ -0,0 +1,6 @@
+//! Subtype queries over a set of classes, built from their `this_class`, `super_class` and
+//! `interfaces`.
+
+mod types;
+
+pub use self::types::*;
@@ -0,0 +1,341 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+use crate::borrowed::ClassHeader;
+use crate::constant_info::ConstantPoolError;
+use crate::types::{ClassAccessFlags, ClassFile};
+use crate::verifier::{TypeHierarchy, array_of};
+
+const OBJECT: &str = "java/lang/Object";
+/// The interfaces every array type implements.
+const ARRAY_INTERFACES: [&str; 2] = ["java/lang/Cloneable", "java/io/Serializable"];
+
+/// A class's place in the hierarchy: its name and direct supertypes, as internal names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HierarchyClass {
+    pub name: String,
+    /// `None` for `java/lang/Object` and `module-info`.
+    pub super_class: Option<String>,
+    pub interfaces: Vec<String>,
+    pub access_flags: ClassAccessFlags,
+}
+
+impl HierarchyClass {
+    pub fn from_class_file(class_file: &ClassFile) -> Result<Self, ConstantPoolError> {
+        Ok(HierarchyClass {
+            name: class_file.this_class_name()?.into_owned(),
+            super_class: class_file.super_class_name()?.map(|name| name.into_owned()),
+            interfaces: class_file
+                .interface_names()?
+                .into_iter()
+                .map(|name| name.into_owned())
+                .collect(),
+            access_flags: class_file.access_flags,
+        })
+    }
+
+    pub fn from_header(header: &ClassHeader) -> Result<Self, ConstantPoolError> {
+        Ok(HierarchyClass {
+            name: header.this_class_name()?.into_owned(),
+            super_class: header.super_class_name()?.map(|name| name.into_owned()),
+            interfaces: header
+                .interface_names()?
+                .into_iter()
+                .map(|name| name.into_owned())
+                .collect(),
+            access_flags: header.access_flags,
+        })
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags.contains(ClassAccessFlags::INTERFACE)
+    }
+
+    /// The superclass followed by the interfaces.
+    fn supertypes(&self) -> impl Iterator<Item = &str> {
+        self.super_class
+            .iter()
+            .chain(&self.interfaces)
+            .map(String::as_str)
+    }
+}
+
+/// The component of an array type in internal form, such as `java/lang/String` for
+/// `[Ljava/lang/String;` or `[I` for `[[I`, `None` if it is a primitive type.
+fn array_component(array: &str) -> Option<&str> {
+    match array.strip_prefix('[')? {
+        component if component.starts_with('[') => Some(component),
+        component => component.strip_prefix('L')?.strip_suffix(';'),
+    }
+}
+
+/// The classes of a program and the subtype relation between them.
+///
+/// Supertypes that were never added are reported by `missing`, and are treated as having no
+/// supertypes of their own. Every class and interface is a subtype of `java/lang/Object`,
+/// whether or not it was added.
+///
+/// ```rust
+/// use classfile_parser::hierarchy::ClassHierarchy;
+///
+/// let classes = ["Nestmates$Shape", "Nestmates$Square", "Nestmates$Circle"]
+///     .map(|name| classfile_parser::parse_class(&format!("./java-assets/compiled-classes/{}", name)).unwrap());
+/// let hierarchy = ClassHierarchy::from_classes(&classes).unwrap();
+///
+/// assert!(hierarchy.is_subtype("Nestmates$Square", "Nestmates$Shape"));
+/// assert_eq!(hierarchy.implementors("Nestmates$Shape"), ["Nestmates$Circle", "Nestmates$Square"]);
+/// assert_eq!(hierarchy.least_common_superclass("Nestmates$Square", "Nestmates$Circle"), "java/lang/Object");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClassHierarchy {
+    classes: BTreeMap<String, HierarchyClass>,
+    /// The direct subclasses, implementors and subinterfaces of each type, by name.
+    subtypes: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ClassHierarchy {
+    pub fn new() -> Self {
+        ClassHierarchy::default()
+    }
+
+    /// Build a hierarchy from a set of parsed classes.
+    pub fn from_classes<'a, I: IntoIterator<Item = &'a ClassFile>>(
+        classes: I,
+    ) -> Result<Self, ConstantPoolError> {
+        let mut hierarchy = ClassHierarchy::new();
+        for class_file in classes {
+            hierarchy.add(class_file)?;
+        }
+        Ok(hierarchy)
+    }
+
+    /// Add a parsed class. As on a classpath, the first class added with a given name is
+    /// kept and later ones are ignored; the result says whether this one was added.
+    pub fn add(&mut self, class_file: &ClassFile) -> Result<bool, ConstantPoolError> {
+        Ok(self.insert(HierarchyClass::from_class_file(class_file)?))
+    }
+
+    /// Add a class from its header, as returned by `parse_header`.
+    pub fn add_header(&mut self, header: &ClassHeader) -> Result<bool, ConstantPoolError> {
+        Ok(self.insert(HierarchyClass::from_header(header)?))
+    }
+
+    /// Add a class, unless one with the same name has already been added.
+    pub fn insert(&mut self, class: HierarchyClass) -> bool {
+        if self.classes.contains_key(&class.name) {
+            return false;
+        }
+        for supertype in class.supertypes() {
+            self.subtypes
+                .entry(supertype.to_string())
+                .or_default()
+                .insert(class.name.clone());
+        }
+        self.classes.insert(class.name.clone(), class);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.classes.contains_key(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HierarchyClass> {
+        self.classes.get(name)
+    }
+
+    /// The names of all added classes, in sorted order.
+    pub fn class_names(&self) -> impl Iterator<Item = &str> {
+        self.classes.keys().map(String::as_str)
+    }
+
+    /// The supertypes referenced by added classes that were not added themselves.
+    pub fn missing(&self) -> BTreeSet<&str> {
+        self.classes
+            .values()
+            .flat_map(HierarchyClass::supertypes)
+            .filter(|name| !self.classes.contains_key(*name))
+            .collect()
+    }
+
+    /// The cycles among the added classes, such as a class that extends its own subclass.
+    /// Each cycle lists its classes starting from the alphabetically first.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles = BTreeSet::new();
+        let mut done = HashSet::new();
+        for name in self.classes.keys() {
+            let mut path = Vec::new();
+            self.find_cycles(name, &mut path, &mut done, &mut cycles);
+        }
+        cycles.into_iter().collect()
+    }
+
+    fn find_cycles<'a>(
+        &'a self,
+        name: &'a str,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        cycles: &mut BTreeSet<Vec<String>>,
+    ) {
+        if let Some(start) = path.iter().position(|&other| other == name) {
+            let cycle = &path[start..];
+            let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+            cycles.insert(
+                cycle[first..]
+                    .iter()
+                    .chain(&cycle[..first])
+                    .map(|name| name.to_string())
+                    .collect(),
+            );
+            return;
+        }
+        let Some(class) = self.classes.get(name) else {
+            return;
+        };
+        if done.contains(name) {
+            return;
+        }
+        path.push(name);
+        for supertype in class.supertypes() {
+            self.find_cycles(supertype, path, done, cycles);
+        }
+        path.pop();
+        done.insert(name);
+    }
+
+    /// The direct subclasses, implementors and subinterfaces of `name`.
+    pub fn direct_subtypes(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.subtypes
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// The chain of superclasses of `name`, nearest first, ending at the first class that
+    /// was not added or has no superclass.
+    pub fn superclasses(&self, name: &str) -> Vec<&str> {
+        let mut superclasses: Vec<&str> = Vec::new();
+        let mut current = self.classes.get(name);
+        while let Some(super_class) = current.and_then(|class| class.super_class.as_deref()) {
+            if super_class == name || superclasses.contains(&super_class) {
+                break;
+            }
+            superclasses.push(super_class);
+            current = self.classes.get(super_class);
+        }
+        superclasses
+    }
+
+    /// Every transitive superclass and interface of `name`, nearest first.
+    pub fn supertypes(&self, name: &str) -> Vec<&str> {
+        self.walk(name, |class| {
+            self.classes
+                .get(class)
+                .into_iter()
+                .flat_map(HierarchyClass::supertypes)
+                .collect()
+        })
+    }
+
+    /// Every transitive subclass, implementor and subinterface of `name`, nearest first.
+    pub fn subtypes(&self, name: &str) -> Vec<&str> {
+        self.walk(name, |class| self.direct_subtypes(class).collect())
+    }
+
+    /// Breadth-first search from `name`, excluding `name` itself.
+    fn walk<'a>(&'a self, name: &str, next: impl Fn(&str) -> Vec<&'a str>) -> Vec<&'a str> {
+        let mut seen = HashSet::from([name]);
+        let mut found = Vec::new();
+        let mut queue = VecDeque::from(next(name));
+        while let Some(current) = queue.pop_front() {
+            if seen.insert(current) {
+                found.push(current);
+                queue.extend(next(current));
+            }
+        }
+        found
+    }
+
+    /// The classes, not interfaces, that implement the interface `name` directly or through
+    /// a superclass or subinterface, in sorted order.
+    pub fn implementors(&self, name: &str) -> Vec<&str> {
+        let mut implementors: Vec<&str> = self
+            .subtypes(name)
+            .into_iter()
+            .filter(|subtype| {
+                self.classes
+                    .get(*subtype)
+                    .is_some_and(|class| !class.is_interface())
+            })
+            .collect();
+        implementors.sort_unstable();
+        implementors
+    }
+
+    /// Whether a value of type `a` can be assigned to type `b`. Both are internal names,
+    /// or array descriptors such as `[Ljava/lang/String;`.
+    pub fn is_subtype(&self, a: &str, b: &str) -> bool {
+        if a == b {
+            return true;
+        }
+        match (a.starts_with('['), b.starts_with('[')) {
+            (true, true) => match (array_component(a), array_component(b)) {
+                (Some(a), Some(b)) => self.is_subtype(a, b),
+                _ => false,
+            },
+            (true, false) => b == OBJECT || ARRAY_INTERFACES.contains(&b),
+            (false, true) => false,
+            (false, false) => b == OBJECT || self.supertypes(a).contains(&b),
+        }
+    }
+
+    /// The most specific class that both `a` and `b` extend. As in the JVM's verifier
+    /// interfaces are not considered, so this is `java/lang/Object` if either is an
+    /// interface, or if their superclass chains only meet in classes that were not added.
+    ///
+    /// For two arrays of references this is an array of the least common superclass of their
+    /// components, and for any other mix of array types `java/lang/Object`.
+    pub fn least_common_superclass<'a>(&'a self, a: &'a str, b: &'a str) -> Cow<'a, str> {
+        match (a.starts_with('['), b.starts_with('[')) {
+            (true, true) if a == b => Cow::Borrowed(a),
+            (true, true) => match (array_component(a), array_component(b)) {
+                (Some(a), Some(b)) => Cow::Owned(array_of(&self.least_common_superclass(a, b))),
+                _ => Cow::Borrowed(OBJECT),
+            },
+            (false, false) => Cow::Borrowed(self.least_common_class(a, b)),
+            _ => Cow::Borrowed(OBJECT),
+        }
+    }
+
+    /// `least_common_superclass` of two classes.
+    fn least_common_class<'a>(&'a self, a: &'a str, b: &'a str) -> &'a str {
+        let is_interface = |name: &str| {
+            self.classes
+                .get(name)
+                .is_some_and(HierarchyClass::is_interface)
+        };
+        if is_interface(a) || is_interface(b) {
+            return OBJECT;
+        }
+        let mut a_chain = vec![a];
+        a_chain.extend(self.superclasses(a));
+        std::iter::once(b)
+            .chain(self.superclasses(b))
+            .find(|class| a_chain.contains(class))
+            .unwrap_or(OBJECT)
+    }
+}
+
+impl TypeHierarchy for ClassHierarchy {
+    fn common_superclass(&self, a: &str, b: &str) -> String {
+        self.least_common_superclass(a, b).into_owned()
+    }
+}
@@ -0,0 +1,96 @@
+/// The attributes of one manifest section, in file order.
+pub type ManifestAttributes = Vec<(String, String)>;
+
+/// A JAR manifest, `META-INF/MANIFEST.MF`.
+///
+/// Attribute names are compared case-insensitively, as in `java.util.jar.Attributes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// The attributes of the main section, such as `Main-Class`.
+    pub main_attributes: ManifestAttributes,
+    /// The per-entry sections, by the value of their `Name` attribute.
+    pub entries: Vec<(String, ManifestAttributes)>,
+}
+
+fn find<'a>(attributes: &'a ManifestAttributes, name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+impl Manifest {
+    /// Parse the text of a manifest. Continuation lines, which start with a single space, are
+    /// joined onto the line before, and lines without a `: ` separator are ignored.
+    ///
+    /// ```rust
+    /// use classfile_parser::jar::Manifest;
+    ///
+    /// let manifest = Manifest::parse(
+    ///     "Manifest-Version: 1.0\r\nMain-Class: com.example.Ma\r\n in\r\n\r\nName: com/example/\r\nSealed: true\r\n",
+    /// );
+    /// assert_eq!(manifest.main_class(), Some("com.example.Main"));
+    /// assert_eq!(manifest.entry_attribute("com/example/", "sealed"), Some("true"));
+    /// ```
+    pub fn parse(text: &str) -> Manifest {
+        let mut sections: Vec<ManifestAttributes> = vec![Vec::new()];
+        let mut lines: Vec<String> = Vec::new();
+        for line in text.lines() {
+            if let Some(continuation) = line.strip_prefix(' ')
+                && let Some(last) = lines.last_mut()
+            {
+                last.push_str(continuation);
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+        for line in lines {
+            if line.is_empty() {
+                if !sections.last().is_some_and(|section| section.is_empty()) {
+                    sections.push(Vec::new());
+                }
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                sections
+                    .last_mut()
+                    .unwrap()
+                    .push((key.to_string(), value.to_string()));
+            }
+        }
+
+        let mut sections = sections.into_iter();
+        let main_attributes = sections.next().unwrap_or_default();
+        let entries = sections
+            .filter_map(|section| Some((find(&section, "Name")?.to_string(), section)))
+            .collect();
+        Manifest {
+            main_attributes,
+            entries,
+        }
+    }
+
+    /// The value of an attribute of the main section.
+    pub fn main_attribute(&self, name: &str) -> Option<&str> {
+        find(&self.main_attributes, name)
+    }
+
+    /// The value of an attribute of the section for `entry`.
+    pub fn entry_attribute(&self, entry: &str, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(section, _)| section == entry)
+            .and_then(|(_, attributes)| find(attributes, name))
+    }
+
+    /// The `Main-Class` attribute, a binary name such as `com.example.Main`.
+    pub fn main_class(&self) -> Option<&str> {
+        self.main_attribute("Main-Class")
+    }
+
+    /// Whether the `Multi-Release` attribute is `true`.
+    pub fn is_multi_release(&self) -> bool {
+        self.main_attribute("Multi-Release")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+    }
+}
@@ -0,0 +1,10 @@
+//! Reading classes from JAR files, behind the `jar` feature.
+//!
+//! `JarFile` indexes the `.class` entries of an archive, resolving multi-release overlays,
+//! and parses them into `ClassFile`s on demand.
+
+mod manifest;
+mod types;
+
+pub use self::manifest::*;
+pub use self::types::*;
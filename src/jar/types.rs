@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+use crate::error::ClassParseError;
+use crate::parse_class_from_bytes;
+use crate::types::ClassFile;
+
+use super::manifest::Manifest;
+
+const MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+const VERSIONS_PREFIX: &str = "META-INF/versions/";
+/// Versioned directories below this release are ignored, as by the JDK.
+const FIRST_VERSIONED_RELEASE: u16 = 9;
+/// The most buffer space reserved up front for an entry, whatever size the archive declares.
+const MAX_ENTRY_PREALLOCATION: u64 = 1 << 20;
+
+/// An error returned when a JAR cannot be read, or one of its classes cannot be parsed.
+#[derive(Debug)]
+pub enum JarError {
+    /// The archive could not be read.
+    Io(io::Error),
+    /// The archive is not a valid ZIP file, or has no entry with the requested name.
+    Zip(ZipError),
+    /// A class entry could not be parsed.
+    Class {
+        /// The name of the entry, such as `META-INF/versions/11/com/example/Main.class`.
+        entry: String,
+        error: ClassParseError,
+    },
+}
+
+impl fmt::Display for JarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JarError::Io(e) => write!(f, "unable to read jar: {}", e),
+            JarError::Zip(e) => write!(f, "invalid jar: {}", e),
+            JarError::Class { entry, error } => write!(f, "{}: {}", entry, error),
+        }
+    }
+}
+
+impl Error for JarError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JarError::Io(e) => Some(e),
+            JarError::Zip(e) => Some(e),
+            JarError::Class { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for JarError {
+    fn from(e: io::Error) -> Self {
+        JarError::Io(e)
+    }
+}
+
+impl From<ZipError> for JarError {
+    fn from(e: ZipError) -> Self {
+        JarError::Zip(e)
+    }
+}
+
+/// A JAR file, giving access to its entries, manifest and parsed classes.
+///
+/// Classes are named by their internal name, such as `com/example/Main`. In a multi-release
+/// JAR with a release set by `with_release`, each class is read from the
+/// `META-INF/versions/N/` directory with the highest `N` not above the release, falling back
+/// to the root. Without a release only the root entries are used, as by `java.util.jar.JarFile`.
+///
+/// ```rust,no_run
+/// use classfile_parser::jar::JarFile;
+///
+/// let mut jar = JarFile::open("app.jar").unwrap().with_release(17);
+/// if let Some(main_class) = jar.manifest().and_then(|m| m.main_class()) {
+///     println!("main class {}", main_class);
+/// }
+/// for class in jar.classes() {
+///     let (name, class_file) = class.unwrap();
+///     println!("{} has {} methods", name, class_file.methods.len());
+/// }
+/// ```
+pub struct JarFile<R> {
+    archive: ZipArchive<R>,
+    manifest: Option<Manifest>,
+    release: Option<u16>,
+    /// The entry each class is read from, by internal name.
+    classes: BTreeMap<String, String>,
+}
+
+impl JarFile<BufReader<File>> {
+    /// Open the JAR at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, JarError> {
+        JarFile::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> JarFile<R> {
+    /// Read a JAR from any seekable reader, such as an in-memory `Cursor`.
+    pub fn new(reader: R) -> Result<Self, JarError> {
+        let mut archive = ZipArchive::new(reader)?;
+        let manifest = match archive.by_name(MANIFEST_NAME) {
+            Ok(mut entry) => {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                Some(Manifest::parse(&String::from_utf8_lossy(&bytes)))
+            }
+            Err(ZipError::FileNotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let mut jar = JarFile {
+            archive,
+            manifest,
+            release: None,
+            classes: BTreeMap::new(),
+        };
+        jar.index_classes();
+        Ok(jar)
+    }
+
+    /// Read versioned entries for the Java feature release `release`, such as `17`. This
+    /// has no effect unless the manifest declares `Multi-Release: true`.
+    pub fn with_release(mut self, release: u16) -> Self {
+        self.release = Some(release);
+        self.index_classes();
+        self
+    }
+
+    /// Work out which entry each class is read from.
+    fn index_classes(&mut self) {
+        let release = self.release.filter(|_| self.is_multi_release());
+        let mut classes: BTreeMap<String, (u16, String)> = BTreeMap::new();
+        for entry in self.archive.file_names() {
+            let Some(path) = entry.strip_suffix(".class") else {
+                continue;
+            };
+            let (version, class_name) = if let Some(versioned) = path.strip_prefix(VERSIONS_PREFIX)
+            {
+                let Some(release) = release else {
+                    continue;
+                };
+                let Some((version, class_name)) = versioned
+                    .split_once('/')
+                    .and_then(|(version, name)| Some((version.parse::<u16>().ok()?, name)))
+                else {
+                    continue;
+                };
+                if !(FIRST_VERSIONED_RELEASE..=release).contains(&version) {
+                    continue;
+                }
+                (version, class_name)
+            } else if path.starts_with("META-INF/") {
+                continue;
+            } else {
+                (0, path)
+            };
+            match classes.get(class_name) {
+                Some((chosen, _)) if *chosen >= version => {}
+                _ => {
+                    classes.insert(class_name.to_string(), (version, entry.to_string()));
+                }
+            }
+        }
+        self.classes = classes
+            .into_iter()
+            .map(|(class_name, (_, entry))| (class_name, entry))
+            .collect();
+    }
+
+    /// The manifest, if the JAR has one.
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest.as_ref()
+    }
+
+    /// Whether the manifest declares `Multi-Release: true`.
+    pub fn is_multi_release(&self) -> bool {
+        self.manifest
+            .as_ref()
+            .is_some_and(|manifest| manifest.is_multi_release())
+    }
+
+    /// The release versioned entries are read for, if one has been set.
+    pub fn release(&self) -> Option<u16> {
+        self.release
+    }
+
+    /// The names of all entries in the archive, including directories and resources.
+    pub fn entry_names(&self) -> impl Iterator<Item = &str> {
+        self.archive.file_names()
+    }
+
+    /// The internal names of the classes in the JAR, in sorted order.
+    pub fn class_names(&self) -> impl Iterator<Item = &str> {
+        self.classes.keys().map(String::as_str)
+    }
+
+    /// The entry the class `class_name` is read from, taking versioned entries into account.
+    pub fn class_entry_name(&self, class_name: &str) -> Option<&str> {
+        self.classes.get(class_name).map(String::as_str)
+    }
+
+    /// Read the uncompressed contents of the entry called `name`.
+    ///
+    /// Returns an error if the contents do not match the size the archive declares.
+    pub fn read_entry(&mut self, name: &str) -> Result<Vec<u8>, JarError> {
+        let mut entry = self.archive.by_name(name)?;
+        let size = entry.size();
+        let mut bytes = Vec::with_capacity(size.min(MAX_ENTRY_PREALLOCATION) as usize);
+        entry.read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != size {
+            return Err(JarError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "entry {} declares {} bytes but contains {}",
+                    name,
+                    size,
+                    bytes.len()
+                ),
+            )));
+        }
+        Ok(bytes)
+    }
+
+    fn parse_entry(&mut self, entry: &str) -> Result<ClassFile, JarError> {
+        let bytes = self.read_entry(entry)?;
+        parse_class_from_bytes(&bytes).map_err(|error| JarError::Class {
+            entry: entry.to_string(),
+            error,
+        })
+    }
+
+    /// Parse the class `class_name`, `None` if the JAR does not contain it.
+    pub fn class(&mut self, class_name: &str) -> Result<Option<ClassFile>, JarError> {
+        match self.classes.get(class_name).cloned() {
+            Some(entry) => self.parse_entry(&entry).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse each class in turn, in the order of `class_names`.
+    pub fn classes(&mut self) -> Classes<'_, R> {
+        let classes = self
+            .classes
+            .iter()
+            .map(|(class_name, entry)| (class_name.clone(), entry.clone()))
+            .collect::<Vec<_>>();
+        Classes {
+            jar: self,
+            classes: classes.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the parsed classes of a JAR, yielding each class's internal name with it.
+pub struct Classes<'a, R> {
+    jar: &'a mut JarFile<R>,
+    classes: std::vec::IntoIter<(String, String)>,
+}
+
+impl<R: Read + Seek> Iterator for Classes<'_, R> {
+    type Item = Result<(String, ClassFile), JarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (class_name, entry) = self.classes.next()?;
+        Some(
+            self.jar
+                .parse_entry(&entry)
+                .map(|class_file| (class_name, class_file)),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.classes.size_hint()
+    }
+}
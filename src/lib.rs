@@ -1,7 +1,7 @@
 //! A parser for [Java Classfiles](https://docs.oracle.com/javase/specs/jvms/se10/html/jvms-4.html)
 
 use std::fs::File;
-use std::io::{BufReader, prelude::*};
+use std::io::{self, BufReader, prelude::*};
 use std::path::Path;
 
 #[macro_use]
@@ -15,11 +15,27 @@ pub mod constant_info;
 pub mod field_info;
 pub mod method_info;
 
+pub mod borrowed;
+pub mod call_graph;
+pub mod class_path;
 pub mod code_attribute;
+pub mod control_flow;
+pub mod dependencies;
+pub mod descriptor;
+pub mod disassembler;
+pub mod hierarchy;
+#[cfg(feature = "jar")]
+pub mod jar;
+pub mod remapper;
+pub mod signature;
+pub mod verifier;
 
+pub mod error;
 pub mod parser;
 pub mod types;
 
+pub use borrowed::{ClassFileRef, ClassHeader, ClassLayout, ClassPrefix};
+pub use error::{ClassParseError, ParseContext, ParseErrorCause};
 pub use parser::class_parser;
 pub use types::*;
 
@@ -33,14 +49,17 @@ pub use types::*;
 ///     Err(ex) => panic!("Failed to parse: {}", ex),
 /// };
 /// ```
-pub fn parse_class(class_name: &str) -> Result<ClassFile, String> {
+pub fn parse_class(class_name: &str) -> Result<ClassFile, ClassParseError> {
     let class_file_name = &format!("{}.class", class_name);
     let path = Path::new(class_file_name);
     let display = path.display();
 
     let file = match File::open(path) {
         Err(why) => {
-            return Err(format!("Unable to open {}: {}", display, &why.to_string()));
+            return Err(ClassParseError::Io(io::Error::new(
+                why.kind(),
+                format!("Unable to open {}: {}", display, why),
+            )));
         }
         Ok(file) => file,
     };
@@ -60,24 +79,78 @@ pub fn parse_class(class_name: &str) -> Result<ClassFile, String> {
 /// let result = classfile_parser::parse_class_from_reader(&mut reader);
 /// assert!(result.is_err());
 /// ```
-pub fn parse_class_from_reader<T: Read>(reader: &mut T) -> Result<ClassFile, String> {
+pub fn parse_class_from_reader<T: Read>(reader: &mut T) -> Result<ClassFile, ClassParseError> {
     let mut class_bytes = Vec::new();
-    reader
-        .read_to_end(&mut class_bytes)
-        .expect("cannot continue, read_to_end failed");
-
-    let parsed_class = class_parser(&class_bytes);
-    match parsed_class {
-        Ok((a, c)) => {
-            if !a.is_empty() {
-                eprintln!(
-                    "Warning: not all bytes were consumed when parsing classfile, {} bytes remaining",
-                    a.len()
-                );
-            }
-
-            Ok(c)
-        }
-        Err(e) => Err(format!("Failed to parse classfile: {}", e)),
-    }
+    reader.read_to_end(&mut class_bytes)?;
+
+    parse_class_from_bytes(&class_bytes)
+}
+
+/// Attempt to parse a class file from a byte slice.
+///
+/// On failure the returned `ClassParseError` describes the byte offset, the structure that was
+/// being parsed and what was wrong with it. Any bytes after the end of the class file are
+/// ignored.
+///
+/// ```rust
+/// use classfile_parser::{ParseContext, ParseErrorCause};
+///
+/// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+/// let class_file = classfile_parser::parse_class_from_bytes(classfile_bytes).unwrap();
+/// println!("version {},{}", class_file.major_version, class_file.minor_version);
+///
+/// let error = classfile_parser::parse_class_from_bytes(&classfile_bytes[..20]).unwrap_err();
+/// assert_eq!(error.cause(), Some(&ParseErrorCause::Truncated));
+/// assert!(matches!(error.context(), [ParseContext::Constant(_)]));
+/// ```
+pub fn parse_class_from_bytes(class_bytes: &[u8]) -> Result<ClassFile, ClassParseError> {
+    let (_, class_file) = parser::class_file_parser(class_bytes)?;
+    Ok(class_file)
+}
+
+/// Parse a class file from a byte slice without copying, borrowing constant pool strings,
+/// attribute bodies and code arrays from `class_bytes`. Any bytes after the end of the
+/// class file are ignored.
+///
+/// ```rust
+/// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/Factorial.class");
+/// let class_file = classfile_parser::parse_class_ref(classfile_bytes).unwrap();
+///
+/// let pool = &class_file.header.const_pool;
+/// let method = &class_file.methods[1];
+/// assert_eq!(pool.get_utf8(method.name_index).unwrap(), "factorial");
+/// let code = method.code(pool).unwrap().unwrap();
+/// assert_eq!(code.code.len(), 18);
+/// ```
+pub fn parse_class_ref(class_bytes: &[u8]) -> Result<ClassFileRef<'_>, ClassParseError> {
+    let (_, class_file) = borrowed::class_file_ref_parser(class_bytes)?;
+    Ok(class_file)
+}
+
+/// Parse only the version and constant pool at the start of a class file.
+///
+/// ```rust
+/// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+/// let prefix = classfile_parser::parse_until_constant_pool(classfile_bytes).unwrap();
+/// assert_eq!(prefix.major_version, 66);
+/// assert!(!prefix.const_pool.is_empty());
+/// ```
+pub fn parse_until_constant_pool(class_bytes: &[u8]) -> Result<ClassPrefix<'_>, ClassParseError> {
+    let (_, prefix) = borrowed::class_prefix_parser(class_bytes)?;
+    Ok(prefix)
+}
+
+/// Parse a class file up to its field table: the version, constant pool, access flags,
+/// this and super class and interfaces. Nothing after the interfaces is looked at.
+pub fn parse_header(class_bytes: &[u8]) -> Result<ClassHeader<'_>, ClassParseError> {
+    let (_, header) = borrowed::class_header_parser(class_bytes)?;
+    Ok(header)
+}
+
+/// Parse a class file's header and step over its fields, methods and attributes without
+/// decoding them, recording the byte range of each. Any bytes after `attributes.end` are
+/// ignored.
+pub fn scan_class(class_bytes: &[u8]) -> Result<ClassLayout<'_>, ClassParseError> {
+    let (_, layout) = borrowed::class_layout_parser(class_bytes)?;
+    Ok(layout)
 }
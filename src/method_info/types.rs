@@ -1,4 +1,8 @@
-use crate::attribute_info::AttributeInfo;
+use crate::attribute_info::{
+    AttributeInfo, TypedAttribute, has_attribute, has_deprecated, typed_attributes_parser,
+};
+use crate::constant_info::ConstantInfo;
+use crate::error::ClassParseError;
 
 use binrw::binrw;
 
@@ -14,6 +18,30 @@ pub struct MethodInfo {
     pub attributes: Vec<AttributeInfo>,
 }
 
+impl MethodInfo {
+    /// Decode this method's attributes, resolving attribute names through the given
+    /// constant pool of the class the method belongs to.
+    pub fn typed_attributes(
+        &self,
+        const_pool: &[ConstantInfo],
+    ) -> Result<Vec<TypedAttribute>, ClassParseError> {
+        typed_attributes_parser(&self.attributes, const_pool)
+    }
+
+    /// Whether this method is marked deprecated, by a Deprecated attribute or a
+    /// `@java.lang.Deprecated` annotation.
+    pub fn is_deprecated(&self, const_pool: &[ConstantInfo]) -> bool {
+        has_deprecated(&self.attributes, const_pool)
+    }
+
+    /// Whether this method was generated by the compiler, by the `ACC_SYNTHETIC` flag or a
+    /// Synthetic attribute.
+    pub fn is_synthetic(&self, const_pool: &[ConstantInfo]) -> bool {
+        self.access_flags.contains(MethodAccessFlags::SYNTHETIC)
+            || has_attribute(&self.attributes, const_pool, "Synthetic")
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[binrw]
 pub struct MethodAccessFlags(u16);
@@ -1,22 +1,40 @@
 use nom::*;
 
-use crate::attribute_info::attribute_parser;
-use crate::constant_info::constant_parser;
-use crate::field_info::field_parser;
-use crate::method_info::method_parser;
+use crate::attribute_info::{AttributeInfo, attribute_parser};
+use crate::constant_info::{ConstantInfo, constant_pool_parser};
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
+use crate::field_info::{FieldAccessFlags, FieldInfo};
+use crate::method_info::{MethodAccessFlags, MethodInfo};
 use crate::types::{ClassAccessFlags, ClassFile};
 use nom::bytes::complete::tag;
+use nom::error::ErrorKind;
 use nom::multi::count;
+use nom::number::complete::be_u16;
 
 fn magic_parser(input: &[u8]) -> IResult<&[u8], &[u8]> {
     tag(&[0xCA, 0xFE, 0xBA, 0xBE])(input)
 }
 
+/// Check the magic number at the start of a class file, returning the bytes after it.
+pub(crate) fn check_magic(input: &[u8]) -> Result<&[u8], ClassParseError> {
+    let (input, _) = magic_parser(input).map_err(|_| {
+        let cause = if input.len() < 4 && [0xCA, 0xFE, 0xBA, 0xBE].starts_with(input) {
+            ParseErrorCause::Truncated
+        } else {
+            ParseErrorCause::BadMagic
+        };
+        ClassParseError::malformed(0, cause).within(0, ParseContext::Header)
+    })?;
+    Ok(input)
+}
+
 /// Parse a byte array into a ClassFile. This will probably be deprecated in 0.4.0 in as it returns
 /// a nom IResult type, which exposes the internal parsing library and not a good idea.
 ///
-/// If you want to call it directly, as it is the only way to parse a byte slice directly, you must
-/// unwrap the result yourself.
+/// Prefer [`parse_class_from_bytes`](crate::parse_class_from_bytes), which reports where and
+/// why parsing failed.
+///
+/// If you want to call it directly, you must unwrap the result yourself.
 ///
 /// ```rust
 /// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
@@ -29,23 +47,75 @@ fn magic_parser(input: &[u8]) -> IResult<&[u8], &[u8]> {
 /// };
 /// ```
 pub fn class_parser(input: &[u8]) -> IResult<&[u8], ClassFile> {
-    use nom::number::complete::be_u16;
-    let (input, _) = magic_parser(input)?;
-    let (input, minor_version) = be_u16(input)?;
-    let (input, major_version) = be_u16(input)?;
-    let (input, const_pool_size) = be_u16(input)?;
-    let (input, const_pool) = constant_parser(input, (const_pool_size.saturating_sub(1)) as usize)?;
-    let (input, access_flags) = be_u16(input)?;
-    let (input, this_class) = be_u16(input)?;
-    let (input, super_class) = be_u16(input)?;
-    let (input, interfaces_count) = be_u16(input)?;
-    let (input, interfaces) = count(be_u16, interfaces_count as usize)(input)?;
-    let (input, fields_count) = be_u16(input)?;
-    let (input, fields) = count(field_parser, fields_count as usize)(input)?;
-    let (input, methods_count) = be_u16(input)?;
-    let (input, methods) = count(method_parser, methods_count as usize)(input)?;
-    let (input, attributes_count) = be_u16(input)?;
-    let (input, attributes) = count(attribute_parser, attributes_count as usize)(input)?;
+    class_file_parser(input).map_err(|e| {
+        let kind = match e.cause() {
+            Some(ParseErrorCause::BadMagic) => ErrorKind::Tag,
+            Some(ParseErrorCause::Truncated) => ErrorKind::Eof,
+            _ => ErrorKind::Verify,
+        };
+        let offset = e.offset().unwrap_or(0).min(input.len());
+        Err::Error(error_position!(&input[offset..], kind))
+    })
+}
+
+/// Parse a complete class file, returning any unconsumed trailing bytes alongside it.
+/// Error offsets are relative to the start of `input`.
+pub(crate) fn class_file_parser(input: &[u8]) -> Result<(&[u8], ClassFile), ClassParseError> {
+    let whole = input;
+    let at =
+        |context: ParseContext| move |e| ClassParseError::from_nom(whole, e).within(0, context);
+
+    let input = check_magic(input)?;
+    let (input, minor_version) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, major_version) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, const_pool_size) = be_u16(input).map_err(at(ParseContext::Header))?;
+    let (input, const_pool) =
+        constant_pool_parser(input, (const_pool_size.saturating_sub(1)) as usize)
+            .map_err(|e| e.offset_by(whole.len() - input.len()))?;
+    let (input, access_flags) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let (input, this_class) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let (input, super_class) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let (input, interfaces_count) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let (input, interfaces) =
+        count(be_u16, interfaces_count as usize)(input).map_err(at(ParseContext::ClassInfo))?;
+
+    let (mut input, fields_count) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let mut fields = Vec::with_capacity(fields_count as usize);
+    for index in 0..fields_count {
+        let context = ParseContext::Field(index);
+        let (rest, (access_flags, name_index, descriptor_index)) =
+            member_header_parser(input).map_err(at(context.clone()))?;
+        let (rest, (attributes_count, attributes)) =
+            attributes_parser(whole, rest, &const_pool).map_err(|e| e.within(0, context))?;
+        fields.push(FieldInfo {
+            access_flags: FieldAccessFlags::from_bits_truncate(access_flags),
+            name_index,
+            descriptor_index,
+            attributes_count,
+            attributes,
+        });
+        input = rest;
+    }
+
+    let (mut input, methods_count) = be_u16(input).map_err(at(ParseContext::ClassInfo))?;
+    let mut methods = Vec::with_capacity(methods_count as usize);
+    for index in 0..methods_count {
+        let context = ParseContext::Method(index);
+        let (rest, (access_flags, name_index, descriptor_index)) =
+            member_header_parser(input).map_err(at(context.clone()))?;
+        let (rest, (attributes_count, attributes)) =
+            attributes_parser(whole, rest, &const_pool).map_err(|e| e.within(0, context))?;
+        methods.push(MethodInfo {
+            access_flags: MethodAccessFlags::from_bits_truncate(access_flags),
+            name_index,
+            descriptor_index,
+            attributes_count,
+            attributes,
+        });
+        input = rest;
+    }
+
+    let (input, (attributes_count, attributes)) = attributes_parser(whole, input, &const_pool)?;
     Ok((
         input,
         ClassFile {
@@ -67,3 +137,47 @@ pub fn class_parser(input: &[u8]) -> IResult<&[u8], ClassFile> {
         },
     ))
 }
+
+pub(crate) fn member_header_parser(input: &[u8]) -> IResult<&[u8], (u16, u16, u16)> {
+    let (input, access_flags) = be_u16(input)?;
+    let (input, name_index) = be_u16(input)?;
+    let (input, descriptor_index) = be_u16(input)?;
+    Ok((input, (access_flags, name_index, descriptor_index)))
+}
+
+// Using a type alias here evades a Clippy warning about complex types.
+type AttributesResult<'a> = Result<(&'a [u8], (u16, Vec<AttributeInfo>)), ClassParseError>;
+
+fn attributes_parser<'a>(
+    whole: &[u8],
+    input: &'a [u8],
+    const_pool: &[ConstantInfo],
+) -> AttributesResult<'a> {
+    let (mut input, attributes_count) =
+        be_u16(input).map_err(|e| ClassParseError::from_nom(whole, e))?;
+    let mut attributes = Vec::with_capacity(attributes_count as usize);
+    for _ in 0..attributes_count {
+        let (rest, attribute) = attribute_parser(input).map_err(|e| {
+            let name_index = be_u16::<_, error::Error<&[u8]>>(input)
+                .map(|(_, index)| index)
+                .unwrap_or(0);
+            ClassParseError::from_nom(whole, e).within(
+                0,
+                ParseContext::Attribute(attribute_name(const_pool, name_index)),
+            )
+        })?;
+        attributes.push(attribute);
+        input = rest;
+    }
+    Ok((input, (attributes_count, attributes)))
+}
+
+fn attribute_name(const_pool: &[ConstantInfo], name_index: u16) -> String {
+    match (name_index as usize)
+        .checked_sub(1)
+        .and_then(|i| const_pool.get(i))
+    {
+        Some(ConstantInfo::Utf8(utf8)) => utf8.utf8_string.to_string(),
+        _ => format!("#{}", name_index),
+    }
+}
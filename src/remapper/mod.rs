@@ -0,0 +1,15 @@
+//! Renaming classes, fields and methods throughout a class file, for shading dependencies.
+//!
+//! Classes are renamed through the constant pool: Class constants get new names, and the
+//! descriptors of NameAndType and MethodType constants are rewritten, so every attribute
+//! that refers to a class by its Class constant (NestMembers, PermittedSubclasses,
+//! StackMapTable, Module, ...) follows along. Descriptors, generic signatures, annotation
+//! types and inner class names stored as Utf8 indexes inside attributes are patched in
+//! place. New names are added to the constant pool rather than overwriting the old ones,
+//! which may still be needed by String constants, and attribute lengths never change.
+
+mod rewriter;
+mod types;
+
+pub use self::rewriter::{remap_class, remap_class_bytes};
+pub use self::types::*;
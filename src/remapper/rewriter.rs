@@ -0,0 +1,647 @@
+use crate::attribute_info::AttributeInfo;
+use crate::constant_info::*;
+use crate::descriptor::{FieldType, MethodDescriptor, ReturnType};
+use crate::error::{ClassParseError, ParseContext, ParseErrorCause};
+use crate::signature::{
+    ClassSignature, ClassTypeSignature, FieldSignature, JavaTypeSignature, MethodSignature,
+    ReferenceTypeSignature, ResultSignature, TypeArgument, TypeParameter,
+};
+use crate::types::ClassFile;
+
+use super::types::Remapper;
+
+/// Which kind of generic signature a Signature attribute holds.
+#[derive(Copy, Clone)]
+enum SignatureKind {
+    Class,
+    Method,
+    Field,
+}
+
+/// A cursor over an attribute body that can overwrite the indexes it has read.
+struct Patch<'b> {
+    info: &'b mut [u8],
+    offset: usize,
+}
+
+impl<'b> Patch<'b> {
+    fn new(info: &'b mut [u8]) -> Self {
+        Patch { info, offset: 0 }
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), ClassParseError> {
+        if self.info.len() - self.offset < len {
+            return Err(ClassParseError::malformed(
+                self.info.len(),
+                ParseErrorCause::Truncated,
+            ));
+        }
+        self.offset += len;
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8, ClassParseError> {
+        self.skip(1)?;
+        Ok(self.info[self.offset - 1])
+    }
+
+    fn u16(&mut self) -> Result<u16, ClassParseError> {
+        self.skip(2)?;
+        Ok(u16::from_be_bytes([
+            self.info[self.offset - 2],
+            self.info[self.offset - 1],
+        ]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ClassParseError> {
+        Ok((self.u16()? as u32) << 16 | self.u16()? as u32)
+    }
+
+    /// Replace the u16 just read.
+    fn set_u16(&mut self, value: u16) {
+        self.info[self.offset - 2..self.offset].copy_from_slice(&value.to_be_bytes());
+    }
+
+    fn invalid(&self) -> ClassParseError {
+        ClassParseError::malformed(self.offset - 1, ParseErrorCause::Invalid)
+    }
+}
+
+/// The new simple name of the inner class `name`, called `inner_name` in the source, as
+/// recorded by an InnerClasses entry. The simple name only changes if the class is renamed
+/// to something that is itself an inner class name, after the last `$` and any digits of a
+/// local class.
+fn inner_class_name(name: &str, mapped: &str, inner_name: &str) -> Option<String> {
+    let simple = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+    if simple(name) == simple(mapped) {
+        return None;
+    }
+    let (_, suffix) = mapped.rsplit_once('$')?;
+    let suffix = suffix.trim_start_matches(|c: char| c.is_ascii_digit());
+    (!suffix.is_empty() && suffix != inner_name).then(|| suffix.to_string())
+}
+
+struct Rewriter<'a, R: Remapper + ?Sized> {
+    remapper: &'a R,
+    /// The original constant pool, which every index read from the class refers to.
+    pool: ConstantPool<'a>,
+    builder: ConstantPoolBuilder,
+}
+
+impl<R: Remapper + ?Sized> Rewriter<'_, R> {
+    /// The new name of a class given by internal name or array descriptor.
+    fn class_name(&self, name: &str) -> Result<Option<String>, ClassParseError> {
+        if name.starts_with('[') {
+            return self.descriptor(name);
+        }
+        Ok(self
+            .remapper
+            .map_class(name)
+            .filter(|mapped| mapped != name))
+    }
+
+    fn field_type(&self, field_type: &mut FieldType) -> bool {
+        match field_type {
+            FieldType::Base(_) => false,
+            FieldType::Object(name) => match self.remapper.map_class(name) {
+                Some(mapped) if mapped != *name => {
+                    *name = mapped;
+                    true
+                }
+                _ => false,
+            },
+            FieldType::Array { element, .. } => self.field_type(element),
+        }
+    }
+
+    /// The new form of a field or method descriptor, `None` if it is unchanged.
+    fn descriptor(&self, descriptor: &str) -> Result<Option<String>, ClassParseError> {
+        if descriptor.starts_with('(') {
+            let mut descriptor: MethodDescriptor = descriptor.parse()?;
+            let mut changed = false;
+            for param in &mut descriptor.params {
+                changed |= self.field_type(param);
+            }
+            if let ReturnType::Type(return_type) = &mut descriptor.return_type {
+                changed |= self.field_type(return_type);
+            }
+            Ok(changed.then(|| descriptor.to_string()))
+        } else {
+            let mut field_type: FieldType = descriptor.parse()?;
+            Ok(self
+                .field_type(&mut field_type)
+                .then(|| field_type.to_string()))
+        }
+    }
+
+    fn class_type_signature(&self, signature: &mut ClassTypeSignature) -> bool {
+        let mut changed = false;
+        for class in std::iter::once(&mut signature.class).chain(&mut signature.suffix) {
+            for argument in &mut class.type_arguments {
+                match argument {
+                    TypeArgument::Any => {}
+                    TypeArgument::Exact(reference)
+                    | TypeArgument::Extends(reference)
+                    | TypeArgument::Super(reference) => {
+                        changed |= self.reference_signature(reference)
+                    }
+                }
+            }
+        }
+
+        let mut name = match signature.package.as_str() {
+            "" => signature.class.name.clone(),
+            package => format!("{}/{}", package, signature.class.name),
+        };
+        let mut mapped = match self.remapper.map_class(&name) {
+            Some(outer) if outer != name => {
+                let (package, class) = outer.rsplit_once('/').unwrap_or(("", &outer));
+                signature.package = package.to_string();
+                signature.class.name = class.to_string();
+                changed = true;
+                outer
+            }
+            _ => name.clone(),
+        };
+        for inner in &mut signature.suffix {
+            name = format!("{}${}", name, inner.name);
+            match self.remapper.map_class(&name) {
+                Some(full) if full != format!("{}${}", mapped, inner.name) => {
+                    let simple = match full.strip_prefix(&format!("{}$", mapped)) {
+                        Some(simple) => simple,
+                        None => full.rsplit_once('$').map_or(&*inner.name, |(_, s)| s),
+                    }
+                    .to_string();
+                    if simple != inner.name {
+                        inner.name = simple;
+                        changed = true;
+                    }
+                    mapped = full;
+                }
+                _ => mapped = format!("{}${}", mapped, inner.name),
+            }
+        }
+        changed
+    }
+
+    fn reference_signature(&self, signature: &mut ReferenceTypeSignature) -> bool {
+        match signature {
+            ReferenceTypeSignature::Class(class) => self.class_type_signature(class),
+            ReferenceTypeSignature::TypeVariable(_) => false,
+            ReferenceTypeSignature::Array(component) => self.type_signature(component),
+        }
+    }
+
+    fn type_signature(&self, signature: &mut JavaTypeSignature) -> bool {
+        match signature {
+            JavaTypeSignature::Base(_) => false,
+            JavaTypeSignature::Reference(reference) => self.reference_signature(reference),
+        }
+    }
+
+    fn type_parameters(&self, type_parameters: &mut [TypeParameter]) -> bool {
+        let mut changed = false;
+        for type_parameter in type_parameters {
+            for bound in type_parameter
+                .class_bound
+                .iter_mut()
+                .chain(&mut type_parameter.interface_bounds)
+            {
+                changed |= self.reference_signature(bound);
+            }
+        }
+        changed
+    }
+
+    /// The new form of a generic signature, `None` if it is unchanged.
+    fn signature(
+        &self,
+        signature: &str,
+        kind: SignatureKind,
+    ) -> Result<Option<String>, ClassParseError> {
+        Ok(match kind {
+            SignatureKind::Class => {
+                let mut signature: ClassSignature = signature.parse()?;
+                let mut changed = self.type_parameters(&mut signature.type_parameters);
+                changed |= self.class_type_signature(&mut signature.superclass);
+                for interface in &mut signature.interfaces {
+                    changed |= self.class_type_signature(interface);
+                }
+                changed.then(|| signature.to_string())
+            }
+            SignatureKind::Method => {
+                let mut signature: MethodSignature = signature.parse()?;
+                let mut changed = self.type_parameters(&mut signature.type_parameters);
+                for param in &mut signature.params {
+                    changed |= self.type_signature(param);
+                }
+                if let ResultSignature::Type(result) = &mut signature.result {
+                    changed |= self.type_signature(result);
+                }
+                for thrown in &mut signature.throws {
+                    changed |= self.reference_signature(thrown);
+                }
+                changed.then(|| signature.to_string())
+            }
+            SignatureKind::Field => {
+                let mut signature: FieldSignature = signature.parse()?;
+                self.reference_signature(&mut signature.0)
+                    .then(|| signature.to_string())
+            }
+        })
+    }
+
+    /// Point the Utf8 index just read at the mapped form of the string it refers to.
+    fn patch_utf8(
+        &mut self,
+        patch: &mut Patch,
+        map: impl FnOnce(&Self, &str) -> Result<Option<String>, ClassParseError>,
+    ) -> Result<(), ClassParseError> {
+        let value = self.pool.get_utf8(patch.u16()?)?;
+        if let Some(mapped) = map(self, &value)? {
+            patch.set_u16(self.builder.add_utf8(&mapped)?);
+        }
+        Ok(())
+    }
+
+    fn patch_descriptor(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        self.patch_utf8(patch, |this, descriptor| match descriptor {
+            // The return descriptor of `void.class` in an annotation
+            "V" => Ok(None),
+            descriptor => this.descriptor(descriptor),
+        })
+    }
+
+    fn patch_signature(
+        &mut self,
+        patch: &mut Patch,
+        kind: SignatureKind,
+    ) -> Result<(), ClassParseError> {
+        self.patch_utf8(patch, |this, signature| this.signature(signature, kind))
+    }
+
+    fn annotation(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        self.patch_descriptor(patch)?;
+        for _ in 0..patch.u16()? {
+            patch.skip(2)?;
+            self.element_value(patch)?;
+        }
+        Ok(())
+    }
+
+    fn annotations(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            self.annotation(patch)?;
+        }
+        Ok(())
+    }
+
+    fn element_value(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        match patch.u8()? {
+            b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' => patch.skip(2),
+            b'e' => {
+                self.patch_descriptor(patch)?;
+                patch.skip(2)
+            }
+            b'c' => self.patch_descriptor(patch),
+            b'@' => self.annotation(patch),
+            b'[' => {
+                for _ in 0..patch.u16()? {
+                    self.element_value(patch)?;
+                }
+                Ok(())
+            }
+            _ => Err(patch.invalid()),
+        }
+    }
+
+    fn type_annotations(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            // target_info, whose layout depends on target_type
+            match patch.u8()? {
+                0x13..=0x15 => {}
+                0x00 | 0x01 | 0x16 => patch.skip(1)?,
+                0x10 | 0x11 | 0x12 | 0x17 | 0x42..=0x46 => patch.skip(2)?,
+                0x47..=0x4b => patch.skip(3)?,
+                0x40 | 0x41 => {
+                    let table_length = patch.u16()?;
+                    patch.skip(6 * table_length as usize)?;
+                }
+                _ => return Err(patch.invalid()),
+            }
+            let path_length = patch.u8()?;
+            patch.skip(2 * path_length as usize)?;
+            self.annotation(patch)?;
+        }
+        Ok(())
+    }
+
+    fn attributes(
+        &mut self,
+        patch: &mut Patch,
+        kind: SignatureKind,
+    ) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            let name_index = patch.u16()?;
+            let length = patch.u32()? as usize;
+            let start = patch.offset;
+            patch.skip(length)?;
+            self.attribute(name_index, &mut patch.info[start..start + length], kind)
+                .map_err(|e| e.offset_by(start))?;
+        }
+        Ok(())
+    }
+
+    fn code(&mut self, patch: &mut Patch, kind: SignatureKind) -> Result<(), ClassParseError> {
+        patch.skip(4)?;
+        let code_length = patch.u32()?;
+        patch.skip(code_length as usize)?;
+        let exception_table_length = patch.u16()?;
+        patch.skip(8 * exception_table_length as usize)?;
+        self.attributes(patch, kind)
+    }
+
+    /// A LocalVariableTable or LocalVariableTypeTable, which share a layout.
+    fn local_variables(
+        &mut self,
+        patch: &mut Patch,
+        signatures: bool,
+    ) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            patch.skip(6)?;
+            if signatures {
+                self.patch_signature(patch, SignatureKind::Field)?;
+            } else {
+                self.patch_descriptor(patch)?;
+            }
+            patch.skip(2)?;
+        }
+        Ok(())
+    }
+
+    fn parameter_annotations(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u8()? {
+            self.annotations(patch)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            patch.skip(2)?;
+            self.patch_descriptor(patch)?;
+            self.attributes(patch, SignatureKind::Field)?;
+        }
+        Ok(())
+    }
+
+    fn inner_classes(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        for _ in 0..patch.u16()? {
+            let inner_class = self.pool.get_class_name(patch.u16()?)?;
+            patch.skip(2)?;
+            let inner_name_index = patch.u16()?;
+            if inner_name_index != 0
+                && let Some(mapped) = self.class_name(&inner_class)?
+            {
+                let inner_name = self.pool.get_utf8(inner_name_index)?;
+                if let Some(simple) = inner_class_name(&inner_class, &mapped, &inner_name) {
+                    patch.set_u16(self.builder.add_utf8(&simple)?);
+                }
+            }
+            patch.skip(2)?;
+        }
+        Ok(())
+    }
+
+    fn enclosing_method(&mut self, patch: &mut Patch) -> Result<(), ClassParseError> {
+        let owner = self.pool.get_class_name(patch.u16()?)?;
+        let method_index = patch.u16()?;
+        if method_index == 0 {
+            return Ok(());
+        }
+        let (name, descriptor) = self.pool.get_name_and_type(method_index)?;
+        if let Some(name) = self.remapper.map_method_name(&owner, &name, &descriptor) {
+            let descriptor = self.descriptor(&descriptor)?.unwrap_or(descriptor.into());
+            patch.set_u16(self.builder.add_name_and_type(&name, &descriptor)?);
+        }
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        name_index: u16,
+        info: &mut [u8],
+        kind: SignatureKind,
+    ) -> Result<(), ClassParseError> {
+        let name = self.pool.get_utf8(name_index)?;
+        let patch = &mut Patch::new(info);
+        let result = match name.as_ref() {
+            "Signature" => self.patch_signature(patch, kind),
+            "Code" => self.code(patch, kind),
+            "LocalVariableTable" => self.local_variables(patch, false),
+            "LocalVariableTypeTable" => self.local_variables(patch, true),
+            "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => self.annotations(patch),
+            "RuntimeVisibleParameterAnnotations" | "RuntimeInvisibleParameterAnnotations" => {
+                self.parameter_annotations(patch)
+            }
+            "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => {
+                self.type_annotations(patch)
+            }
+            "AnnotationDefault" => self.element_value(patch),
+            "Record" => self.record(patch),
+            "InnerClasses" => self.inner_classes(patch),
+            "EnclosingMethod" => self.enclosing_method(patch),
+            _ => Ok(()),
+        };
+        result.map_err(|e| e.within(0, ParseContext::Attribute(name.into_owned())))
+    }
+
+    fn class_attributes(
+        &mut self,
+        attributes: &mut [AttributeInfo],
+        kind: SignatureKind,
+    ) -> Result<(), ClassParseError> {
+        for attribute in attributes {
+            self.attribute(attribute.attribute_name_index, &mut attribute.info, kind)?;
+        }
+        Ok(())
+    }
+
+    /// Rename classes and rewrite descriptors in the constant pool itself, then give each
+    /// renamed member reference a NameAndType of its own.
+    fn constant_pool(&mut self) -> Result<(), ClassParseError> {
+        for (index, constant) in self.pool.iter() {
+            let replacement = match constant {
+                ConstantInfo::Class(_) => {
+                    let name = self.pool.get_class_name(index)?;
+                    match self.class_name(&name)? {
+                        Some(mapped) => ConstantInfo::Class(ClassConstant {
+                            name_index: self.builder.add_utf8(&mapped)?,
+                        }),
+                        None => continue,
+                    }
+                }
+                ConstantInfo::NameAndType(name_and_type) => {
+                    let descriptor = self.pool.get_utf8(name_and_type.descriptor_index)?;
+                    match self.descriptor(&descriptor)? {
+                        Some(mapped) => ConstantInfo::NameAndType(NameAndTypeConstant {
+                            name_index: name_and_type.name_index,
+                            descriptor_index: self.builder.add_utf8(&mapped)?,
+                        }),
+                        None => continue,
+                    }
+                }
+                ConstantInfo::MethodType(method_type) => {
+                    let descriptor = self.pool.get_utf8(method_type.descriptor_index)?;
+                    match self.descriptor(&descriptor)? {
+                        Some(mapped) => ConstantInfo::MethodType(MethodTypeConstant {
+                            descriptor_index: self.builder.add_utf8(&mapped)?,
+                        }),
+                        None => continue,
+                    }
+                }
+                ConstantInfo::Package(_) => {
+                    let name = self.pool.get_package_name(index)?;
+                    match self.remapper.map_package(&name) {
+                        Some(mapped) if mapped != name => ConstantInfo::Package(PackageConstant {
+                            name_index: self.builder.add_utf8(&mapped)?,
+                        }),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            self.builder.replace(index, replacement);
+        }
+
+        for (index, constant) in self.pool.iter() {
+            let is_field = match constant {
+                ConstantInfo::FieldRef(_) => true,
+                ConstantInfo::MethodRef(_) | ConstantInfo::InterfaceMethodRef(_) => false,
+                _ => continue,
+            };
+            let (owner, name, descriptor) = self.pool.resolve_member_ref(index)?;
+            let mapped = if is_field {
+                self.remapper.map_field_name(&owner, &name, &descriptor)
+            } else {
+                self.remapper.map_method_name(&owner, &name, &descriptor)
+            };
+            let Some(mapped) = mapped.filter(|mapped| *mapped != name) else {
+                continue;
+            };
+            let descriptor = self.descriptor(&descriptor)?.unwrap_or(descriptor.into());
+            let name_and_type_index = self.builder.add_name_and_type(&mapped, &descriptor)?;
+            let replacement = match constant {
+                ConstantInfo::FieldRef(r) => ConstantInfo::FieldRef(FieldRefConstant {
+                    class_index: r.class_index,
+                    name_and_type_index,
+                }),
+                ConstantInfo::MethodRef(r) => ConstantInfo::MethodRef(MethodRefConstant {
+                    class_index: r.class_index,
+                    name_and_type_index,
+                }),
+                ConstantInfo::InterfaceMethodRef(r) => {
+                    ConstantInfo::InterfaceMethodRef(InterfaceMethodRefConstant {
+                        class_index: r.class_index,
+                        name_and_type_index,
+                    })
+                }
+                _ => unreachable!(),
+            };
+            self.builder.replace(index, replacement);
+        }
+        Ok(())
+    }
+
+    /// The new name and descriptor indexes of a field or method of `owner`.
+    fn member(
+        &mut self,
+        owner: &str,
+        is_field: bool,
+        name_index: u16,
+        descriptor_index: u16,
+    ) -> Result<(u16, u16), ClassParseError> {
+        let name = self.pool.get_utf8(name_index)?;
+        let descriptor = self.pool.get_utf8(descriptor_index)?;
+        let mapped = if is_field {
+            self.remapper.map_field_name(owner, &name, &descriptor)
+        } else {
+            self.remapper.map_method_name(owner, &name, &descriptor)
+        };
+        let name_index = match mapped {
+            Some(mapped) if mapped != name => self.builder.add_utf8(&mapped)?,
+            _ => name_index,
+        };
+        let descriptor_index = match self.descriptor(&descriptor)? {
+            Some(mapped) => self.builder.add_utf8(&mapped)?,
+            None => descriptor_index,
+        };
+        Ok((name_index, descriptor_index))
+    }
+}
+
+/// Rename the classes, fields and methods of a class file, and every reference to them,
+/// as `remapper` directs.
+///
+/// The class keeps its constant pool entries at their original indexes, with new names
+/// added after them, so renaming nothing gives back an identical class file.
+///
+/// ```rust
+/// use classfile_parser::remapper::{Relocator, remap_class};
+///
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Generics").unwrap();
+/// let relocator = Relocator::new().relocate("java/util", "shaded/java/util");
+/// let remapped = remap_class(&class_file, &relocator).unwrap();
+///
+/// assert_eq!(remapped.super_class_name().unwrap().as_deref(), Some("shaded/java/util/ArrayList"));
+/// ```
+pub fn remap_class<R: Remapper + ?Sized>(
+    class_file: &ClassFile,
+    remapper: &R,
+) -> Result<ClassFile, ClassParseError> {
+    let mut rewriter = Rewriter {
+        remapper,
+        pool: class_file.constant_pool(),
+        builder: ConstantPoolBuilder::from_entries(&class_file.const_pool),
+    };
+    let this_class = class_file.this_class_name()?;
+    let mut remapped = class_file.clone();
+    rewriter.constant_pool()?;
+
+    for (i, field) in remapped.fields.iter_mut().enumerate() {
+        let at = |e: ClassParseError| e.within(0, ParseContext::Field(i as u16));
+        (field.name_index, field.descriptor_index) = rewriter
+            .member(&this_class, true, field.name_index, field.descriptor_index)
+            .map_err(at)?;
+        rewriter
+            .class_attributes(&mut field.attributes, SignatureKind::Field)
+            .map_err(at)?;
+    }
+    for (i, method) in remapped.methods.iter_mut().enumerate() {
+        let at = |e: ClassParseError| e.within(0, ParseContext::Method(i as u16));
+        (method.name_index, method.descriptor_index) = rewriter
+            .member(
+                &this_class,
+                false,
+                method.name_index,
+                method.descriptor_index,
+            )
+            .map_err(at)?;
+        rewriter
+            .class_attributes(&mut method.attributes, SignatureKind::Method)
+            .map_err(at)?;
+    }
+    rewriter.class_attributes(&mut remapped.attributes, SignatureKind::Class)?;
+
+    rewriter.builder.apply_to(&mut remapped);
+    Ok(remapped)
+}
+
+/// Parse a class file, remap it with `remap_class` and serialize the result.
+pub fn remap_class_bytes<R: Remapper + ?Sized>(
+    class_bytes: &[u8],
+    remapper: &R,
+) -> Result<Vec<u8>, ClassParseError> {
+    let class_file = crate::parse_class_from_bytes(class_bytes)?;
+    Ok(remap_class(&class_file, remapper)?.to_bytes()?)
+}
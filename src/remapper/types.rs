@@ -0,0 +1,77 @@
+/// Renames classes, packages, fields and methods. Each method returns `None` to keep the
+/// name it is given; by default nothing is renamed.
+///
+/// Class names are internal names such as `com/example/Foo$Bar`. Array types never reach
+/// `map_class`; their element class is mapped instead. Members are identified by their
+/// original owner, name and descriptor, before any class in them is renamed.
+pub trait Remapper {
+    fn map_class(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Map a package by its internal name, such as `com/example`, as it appears in the
+    /// Module and ModulePackages attributes of a `module-info` class.
+    fn map_package(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn map_field_name(&self, _owner: &str, _name: &str, _descriptor: &str) -> Option<String> {
+        None
+    }
+
+    fn map_method_name(&self, _owner: &str, _name: &str, _descriptor: &str) -> Option<String> {
+        None
+    }
+}
+
+/// A `Remapper` that moves packages and their subpackages to new prefixes, the way
+/// shading tools relocate bundled dependencies. The first matching relocation wins.
+///
+/// ```rust
+/// use classfile_parser::remapper::{Relocator, Remapper};
+///
+/// let relocator = Relocator::new().relocate("com/google", "shaded/com/google");
+/// assert_eq!(
+///     relocator.map_class("com/google/common/base/Strings").as_deref(),
+///     Some("shaded/com/google/common/base/Strings")
+/// );
+/// assert_eq!(relocator.map_class("com/googled/Foo"), None);
+/// assert_eq!(relocator.map_package("com/google").as_deref(), Some("shaded/com/google"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Relocator {
+    relocations: Vec<(String, String)>,
+}
+
+impl Relocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the package `from`, such as `com/google`, to `to`.
+    pub fn relocate(mut self, from: &str, to: &str) -> Self {
+        self.relocations.push((from.to_string(), to.to_string()));
+        self
+    }
+
+    fn map(&self, name: &str, is_package: bool) -> Option<String> {
+        self.relocations.iter().find_map(|(from, to)| {
+            let rest = name.strip_prefix(from.as_str())?;
+            if rest.starts_with('/') || (is_package && rest.is_empty()) {
+                Some(format!("{}{}", to, rest))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Remapper for Relocator {
+    fn map_class(&self, name: &str) -> Option<String> {
+        self.map(name, false)
+    }
+
+    fn map_package(&self, name: &str) -> Option<String> {
+        self.map(name, true)
+    }
+}
@@ -0,0 +1,8 @@
+mod parser;
+mod types;
+
+pub use self::parser::{
+    class_signature_parser, field_signature_parser, method_signature_parser,
+    reference_type_signature_parser,
+};
+pub use self::types::*;
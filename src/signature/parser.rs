@@ -0,0 +1,202 @@
+use nom::{
+    Err as BaseErr,
+    bytes::complete::take_till1,
+    character::complete::{char, one_of},
+    combinator::{eof, opt},
+    error::{Error, ErrorKind},
+    multi::{many0, many1},
+    sequence::{delimited, preceded, terminated},
+};
+
+use crate::descriptor::base_type_parser;
+use crate::signature::types::*;
+
+// Using a type alias here evades a Clippy warning about complex types.
+type Err<E> = BaseErr<Error<E>>;
+
+fn identifier_parser(input: &str) -> Result<(&str, &str), Err<&str>> {
+    take_till1(|c| matches!(c, '.' | ';' | '[' | '/' | '<' | '>' | ':'))(input)
+}
+
+fn type_argument_parser(input: &str) -> Result<(&str, TypeArgument), Err<&str>> {
+    if let Ok((rest, _)) = char::<_, Error<&str>>('*')(input) {
+        return Ok((rest, TypeArgument::Any));
+    }
+    let (input, indicator) = opt(one_of("+-"))(input)?;
+    let (input, bound) = reference_type_signature_parser(input)?;
+    let argument = match indicator {
+        Some('+') => TypeArgument::Extends(bound),
+        Some(_) => TypeArgument::Super(bound),
+        None => TypeArgument::Exact(bound),
+    };
+    Ok((input, argument))
+}
+
+fn type_arguments_parser(input: &str) -> Result<(&str, Vec<TypeArgument>), Err<&str>> {
+    let (input, arguments) =
+        opt(delimited(char('<'), many1(type_argument_parser), char('>')))(input)?;
+    Ok((input, arguments.unwrap_or_default()))
+}
+
+fn simple_class_type_signature_parser(
+    input: &str,
+) -> Result<(&str, SimpleClassTypeSignature), Err<&str>> {
+    let (input, name) = identifier_parser(input)?;
+    let (input, type_arguments) = type_arguments_parser(input)?;
+    Ok((
+        input,
+        SimpleClassTypeSignature {
+            name: name.to_string(),
+            type_arguments,
+        },
+    ))
+}
+
+fn class_type_signature_parser(input: &str) -> Result<(&str, ClassTypeSignature), Err<&str>> {
+    let (mut input, _) = char('L')(input)?;
+    // Identifiers followed by a `/` belong to the package specifier
+    let mut package = Vec::new();
+    loop {
+        let (rest, identifier) = identifier_parser(input)?;
+        match char::<_, Error<&str>>('/')(rest) {
+            Ok((rest, _)) => {
+                package.push(identifier);
+                input = rest;
+            }
+            Err(_) => break,
+        }
+    }
+    let (input, class) = simple_class_type_signature_parser(input)?;
+    let (input, suffix) = many0(preceded(char('.'), simple_class_type_signature_parser))(input)?;
+    let (input, _) = char(';')(input)?;
+    Ok((
+        input,
+        ClassTypeSignature {
+            package: package.join("/"),
+            class,
+            suffix,
+        },
+    ))
+}
+
+fn type_variable_signature_parser(input: &str) -> Result<(&str, String), Err<&str>> {
+    let (input, name) = delimited(char('T'), identifier_parser, char(';'))(input)?;
+    Ok((input, name.to_string()))
+}
+
+/// Parse a reference type signature: a class type, a type variable or an array type.
+pub fn reference_type_signature_parser(
+    input: &str,
+) -> Result<(&str, ReferenceTypeSignature), Err<&str>> {
+    match input.chars().next() {
+        Some('L') => {
+            let (input, class) = class_type_signature_parser(input)?;
+            Ok((input, ReferenceTypeSignature::Class(class)))
+        }
+        Some('T') => {
+            let (input, name) = type_variable_signature_parser(input)?;
+            Ok((input, ReferenceTypeSignature::TypeVariable(name)))
+        }
+        Some('[') => {
+            let (input, component) = java_type_signature_parser(&input[1..])?;
+            Ok((input, ReferenceTypeSignature::Array(Box::new(component))))
+        }
+        _ => Err(BaseErr::Error(Error::new(input, ErrorKind::Tag))),
+    }
+}
+
+fn java_type_signature_parser(input: &str) -> Result<(&str, JavaTypeSignature), Err<&str>> {
+    if let Ok((rest, base_type)) = base_type_parser(input) {
+        return Ok((rest, JavaTypeSignature::Base(base_type)));
+    }
+    let (input, reference) = reference_type_signature_parser(input)?;
+    Ok((input, JavaTypeSignature::Reference(reference)))
+}
+
+fn type_parameter_parser(input: &str) -> Result<(&str, TypeParameter), Err<&str>> {
+    let (input, name) = identifier_parser(input)?;
+    let (input, class_bound) = preceded(char(':'), opt(reference_type_signature_parser))(input)?;
+    let (input, interface_bounds) =
+        many0(preceded(char(':'), reference_type_signature_parser))(input)?;
+    Ok((
+        input,
+        TypeParameter {
+            name: name.to_string(),
+            class_bound,
+            interface_bounds,
+        },
+    ))
+}
+
+fn type_parameters_parser(input: &str) -> Result<(&str, Vec<TypeParameter>), Err<&str>> {
+    let (input, parameters) = opt(delimited(
+        char('<'),
+        many1(type_parameter_parser),
+        char('>'),
+    ))(input)?;
+    Ok((input, parameters.unwrap_or_default()))
+}
+
+/// Parse the signature of a class or interface.
+pub fn class_signature_parser(input: &str) -> Result<(&str, ClassSignature), Err<&str>> {
+    let (input, type_parameters) = type_parameters_parser(input)?;
+    let (input, superclass) = class_type_signature_parser(input)?;
+    let (input, interfaces) = many0(class_type_signature_parser)(input)?;
+    Ok((
+        input,
+        ClassSignature {
+            type_parameters,
+            superclass,
+            interfaces,
+        },
+    ))
+}
+
+fn result_parser(input: &str) -> Result<(&str, ResultSignature), Err<&str>> {
+    if let Ok((rest, _)) = char::<_, Error<&str>>('V')(input) {
+        return Ok((rest, ResultSignature::Void));
+    }
+    let (input, result) = java_type_signature_parser(input)?;
+    Ok((input, ResultSignature::Type(result)))
+}
+
+/// Parse the signature of a method or constructor.
+pub fn method_signature_parser(input: &str) -> Result<(&str, MethodSignature), Err<&str>> {
+    let (input, type_parameters) = type_parameters_parser(input)?;
+    let (input, params) =
+        delimited(char('('), many0(java_type_signature_parser), char(')'))(input)?;
+    let (input, result) = result_parser(input)?;
+    let (input, throws) = many0(preceded(char('^'), reference_type_signature_parser))(input)?;
+    Ok((
+        input,
+        MethodSignature {
+            type_parameters,
+            params,
+            result,
+            throws,
+        },
+    ))
+}
+
+/// Parse the signature of a field, record component or local variable.
+pub fn field_signature_parser(input: &str) -> Result<(&str, FieldSignature), Err<&str>> {
+    let (input, field_type) = reference_type_signature_parser(input)?;
+    Ok((input, FieldSignature(field_type)))
+}
+
+/// Run `parser` over the whole of `signature`, reporting the offset of the first byte that
+/// could not be consumed.
+pub(crate) fn parse_complete<T>(
+    signature: &str,
+    parser: impl Fn(&str) -> Result<(&str, T), Err<&str>>,
+) -> Result<T, SignatureError> {
+    let error = |rest: &str| SignatureError {
+        signature: signature.to_string(),
+        offset: signature.len() - rest.len(),
+    };
+    match terminated(parser, eof)(signature) {
+        Ok((_, value)) => Ok(value),
+        Err(BaseErr::Error(e)) | Err(BaseErr::Failure(e)) => Err(error(e.input)),
+        Err(BaseErr::Incomplete(_)) => Err(error("")),
+    }
+}
@@ -0,0 +1,450 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::descriptor::BaseType;
+use crate::signature::parser::{
+    class_signature_parser, field_signature_parser, method_signature_parser, parse_complete,
+};
+
+/// Any type that can appear in a signature.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum JavaTypeSignature {
+    Base(BaseType),
+    Reference(ReferenceTypeSignature),
+}
+
+impl JavaTypeSignature {
+    /// The type as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        match self {
+            JavaTypeSignature::Base(base_type) => base_type.java_name().to_string(),
+            JavaTypeSignature::Reference(reference) => reference.to_java(),
+        }
+    }
+}
+
+impl fmt::Display for JavaTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JavaTypeSignature::Base(base_type) => write!(f, "{}", base_type.descriptor_char()),
+            JavaTypeSignature::Reference(reference) => write!(f, "{}", reference),
+        }
+    }
+}
+
+/// A class type, type variable or array type.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ReferenceTypeSignature {
+    Class(ClassTypeSignature),
+    /// A type variable, by name such as `T`.
+    TypeVariable(String),
+    /// An array type with the given component type.
+    Array(Box<JavaTypeSignature>),
+}
+
+impl ReferenceTypeSignature {
+    /// The type as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        match self {
+            ReferenceTypeSignature::Class(class) => class.to_java(),
+            ReferenceTypeSignature::TypeVariable(name) => name.clone(),
+            ReferenceTypeSignature::Array(component) => component.to_java() + "[]",
+        }
+    }
+}
+
+impl fmt::Display for ReferenceTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceTypeSignature::Class(class) => write!(f, "{}", class),
+            ReferenceTypeSignature::TypeVariable(name) => write!(f, "T{};", name),
+            ReferenceTypeSignature::Array(component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+/// A possibly parameterized class type such as `Ljava/util/Map<TK;TV;>.Entry<TK;TV;>;`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ClassTypeSignature {
+    /// The package in internal form such as `java/util`, empty for the default package.
+    pub package: String,
+    /// The outermost class.
+    pub class: SimpleClassTypeSignature,
+    /// The inner classes, from the outermost to the innermost.
+    pub suffix: Vec<SimpleClassTypeSignature>,
+}
+
+impl ClassTypeSignature {
+    /// The type as it would be written in Java source, such as
+    /// `java.util.Map<K, V>.Entry<K, V>`.
+    pub fn to_java(&self) -> String {
+        let mut java = String::new();
+        if !self.package.is_empty() {
+            java.push_str(&self.package.replace('/', "."));
+            java.push('.');
+        }
+        java.push_str(&self.class.to_java());
+        for inner in &self.suffix {
+            java.push('.');
+            java.push_str(&inner.to_java());
+        }
+        java
+    }
+}
+
+impl fmt::Display for ClassTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L")?;
+        if !self.package.is_empty() {
+            write!(f, "{}/", self.package)?;
+        }
+        write!(f, "{}", self.class)?;
+        for inner in &self.suffix {
+            write!(f, ".{}", inner)?;
+        }
+        write!(f, ";")
+    }
+}
+
+/// A single class name with its type arguments.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SimpleClassTypeSignature {
+    pub name: String,
+    pub type_arguments: Vec<TypeArgument>,
+}
+
+impl SimpleClassTypeSignature {
+    fn to_java(&self) -> String {
+        if self.type_arguments.is_empty() {
+            return self.name.clone();
+        }
+        let arguments: Vec<_> = self
+            .type_arguments
+            .iter()
+            .map(TypeArgument::to_java)
+            .collect();
+        format!("{}<{}>", self.name, arguments.join(", "))
+    }
+}
+
+impl fmt::Display for SimpleClassTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.type_arguments.is_empty() {
+            write!(f, "<")?;
+            for argument in &self.type_arguments {
+                write!(f, "{}", argument)?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+}
+
+/// A type argument of a parameterized class type.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TypeArgument {
+    /// The unbounded wildcard `?`.
+    Any,
+    /// A concrete type argument.
+    Exact(ReferenceTypeSignature),
+    /// A wildcard with an upper bound, `? extends T`.
+    Extends(ReferenceTypeSignature),
+    /// A wildcard with a lower bound, `? super T`.
+    Super(ReferenceTypeSignature),
+}
+
+impl TypeArgument {
+    /// The type argument as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        match self {
+            TypeArgument::Any => "?".to_string(),
+            TypeArgument::Exact(bound) => bound.to_java(),
+            TypeArgument::Extends(bound) => format!("? extends {}", bound.to_java()),
+            TypeArgument::Super(bound) => format!("? super {}", bound.to_java()),
+        }
+    }
+}
+
+impl fmt::Display for TypeArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeArgument::Any => write!(f, "*"),
+            TypeArgument::Exact(bound) => write!(f, "{}", bound),
+            TypeArgument::Extends(bound) => write!(f, "+{}", bound),
+            TypeArgument::Super(bound) => write!(f, "-{}", bound),
+        }
+    }
+}
+
+/// A type parameter declaration such as `T extends Comparable<T>`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TypeParameter {
+    pub name: String,
+    /// The class bound, absent when the parameter is only bounded by interfaces.
+    pub class_bound: Option<ReferenceTypeSignature>,
+    pub interface_bounds: Vec<ReferenceTypeSignature>,
+}
+
+impl TypeParameter {
+    /// The type parameter as it would be written in Java source. A lone `java.lang.Object`
+    /// bound is left out.
+    pub fn to_java(&self) -> String {
+        let bounds: Vec<_> = self
+            .class_bound
+            .iter()
+            .chain(&self.interface_bounds)
+            .map(ReferenceTypeSignature::to_java)
+            .collect();
+        if bounds.is_empty() || bounds == ["java.lang.Object"] {
+            self.name.clone()
+        } else {
+            format!("{} extends {}", self.name, bounds.join(" & "))
+        }
+    }
+}
+
+impl fmt::Display for TypeParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name)?;
+        if let Some(class_bound) = &self.class_bound {
+            write!(f, "{}", class_bound)?;
+        }
+        for bound in &self.interface_bounds {
+            write!(f, ":{}", bound)?;
+        }
+        Ok(())
+    }
+}
+
+fn type_parameters_to_java(type_parameters: &[TypeParameter]) -> String {
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+    let parameters: Vec<_> = type_parameters.iter().map(TypeParameter::to_java).collect();
+    format!("<{}>", parameters.join(", "))
+}
+
+fn fmt_type_parameters(
+    f: &mut fmt::Formatter<'_>,
+    type_parameters: &[TypeParameter],
+) -> fmt::Result {
+    if !type_parameters.is_empty() {
+        write!(f, "<")?;
+        for parameter in type_parameters {
+            write!(f, "{}", parameter)?;
+        }
+        write!(f, ">")?;
+    }
+    Ok(())
+}
+
+/// The generic signature of a class or interface.
+///
+/// ```rust
+/// use classfile_parser::signature::ClassSignature;
+///
+/// let signature: ClassSignature =
+///     "<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;Ljava/util/List<TT;>;"
+///         .parse()
+///         .unwrap();
+/// assert_eq!(
+///     signature.to_java("Sorted"),
+///     "Sorted<T extends java.lang.Comparable<T>> extends java.lang.Object implements java.util.List<T>"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ClassSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub superclass: ClassTypeSignature,
+    pub interfaces: Vec<ClassTypeSignature>,
+}
+
+impl ClassSignature {
+    /// The class declaration as it would be written in Java source, using `name` as the
+    /// class name.
+    pub fn to_java(&self, name: &str) -> String {
+        let mut java = format!(
+            "{}{} extends {}",
+            name,
+            type_parameters_to_java(&self.type_parameters),
+            self.superclass.to_java()
+        );
+        if !self.interfaces.is_empty() {
+            let interfaces: Vec<_> = self
+                .interfaces
+                .iter()
+                .map(ClassTypeSignature::to_java)
+                .collect();
+            java.push_str(" implements ");
+            java.push_str(&interfaces.join(", "));
+        }
+        java
+    }
+}
+
+impl fmt::Display for ClassSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_type_parameters(f, &self.type_parameters)?;
+        write!(f, "{}", self.superclass)?;
+        for interface in &self.interfaces {
+            write!(f, "{}", interface)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ClassSignature {
+    type Err = SignatureError;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        parse_complete(signature, class_signature_parser)
+    }
+}
+
+/// The result type of a method signature.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ResultSignature {
+    Void,
+    Type(JavaTypeSignature),
+}
+
+impl ResultSignature {
+    /// The type as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        match self {
+            ResultSignature::Void => "void".to_string(),
+            ResultSignature::Type(result) => result.to_java(),
+        }
+    }
+}
+
+impl fmt::Display for ResultSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResultSignature::Void => write!(f, "V"),
+            ResultSignature::Type(result) => write!(f, "{}", result),
+        }
+    }
+}
+
+/// The generic signature of a method or constructor.
+///
+/// ```rust
+/// use classfile_parser::signature::MethodSignature;
+///
+/// let signature: MethodSignature =
+///     "<E:Ljava/lang/Exception;>(Ljava/util/List<+Ljava/lang/Number;>;I)V^TE;"
+///         .parse()
+///         .unwrap();
+/// assert_eq!(
+///     signature.to_java("sum"),
+///     "<E extends java.lang.Exception> void sum(java.util.List<? extends java.lang.Number>, int) throws E"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MethodSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub params: Vec<JavaTypeSignature>,
+    pub result: ResultSignature,
+    /// The thrown class types and type variables.
+    pub throws: Vec<ReferenceTypeSignature>,
+}
+
+impl MethodSignature {
+    /// The method declaration as it would be written in Java source, using `name` as the
+    /// method name.
+    pub fn to_java(&self, name: &str) -> String {
+        let mut java = type_parameters_to_java(&self.type_parameters);
+        if !java.is_empty() {
+            java.push(' ');
+        }
+        let params: Vec<_> = self.params.iter().map(JavaTypeSignature::to_java).collect();
+        java.push_str(&format!(
+            "{} {}({})",
+            self.result.to_java(),
+            name,
+            params.join(", ")
+        ));
+        if !self.throws.is_empty() {
+            let throws: Vec<_> = self
+                .throws
+                .iter()
+                .map(ReferenceTypeSignature::to_java)
+                .collect();
+            java.push_str(" throws ");
+            java.push_str(&throws.join(", "));
+        }
+        java
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_type_parameters(f, &self.type_parameters)?;
+        write!(f, "(")?;
+        for param in &self.params {
+            write!(f, "{}", param)?;
+        }
+        write!(f, "){}", self.result)?;
+        for thrown in &self.throws {
+            write!(f, "^{}", thrown)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MethodSignature {
+    type Err = SignatureError;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        parse_complete(signature, method_signature_parser)
+    }
+}
+
+/// The generic signature of a field, record component or local variable.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FieldSignature(pub ReferenceTypeSignature);
+
+impl FieldSignature {
+    /// The type as it would be written in Java source.
+    pub fn to_java(&self) -> String {
+        self.0.to_java()
+    }
+}
+
+impl fmt::Display for FieldSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for FieldSignature {
+    type Err = SignatureError;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        parse_complete(signature, field_signature_parser)
+    }
+}
+
+/// An error returned when a signature string is malformed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignatureError {
+    /// The signature that failed to parse.
+    pub signature: String,
+    /// The byte offset within `signature` where parsing failed.
+    pub offset: usize,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid signature {:?} at offset {}",
+            self.signature, self.offset
+        )
+    }
+}
+
+impl Error for SignatureError {}
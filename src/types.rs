@@ -1,9 +1,18 @@
-use crate::attribute_info::AttributeInfo;
-use crate::constant_info::ConstantInfo;
+use crate::attribute_info::{
+    AttributeInfo, ModuleDescriptor, TypedAttribute, has_attribute, has_deprecated,
+    typed_attributes_parser,
+};
+use crate::constant_info::{ConstantInfo, ConstantPool, ConstantPoolError};
+use crate::dependencies::{DependencyKind, referenced_classes};
+use crate::error::ClassParseError;
 use crate::field_info::FieldInfo;
 use crate::method_info::MethodInfo;
 
-use binrw::binrw;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Cursor, Write};
+
+use binrw::{BinWrite, binrw};
 
 #[derive(Clone, Debug)]
 #[binrw]
@@ -31,6 +40,184 @@ pub struct ClassFile {
     pub attributes: Vec<AttributeInfo>,
 }
 
+impl ClassFile {
+    /// A view of the constant pool with checked, 1-based accessors.
+    pub fn constant_pool(&self) -> ConstantPool<'_> {
+        ConstantPool::new(&self.const_pool)
+    }
+
+    /// The internal name of this class, such as `java/lang/String`.
+    pub fn this_class_name(&self) -> Result<Cow<'_, str>, ConstantPoolError> {
+        self.constant_pool().get_class_name(self.this_class)
+    }
+
+    /// The internal name of the superclass, `None` for `java/lang/Object` and modules.
+    pub fn super_class_name(&self) -> Result<Option<Cow<'_, str>>, ConstantPoolError> {
+        match self.super_class {
+            0 => Ok(None),
+            index => self.constant_pool().get_class_name(index).map(Some),
+        }
+    }
+
+    /// The internal names of the directly implemented interfaces, in declaration order.
+    pub fn interface_names(&self) -> Result<Vec<Cow<'_, str>>, ConstantPoolError> {
+        let pool = self.constant_pool();
+        self.interfaces
+            .iter()
+            .map(|&index| pool.get_class_name(index))
+            .collect()
+    }
+
+    /// Decode the class level attributes (SourceFile, InnerClasses, BootstrapMethods, ...)
+    /// using this class file's constant pool to resolve attribute names.
+    pub fn typed_attributes(&self) -> Result<Vec<TypedAttribute>, ClassParseError> {
+        typed_attributes_parser(&self.attributes, &self.const_pool)
+    }
+
+    /// Whether this class is marked deprecated, by a Deprecated attribute or a
+    /// `@java.lang.Deprecated` annotation.
+    pub fn is_deprecated(&self) -> bool {
+        has_deprecated(&self.attributes, &self.const_pool)
+    }
+
+    /// Whether this class was generated by the compiler, by the `ACC_SYNTHETIC` flag or a
+    /// Synthetic attribute.
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags.contains(ClassAccessFlags::SYNTHETIC)
+            || has_attribute(&self.attributes, &self.const_pool, "Synthetic")
+    }
+
+    /// The resolved module declaration of a `module-info` class, `None` if the class has no
+    /// Module attribute.
+    ///
+    /// ```rust
+    /// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/module-info.class");
+    /// let class_file = classfile_parser::parse_class_from_bytes(classfile_bytes).unwrap();
+    ///
+    /// let module = class_file.module_descriptor().unwrap().unwrap();
+    /// assert_eq!(module.name, "my.module");
+    /// assert_eq!(module.exports[0].package, "com/some");
+    /// ```
+    pub fn module_descriptor(&self) -> Result<Option<ModuleDescriptor>, ClassParseError> {
+        let (mut module, mut packages, mut main_class) = (None, None, None);
+        for attribute in self.typed_attributes()? {
+            match attribute {
+                TypedAttribute::Module(attribute) => module = Some(attribute),
+                TypedAttribute::ModulePackages(attribute) => packages = Some(attribute),
+                TypedAttribute::ModuleMainClass(attribute) => main_class = Some(attribute),
+                _ => {}
+            }
+        }
+        let Some(module) = module else {
+            return Ok(None);
+        };
+        let descriptor = ModuleDescriptor::new(
+            &module,
+            packages.as_ref(),
+            main_class.as_ref(),
+            &self.constant_pool(),
+        )?;
+        Ok(Some(descriptor))
+    }
+
+    /// The classes this class refers to, each marked `Api` if it appears in the superclass,
+    /// interfaces, annotations or the signature of the class or a non-private member, and
+    /// `Implementation` if it is only used by private members or bytecode. Array types are
+    /// reduced to their element class; primitives and the class itself are left out.
+    ///
+    /// ```rust
+    /// use classfile_parser::dependencies::DependencyKind;
+    ///
+    /// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Dispatch").unwrap();
+    /// let classes = class_file.referenced_classes().unwrap();
+    /// assert_eq!(classes["Dispatch$Greeter"], DependencyKind::Api);
+    /// assert_eq!(classes["java/io/PrintStream"], DependencyKind::Implementation);
+    /// ```
+    pub fn referenced_classes(&self) -> Result<BTreeMap<String, DependencyKind>, ClassParseError> {
+        referenced_classes(self)
+    }
+
+    /// Serialize this class file and write it to `writer`.
+    ///
+    /// The `*_count`, `const_pool_size` and `attribute_length` fields are written as they are,
+    /// so they must agree with the lengths of the collections they describe.
+    ///
+    /// ```rust
+    /// let classfile_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+    /// let class_file = classfile_parser::parse_class_from_bytes(classfile_bytes).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// class_file.write_to(&mut out).unwrap();
+    /// assert_eq!(&out[..], &classfile_bytes[..]);
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes()?)
+    }
+
+    /// Serialize this class file into a new byte vector.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        self.check_counts()?;
+        let mut cursor = Cursor::new(Vec::new());
+        self.write(&mut cursor).map_err(|e| match e {
+            binrw::Error::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+        })?;
+        Ok(cursor.into_inner())
+    }
+
+    fn check_counts(&self) -> io::Result<()> {
+        fn check(name: &str, count: usize, len: usize) -> io::Result<()> {
+            if count == len {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is {} but there are {} entries", name, count, len),
+                ))
+            }
+        }
+        fn check_attributes(attributes: &[AttributeInfo], count: u16) -> io::Result<()> {
+            check("attributes_count", count as usize, attributes.len())?;
+            for attribute in attributes {
+                check(
+                    "attribute_length",
+                    attribute.attribute_length as usize,
+                    attribute.info.len(),
+                )?;
+            }
+            Ok(())
+        }
+
+        check(
+            "const_pool_size",
+            self.const_pool_size.saturating_sub(1) as usize,
+            self.const_pool.len(),
+        )?;
+        check(
+            "interfaces_count",
+            self.interfaces_count as usize,
+            self.interfaces.len(),
+        )?;
+        check(
+            "fields_count",
+            self.fields_count as usize,
+            self.fields.len(),
+        )?;
+        for field in &self.fields {
+            check_attributes(&field.attributes, field.attributes_count)?;
+        }
+        check(
+            "methods_count",
+            self.methods_count as usize,
+            self.methods.len(),
+        )?;
+        for method in &self.methods {
+            check_attributes(&method.attributes, method.attributes_count)?;
+        }
+        check_attributes(&self.attributes, self.attributes_count)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[binrw]
 pub struct ClassAccessFlags(u16);
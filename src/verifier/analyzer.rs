@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::attribute_info::ExceptionEntry;
+use crate::code_attribute::Instruction;
+use crate::control_flow::flow;
+
+use super::interpreter::{Interpreter, array_of};
+use super::types::*;
+
+/// Lay out one entry per value as slots, where a long or double takes two.
+pub(crate) fn to_slots(values: &[VerificationType]) -> Vec<VerificationType> {
+    let mut slots = Vec::with_capacity(values.len());
+    for value in values {
+        slots.push(value.clone());
+        if value.is_category2() {
+            slots.push(VerificationType::Top);
+        }
+    }
+    slots
+}
+
+/// The most specific class or array type two class or array types are both assignable to.
+fn common_supertype(hierarchy: &dyn TypeHierarchy, a: &str, b: &str) -> String {
+    if a == b {
+        return a.to_string();
+    }
+    match (a.strip_prefix('['), b.strip_prefix('[')) {
+        (Some(a), Some(b)) => match (component_class(a), component_class(b)) {
+            (Some(a), Some(b)) => array_of(&common_supertype(hierarchy, a, b)),
+            _ => "java/lang/Object".to_string(),
+        },
+        (None, None) => hierarchy.common_superclass(a, b),
+        _ => "java/lang/Object".to_string(),
+    }
+}
+
+/// The type both `a` and `b` can be used as, `None` if there is none but `Top`.
+fn merge_type(
+    hierarchy: &dyn TypeHierarchy,
+    a: &VerificationType,
+    b: &VerificationType,
+) -> Option<VerificationType> {
+    use VerificationType::*;
+    match (a, b) {
+        (a, b) if a == b => Some(a.clone()),
+        (Null, Object(_)) => Some(b.clone()),
+        (Object(_), Null) => Some(a.clone()),
+        (Object(a), Object(b)) => Some(Object(common_supertype(hierarchy, a, b))),
+        _ => None,
+    }
+}
+
+fn merge(hierarchy: &dyn TypeHierarchy, a: &Frame, b: &Frame) -> Option<Frame> {
+    if a.stack.len() != b.stack.len() {
+        return None;
+    }
+    let locals = a
+        .locals
+        .iter()
+        .zip(&b.locals)
+        .map(|(a, b)| merge_type(hierarchy, a, b).unwrap_or(VerificationType::Top))
+        .collect();
+    let stack = a
+        .stack
+        .iter()
+        .zip(&b.stack)
+        .map(|(a, b)| merge_type(hierarchy, a, b))
+        .collect::<Option<_>>()?;
+    Some(Frame { locals, stack })
+}
+
+/// A worklist data-flow analysis over the instructions of one method.
+pub(crate) struct Analyzer<'a> {
+    pub(crate) interpreter: Interpreter<'a>,
+    pub(crate) instructions: &'a [(usize, Instruction)],
+    pub(crate) exception_table: &'a [ExceptionEntry],
+    pub(crate) max_stack: u16,
+    pub(crate) hierarchy: &'a dyn TypeHierarchy,
+    /// StackMapTable frames in slot form, by address.
+    pub(crate) declared: BTreeMap<usize, Frame>,
+    /// Whether every branch target and handler needs a StackMapTable frame.
+    pub(crate) frames_required: bool,
+}
+
+struct State {
+    frames: Vec<Option<Frame>>,
+    worklist: BTreeSet<usize>,
+    errors: Vec<VerifyError>,
+}
+
+impl State {
+    fn error(&mut self, address: usize, kind: VerifyErrorKind) {
+        let error = VerifyError { address, kind };
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+}
+
+impl Analyzer<'_> {
+    /// Run the analysis from `initial`, the frame at the first instruction.
+    pub(crate) fn run(&self, initial: Frame) -> MethodVerification {
+        let indices: HashMap<usize, usize> = self
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(i, &(address, _))| (address, i))
+            .collect();
+        let mut state = State {
+            frames: vec![None; self.instructions.len()],
+            worklist: BTreeSet::new(),
+            errors: Vec::new(),
+        };
+        for &address in self.declared.keys() {
+            if !indices.contains_key(&address) {
+                state.error(address, VerifyErrorKind::FrameOffset);
+            }
+        }
+        if self.instructions.is_empty() {
+            return MethodVerification {
+                errors: state.errors,
+                ..MethodVerification::default()
+            };
+        }
+
+        let mut max_depth = initial.stack.len();
+        self.propagate(&mut state, 0, initial, false);
+        while let Some(i) = state.worklist.pop_first() {
+            let (address, instruction) = &self.instructions[i];
+            let before = state.frames[i].clone().unwrap();
+
+            for entry in self.exception_table {
+                if (entry.start_pc as usize..entry.end_pc as usize).contains(address) {
+                    self.propagate_handler(&mut state, &indices, entry, &before);
+                }
+            }
+
+            let mut after = before.clone();
+            if let Err(kind) = self.interpreter.execute(&mut after, *address, instruction) {
+                state.error(*address, kind);
+                continue;
+            }
+            max_depth = max_depth.max(after.stack.len());
+            if after.stack.len() > self.max_stack as usize {
+                state.error(
+                    *address,
+                    VerifyErrorKind::StackOverflow {
+                        depth: after.stack.len(),
+                        max_stack: self.max_stack,
+                    },
+                );
+            }
+            if after.locals != before.locals {
+                for entry in self.exception_table {
+                    if (entry.start_pc as usize..entry.end_pc as usize).contains(address) {
+                        self.propagate_handler(&mut state, &indices, entry, &after);
+                    }
+                }
+            }
+
+            let (targets, falls_through) = flow(instruction, *address);
+            for (target, _) in targets {
+                match usize::try_from(target).ok().and_then(|t| indices.get(&t)) {
+                    Some(&target) => self.propagate(&mut state, target, after.clone(), true),
+                    None => state.error(*address, VerifyErrorKind::InvalidBranchTarget(target)),
+                }
+            }
+            if falls_through {
+                if i + 1 < self.instructions.len() {
+                    self.propagate(&mut state, i + 1, after, false);
+                } else {
+                    state.error(*address, VerifyErrorKind::FallsOffEnd);
+                }
+            }
+        }
+
+        let frames = self
+            .instructions
+            .iter()
+            .zip(state.frames)
+            .filter_map(|(&(address, _), frame)| Some((address, frame?)))
+            .collect();
+        state.errors.sort_by_key(|error| error.address);
+        MethodVerification {
+            frames,
+            max_stack: max_depth as u16,
+            errors: state.errors,
+        }
+    }
+
+    fn propagate_handler(
+        &self,
+        state: &mut State,
+        indices: &HashMap<usize, usize>,
+        entry: &ExceptionEntry,
+        frame: &Frame,
+    ) {
+        let handler = entry.handler_pc as usize;
+        let caught = match entry.catch_type {
+            0 => "java/lang/Throwable".into(),
+            index => match self.interpreter.pool.get_class_name(index) {
+                Ok(name) => name,
+                Err(e) => return state.error(handler, e.into()),
+            },
+        };
+        let Some(&target) = indices.get(&handler) else {
+            return state.error(handler, VerifyErrorKind::FrameOffset);
+        };
+        let frame = Frame {
+            locals: frame.locals.clone(),
+            stack: vec![VerificationType::Object(caught.into_owned())],
+        };
+        self.propagate(state, target, frame, true);
+    }
+
+    /// Flow `frame` into the instruction at index `target`, reached by a jump or handler if
+    /// `jump` is set and by falling through otherwise.
+    fn propagate(&self, state: &mut State, target: usize, frame: Frame, jump: bool) {
+        let address = self.instructions[target].0;
+        if let Some(declared) = self.declared.get(&address) {
+            if !frame.is_assignable_to(declared) {
+                state.error(
+                    address,
+                    VerifyErrorKind::FrameMismatch {
+                        computed: frame,
+                        declared: declared.clone(),
+                    },
+                );
+            }
+            if state.frames[target].is_none() {
+                state.frames[target] = Some(declared.clone());
+                state.worklist.insert(target);
+            }
+            return;
+        }
+        if jump && self.frames_required {
+            state.error(address, VerifyErrorKind::MissingFrame);
+        }
+        match &state.frames[target] {
+            None => {
+                state.frames[target] = Some(frame);
+                state.worklist.insert(target);
+            }
+            Some(old) => match merge(self.hierarchy, old, &frame) {
+                Some(merged) if &merged != old => {
+                    state.frames[target] = Some(merged);
+                    state.worklist.insert(target);
+                }
+                Some(_) => {}
+                None => state.error(address, VerifyErrorKind::InconsistentStack),
+            },
+        }
+    }
+}
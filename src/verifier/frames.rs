@@ -0,0 +1,267 @@
+use std::collections::BTreeSet;
+
+use crate::attribute_info::{ExceptionEntry, StackMapFrame, VerificationTypeInfo};
+use crate::code_attribute::Instruction;
+use crate::constant_info::ConstantPoolBuilder;
+use crate::control_flow::flow;
+use crate::descriptor::MethodDescriptor;
+use crate::method_info::MethodAccessFlags;
+use crate::types::ClassFile;
+
+use super::analyzer::{Analyzer, to_slots};
+use super::interpreter::Interpreter;
+use super::types::*;
+use super::{entry_locals, news, pad_locals};
+
+/// Lay out slots as one entry per value, dropping the `Top` after each long or double.
+fn to_entries(slots: &[VerificationType]) -> Vec<VerificationType> {
+    let mut entries = Vec::with_capacity(slots.len());
+    let mut slots = slots.iter();
+    while let Some(slot) = slots.next() {
+        entries.push(slot.clone());
+        if slot.is_category2() {
+            slots.next();
+        }
+    }
+    entries
+}
+
+fn type_info(
+    builder: &mut ConstantPoolBuilder,
+    value: &VerificationType,
+) -> Result<VerificationTypeInfo, VerifyErrorKind> {
+    Ok(match value {
+        VerificationType::Top => VerificationTypeInfo::Top,
+        VerificationType::Integer => VerificationTypeInfo::Integer,
+        VerificationType::Float => VerificationTypeInfo::Float,
+        VerificationType::Long => VerificationTypeInfo::Long,
+        VerificationType::Double => VerificationTypeInfo::Double,
+        VerificationType::Null => VerificationTypeInfo::Null,
+        VerificationType::UninitializedThis => VerificationTypeInfo::UninitializedThis,
+        VerificationType::Object(class_name) => VerificationTypeInfo::Object {
+            class: builder
+                .add_class(class_name)
+                .map_err(VerifyErrorKind::Constant)?,
+        },
+        VerificationType::Uninitialized(offset) => {
+            VerificationTypeInfo::Uninitialized { offset: *offset }
+        }
+    })
+}
+
+/// Encode a frame relative to the locals of the previous one, choosing the most compact
+/// frame type that can express it.
+fn encode_frame(
+    builder: &mut ConstantPoolBuilder,
+    offset_delta: u16,
+    previous: &[VerificationType],
+    locals: &[VerificationType],
+    stack: &[VerificationType],
+) -> Result<StackMapFrame, VerifyErrorKind> {
+    let mut type_infos = |values: &[VerificationType]| {
+        values
+            .iter()
+            .map(|value| type_info(builder, value))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let same_locals = locals == previous;
+    Ok(match stack {
+        [] if same_locals && offset_delta < 64 => StackMapFrame::SameFrame {
+            frame_type: offset_delta as u8,
+        },
+        [] if same_locals => StackMapFrame::SameFrameExtended {
+            frame_type: 251,
+            offset_delta,
+        },
+        [value] if same_locals && offset_delta < 64 => StackMapFrame::SameLocals1StackItemFrame {
+            frame_type: 64 + offset_delta as u8,
+            stack: type_infos(std::slice::from_ref(value))?.remove(0),
+        },
+        [value] if same_locals => StackMapFrame::SameLocals1StackItemFrameExtended {
+            frame_type: 247,
+            offset_delta,
+            stack: type_infos(std::slice::from_ref(value))?.remove(0),
+        },
+        [] if locals.len() < previous.len()
+            && previous.len() - locals.len() <= 3
+            && previous.starts_with(locals) =>
+        {
+            StackMapFrame::ChopFrame {
+                frame_type: (251 - (previous.len() - locals.len())) as u8,
+                offset_delta,
+            }
+        }
+        [] if locals.len() > previous.len()
+            && locals.len() - previous.len() <= 3
+            && locals.starts_with(previous) =>
+        {
+            let appended = type_infos(&locals[previous.len()..])?;
+            StackMapFrame::AppendFrame {
+                frame_type: (251 + appended.len()) as u8,
+                offset_delta,
+                locals: appended,
+            }
+        }
+        _ => {
+            let locals = type_infos(locals)?;
+            let stack = type_infos(stack)?;
+            StackMapFrame::FullFrame {
+                frame_type: 255,
+                offset_delta,
+                number_of_locals: locals.len() as u16,
+                locals,
+                number_of_stack_items: stack.len() as u16,
+                stack,
+            }
+        }
+    })
+}
+
+/// Compute the StackMapTable frames for new code of the method at `method_index`.
+///
+/// A frame is produced at every branch target, exception handler and instruction following
+/// an unconditional jump, using the most compact frame types possible. Where control-flow
+/// paths join, class types are merged with the help of `hierarchy`. Class constants needed by
+/// the frames are added to the constant pool if it does not have them yet.
+///
+/// An error is returned if the code does not verify, or has unreachable instructions, which
+/// cannot be given a frame.
+///
+/// # Panics
+///
+/// Panics if `method_index` is not the index of one of the class's methods.
+///
+/// ```rust
+/// use classfile_parser::attribute_info::StackMapFrame;
+/// use classfile_parser::attribute_info::TypedAttribute;
+/// use classfile_parser::verifier::compute_frames;
+///
+/// let mut class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+/// let code = match &class_file.methods[1].typed_attributes(&class_file.const_pool).unwrap()[0] {
+///     TypedAttribute::Code(code) => code.clone(),
+///     _ => unreachable!(),
+/// };
+/// let object = |_: &str, _: &str| "java/lang/Object".to_string();
+/// let frames = compute_frames(
+///     &mut class_file,
+///     1,
+///     code.max_locals,
+///     &code.instructions().unwrap(),
+///     &code.exception_table,
+///     &object,
+/// )
+/// .unwrap();
+/// assert_eq!(frames.len(), 2);
+/// assert!(matches!(frames[0], StackMapFrame::SameFrame { frame_type: 9 }));
+/// assert!(matches!(frames[1], StackMapFrame::SameLocals1StackItemFrame { frame_type: 71, .. }));
+/// ```
+pub fn compute_frames(
+    class_file: &mut ClassFile,
+    method_index: usize,
+    max_locals: u16,
+    instructions: &[(usize, Instruction)],
+    exception_table: &[ExceptionEntry],
+    hierarchy: &dyn TypeHierarchy,
+) -> Result<Vec<StackMapFrame>, VerifyError> {
+    let at_start = |kind: VerifyErrorKind| VerifyError { address: 0, kind };
+
+    let (initial, frames) = {
+        let method = &class_file.methods[method_index];
+        let pool = class_file.constant_pool();
+        let this_class = class_file
+            .this_class_name()
+            .map_err(|e| at_start(e.into()))?
+            .into_owned();
+        let name = pool
+            .get_utf8(method.name_index)
+            .map_err(|e| at_start(e.into()))?;
+        let descriptor = pool
+            .get_utf8(method.descriptor_index)
+            .map_err(|e| at_start(e.into()))?;
+        let descriptor = descriptor
+            .parse::<MethodDescriptor>()
+            .map_err(|_| at_start(VerifyErrorKind::InvalidDescriptor(descriptor.to_string())))?;
+        let is_static = method.access_flags.contains(MethodAccessFlags::STATIC);
+        let initial = entry_locals(&this_class, &name, is_static, &descriptor);
+
+        let locals = to_slots(&initial);
+        if locals.len() > max_locals as usize {
+            return Err(at_start(VerifyErrorKind::InvalidLocal {
+                index: locals.len() as u16 - 1,
+                max_locals,
+            }));
+        }
+        let analyzer = Analyzer {
+            interpreter: Interpreter {
+                pool,
+                this_class,
+                return_type: VerificationType::from_return_type(&descriptor.return_type),
+                max_locals,
+                news: news(instructions),
+            },
+            instructions,
+            exception_table,
+            max_stack: u16::MAX,
+            hierarchy,
+            declared: Default::default(),
+            frames_required: false,
+        };
+        let verification = analyzer.run(Frame {
+            locals: pad_locals(locals, max_locals),
+            stack: Vec::new(),
+        });
+        if let Some(error) = verification.errors.into_iter().next() {
+            return Err(error);
+        }
+        (initial, verification.frames)
+    };
+
+    let mut addresses = BTreeSet::new();
+    for (i, (address, instruction)) in instructions.iter().enumerate() {
+        let (targets, falls_through) = flow(instruction, *address);
+        addresses.extend(targets.into_iter().map(|(target, _)| target as usize));
+        if !falls_through && let Some((next, _)) = instructions.get(i + 1) {
+            addresses.insert(*next);
+        }
+    }
+    addresses.extend(
+        exception_table
+            .iter()
+            .map(|entry| entry.handler_pc as usize),
+    );
+
+    let mut builder = ConstantPoolBuilder::from_entries(&class_file.const_pool);
+    let mut encoded = Vec::with_capacity(addresses.len());
+    let mut previous_locals = initial;
+    let mut previous_address = None;
+    for address in addresses {
+        let Some(frame) = frames.get(&address) else {
+            return Err(VerifyError {
+                address,
+                kind: VerifyErrorKind::Unreachable,
+            });
+        };
+        let mut locals = to_entries(&frame.locals);
+        while locals.last() == Some(&VerificationType::Top) {
+            locals.pop();
+        }
+        let stack = to_entries(&frame.stack);
+        let offset_delta = match previous_address {
+            None => address,
+            Some(previous) => address - previous - 1,
+        };
+        let frame = encode_frame(
+            &mut builder,
+            offset_delta as u16,
+            &previous_locals,
+            &locals,
+            &stack,
+        )
+        .map_err(|kind| VerifyError { address, kind })?;
+        encoded.push(frame);
+        previous_locals = locals;
+        previous_address = Some(address);
+    }
+    builder.apply_to(class_file);
+    Ok(encoded)
+}
@@ -0,0 +1,650 @@
+use std::collections::HashMap;
+
+use crate::code_attribute::Instruction;
+use crate::constant_info::{ConstantInfo, ConstantPool, wrong_kind};
+use crate::descriptor::{FieldType, MethodDescriptor};
+
+use super::types::*;
+
+use VerificationType::*;
+
+fn mismatch(expected: VerificationType, found: VerificationType) -> VerifyErrorKind {
+    VerifyErrorKind::TypeMismatch { expected, found }
+}
+
+fn any_reference() -> VerificationType {
+    Object("java/lang/Object".to_string())
+}
+
+/// The type of an array created by `newarray`.
+fn primitive_array(atype: u8) -> VerificationType {
+    let descriptor = match atype {
+        4 => "[Z",
+        5 => "[C",
+        6 => "[F",
+        7 => "[D",
+        8 => "[B",
+        9 => "[S",
+        11 => "[J",
+        _ => "[I",
+    };
+    Object(descriptor.to_string())
+}
+
+/// The array type whose components are `class_name`, itself an internal name or array
+/// descriptor.
+pub(crate) fn array_of(class_name: &str) -> String {
+    if class_name.starts_with('[') {
+        format!("[{}", class_name)
+    } else {
+        format!("[L{};", class_name)
+    }
+}
+
+impl Frame {
+    pub(crate) fn push(&mut self, value: VerificationType) {
+        let category2 = value.is_category2();
+        self.stack.push(value);
+        if category2 {
+            self.stack.push(Top);
+        }
+    }
+
+    fn pop_slot(&mut self) -> Result<VerificationType, VerifyErrorKind> {
+        self.stack.pop().ok_or(VerifyErrorKind::StackUnderflow)
+    }
+
+    /// Pop a value that must be assignable to `expected`, both slots for a long or double.
+    fn pop(&mut self, expected: &VerificationType) -> Result<VerificationType, VerifyErrorKind> {
+        if expected.is_category2() {
+            let top = self.pop_slot()?;
+            let value = self.pop_slot()?;
+            if top != Top || &value != expected {
+                return Err(mismatch(expected.clone(), value));
+            }
+            return Ok(value);
+        }
+        let value = self.pop_slot()?;
+        if !value.is_assignable_to(expected) || value == Top {
+            return Err(mismatch(expected.clone(), value));
+        }
+        Ok(value)
+    }
+
+    /// Pop any reference, including uninitialized ones.
+    fn pop_reference(&mut self) -> Result<VerificationType, VerifyErrorKind> {
+        let value = self.pop_slot()?;
+        if !value.is_reference() {
+            return Err(mismatch(any_reference(), value));
+        }
+        Ok(value)
+    }
+
+    /// Check that the top `depth` slots do not split a long or double, so they can be moved
+    /// as a unit by the `dup` and `pop` family.
+    fn check_boundary(&self, depth: usize) -> Result<(), VerifyErrorKind> {
+        let len = self.stack.len();
+        if depth > len {
+            return Err(VerifyErrorKind::StackUnderflow);
+        }
+        match depth.checked_sub(1).map(|i| &self.stack[len - 1 - i]) {
+            Some(Top) => Err(mismatch(any_reference(), Top)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Insert a copy of the top `count` slots `depth` slots down.
+    fn dup(&mut self, count: usize, depth: usize) -> Result<(), VerifyErrorKind> {
+        self.check_boundary(count)?;
+        self.check_boundary(count + depth)?;
+        let len = self.stack.len();
+        let copied: Vec<_> = self.stack[len - count..].to_vec();
+        let at = len - count - depth;
+        self.stack.splice(at..at, copied);
+        Ok(())
+    }
+
+    /// Replace every occurrence of an uninitialized type once its constructor has run.
+    fn initialize(&mut self, uninitialized: &VerificationType, initialized: VerificationType) {
+        for slot in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+            if slot == uninitialized {
+                *slot = initialized.clone();
+            }
+        }
+    }
+}
+
+/// Computes the effect of single instructions on a frame, resolving constant pool references
+/// and checking operand types as it goes.
+pub(crate) struct Interpreter<'a> {
+    pub(crate) pool: ConstantPool<'a>,
+    pub(crate) this_class: String,
+    pub(crate) return_type: Option<VerificationType>,
+    pub(crate) max_locals: u16,
+    /// The class index of each `new` instruction, by address.
+    pub(crate) news: HashMap<u16, u16>,
+}
+
+impl Interpreter<'_> {
+    fn load(
+        &self,
+        frame: &mut Frame,
+        index: u16,
+        expected: VerificationType,
+    ) -> Result<(), VerifyErrorKind> {
+        self.check_local(index, expected.is_category2())?;
+        let value = frame.locals[index as usize].clone();
+        let matches = if expected.is_category2() {
+            value == expected && frame.locals[index as usize + 1] == Top
+        } else if expected.is_reference() {
+            value.is_reference()
+        } else {
+            value == expected
+        };
+        if !matches {
+            return Err(mismatch(expected, value));
+        }
+        frame.push(value);
+        Ok(())
+    }
+
+    fn store(
+        &self,
+        frame: &mut Frame,
+        index: u16,
+        expected: VerificationType,
+    ) -> Result<(), VerifyErrorKind> {
+        let value = if expected.is_reference() {
+            frame.pop_reference()?
+        } else {
+            frame.pop(&expected)?
+        };
+        self.check_local(index, value.is_category2())?;
+        let index = index as usize;
+        // Overwriting either half of a long or double invalidates the other half
+        if index > 0 && frame.locals[index - 1].is_category2() {
+            frame.locals[index - 1] = Top;
+        }
+        if value.is_category2() {
+            frame.locals[index + 1] = Top;
+        }
+        frame.locals[index] = value;
+        Ok(())
+    }
+
+    fn check_local(&self, index: u16, category2: bool) -> Result<(), VerifyErrorKind> {
+        let last = index as u32 + category2 as u32;
+        if last >= self.max_locals as u32 {
+            return Err(VerifyErrorKind::InvalidLocal {
+                index,
+                max_locals: self.max_locals,
+            });
+        }
+        Ok(())
+    }
+
+    fn class_type(&self, index: u16) -> Result<VerificationType, VerifyErrorKind> {
+        Ok(Object(self.pool.get_class_name(index)?.into_owned()))
+    }
+
+    fn field_type(&self, descriptor: &str) -> Result<VerificationType, VerifyErrorKind> {
+        let field_type: FieldType = descriptor
+            .parse()
+            .map_err(|_| VerifyErrorKind::InvalidDescriptor(descriptor.to_string()))?;
+        Ok(VerificationType::from_field_type(&field_type))
+    }
+
+    fn method_descriptor(&self, descriptor: &str) -> Result<MethodDescriptor, VerifyErrorKind> {
+        descriptor
+            .parse()
+            .map_err(|_| VerifyErrorKind::InvalidDescriptor(descriptor.to_string()))
+    }
+
+    fn pop_arguments(
+        &self,
+        frame: &mut Frame,
+        descriptor: &MethodDescriptor,
+    ) -> Result<(), VerifyErrorKind> {
+        for param in descriptor.params.iter().rev() {
+            frame.pop(&VerificationType::from_field_type(param))?;
+        }
+        Ok(())
+    }
+
+    fn push_return(&self, frame: &mut Frame, descriptor: &MethodDescriptor) {
+        if let Some(value) = VerificationType::from_return_type(&descriptor.return_type) {
+            frame.push(value);
+        }
+    }
+
+    fn ldc(&self, frame: &mut Frame, index: u16, wide: bool) -> Result<(), VerifyErrorKind> {
+        let value = match self.pool.get(index)? {
+            ConstantInfo::Integer(_) if !wide => Integer,
+            ConstantInfo::Float(_) if !wide => Float,
+            ConstantInfo::Long(_) if wide => Long,
+            ConstantInfo::Double(_) if wide => Double,
+            ConstantInfo::String(_) if !wide => Object("java/lang/String".to_string()),
+            ConstantInfo::Class(_) if !wide => Object("java/lang/Class".to_string()),
+            ConstantInfo::MethodType(_) if !wide => {
+                Object("java/lang/invoke/MethodType".to_string())
+            }
+            ConstantInfo::MethodHandle(_) if !wide => {
+                Object("java/lang/invoke/MethodHandle".to_string())
+            }
+            ConstantInfo::Dynamic(dynamic) => {
+                let (_, descriptor) = self.pool.get_name_and_type(dynamic.name_and_type_index)?;
+                let value = self.field_type(&descriptor)?;
+                if value.is_category2() != wide {
+                    return Err(mismatch(if wide { Long } else { Integer }, value));
+                }
+                value
+            }
+            other => {
+                let expected = if wide {
+                    "Long or Double"
+                } else {
+                    "Integer, Float, String, Class, MethodType or MethodHandle"
+                };
+                return Err(wrong_kind(index, expected, other).into());
+            }
+        };
+        frame.push(value);
+        Ok(())
+    }
+
+    fn array_load(
+        &self,
+        frame: &mut Frame,
+        element: VerificationType,
+    ) -> Result<(), VerifyErrorKind> {
+        frame.pop(&Integer)?;
+        frame.pop(&any_reference())?;
+        frame.push(element);
+        Ok(())
+    }
+
+    fn array_store(
+        &self,
+        frame: &mut Frame,
+        element: VerificationType,
+    ) -> Result<(), VerifyErrorKind> {
+        frame.pop(&element)?;
+        frame.pop(&Integer)?;
+        frame.pop(&any_reference())?;
+        Ok(())
+    }
+
+    fn binary(&self, frame: &mut Frame, operand: VerificationType) -> Result<(), VerifyErrorKind> {
+        frame.pop(&operand)?;
+        frame.pop(&operand)?;
+        frame.push(operand);
+        Ok(())
+    }
+
+    fn convert(
+        &self,
+        frame: &mut Frame,
+        from: VerificationType,
+        to: VerificationType,
+    ) -> Result<(), VerifyErrorKind> {
+        frame.pop(&from)?;
+        frame.push(to);
+        Ok(())
+    }
+
+    fn compare(&self, frame: &mut Frame, operand: VerificationType) -> Result<(), VerifyErrorKind> {
+        frame.pop(&operand)?;
+        frame.pop(&operand)?;
+        frame.push(Integer);
+        Ok(())
+    }
+
+    fn ret(
+        &self,
+        frame: &mut Frame,
+        value: Option<VerificationType>,
+    ) -> Result<(), VerifyErrorKind> {
+        let expected = match (&self.return_type, &value) {
+            (None, None) => return Ok(()),
+            (Some(expected), Some(value))
+                if expected == value || expected.is_reference() && value.is_reference() =>
+            {
+                expected.clone()
+            }
+            _ => return Err(VerifyErrorKind::BadReturn),
+        };
+        frame.pop(&expected)?;
+        Ok(())
+    }
+
+    fn invoke(
+        &self,
+        frame: &mut Frame,
+        index: u16,
+        receiver: bool,
+        special: bool,
+    ) -> Result<(), VerifyErrorKind> {
+        let (class_name, name, descriptor) = self.pool.resolve_member_ref(index)?;
+        let descriptor = self.method_descriptor(&descriptor)?;
+        self.pop_arguments(frame, &descriptor)?;
+        if special && name == "<init>" {
+            let target = frame.pop_reference()?;
+            let initialized = match &target {
+                UninitializedThis => Object(self.this_class.clone()),
+                Uninitialized(offset) => match self.news.get(offset) {
+                    Some(&class_index) => self.class_type(class_index)?,
+                    None => Object(class_name.into_owned()),
+                },
+                found => return Err(mismatch(Uninitialized(0), found.clone())),
+            };
+            frame.initialize(&target, initialized);
+        } else if receiver {
+            let target = frame.pop_reference()?;
+            if matches!(target, UninitializedThis | Uninitialized(_)) {
+                return Err(mismatch(Object(class_name.into_owned()), target));
+            }
+        }
+        self.push_return(frame, &descriptor);
+        Ok(())
+    }
+
+    /// Apply `instruction`, at `address`, to `frame`.
+    pub(crate) fn execute(
+        &self,
+        frame: &mut Frame,
+        address: usize,
+        instruction: &Instruction,
+    ) -> Result<(), VerifyErrorKind> {
+        use Instruction as I;
+        match instruction {
+            I::Nop => {}
+            I::Aconstnull => frame.push(Null),
+            I::Iconstm1
+            | I::Iconst0
+            | I::Iconst1
+            | I::Iconst2
+            | I::Iconst3
+            | I::Iconst4
+            | I::Iconst5
+            | I::Bipush(_)
+            | I::Sipush(_) => frame.push(Integer),
+            I::Lconst0 | I::Lconst1 => frame.push(Long),
+            I::Fconst0 | I::Fconst1 | I::Fconst2 => frame.push(Float),
+            I::Dconst0 | I::Dconst1 => frame.push(Double),
+            I::Ldc(index) => self.ldc(frame, *index as u16, false)?,
+            I::LdcW(index) => self.ldc(frame, *index, false)?,
+            I::Ldc2W(index) => self.ldc(frame, *index, true)?,
+
+            I::Iload(index) => self.load(frame, *index as u16, Integer)?,
+            I::IloadWide(index) => self.load(frame, *index, Integer)?,
+            I::Iload0 => self.load(frame, 0, Integer)?,
+            I::Iload1 => self.load(frame, 1, Integer)?,
+            I::Iload2 => self.load(frame, 2, Integer)?,
+            I::Iload3 => self.load(frame, 3, Integer)?,
+            I::Lload(index) => self.load(frame, *index as u16, Long)?,
+            I::LloadWide(index) => self.load(frame, *index, Long)?,
+            I::Lload0 => self.load(frame, 0, Long)?,
+            I::Lload1 => self.load(frame, 1, Long)?,
+            I::Lload2 => self.load(frame, 2, Long)?,
+            I::Lload3 => self.load(frame, 3, Long)?,
+            I::Fload(index) => self.load(frame, *index as u16, Float)?,
+            I::FloadWide(index) => self.load(frame, *index, Float)?,
+            I::Fload0 => self.load(frame, 0, Float)?,
+            I::Fload1 => self.load(frame, 1, Float)?,
+            I::Fload2 => self.load(frame, 2, Float)?,
+            I::Fload3 => self.load(frame, 3, Float)?,
+            I::Dload(index) => self.load(frame, *index as u16, Double)?,
+            I::DloadWide(index) => self.load(frame, *index, Double)?,
+            I::Dload0 => self.load(frame, 0, Double)?,
+            I::Dload1 => self.load(frame, 1, Double)?,
+            I::Dload2 => self.load(frame, 2, Double)?,
+            I::Dload3 => self.load(frame, 3, Double)?,
+            I::Aload(index) => self.load(frame, *index as u16, any_reference())?,
+            I::AloadWide(index) => self.load(frame, *index, any_reference())?,
+            I::Aload0 => self.load(frame, 0, any_reference())?,
+            I::Aload1 => self.load(frame, 1, any_reference())?,
+            I::Aload2 => self.load(frame, 2, any_reference())?,
+            I::Aload3 => self.load(frame, 3, any_reference())?,
+
+            I::Istore(index) => self.store(frame, *index as u16, Integer)?,
+            I::IstoreWide(index) => self.store(frame, *index, Integer)?,
+            I::Istore0 => self.store(frame, 0, Integer)?,
+            I::Istore1 => self.store(frame, 1, Integer)?,
+            I::Istore2 => self.store(frame, 2, Integer)?,
+            I::Istore3 => self.store(frame, 3, Integer)?,
+            I::Lstore(index) => self.store(frame, *index as u16, Long)?,
+            I::LstoreWide(index) => self.store(frame, *index, Long)?,
+            I::Lstore0 => self.store(frame, 0, Long)?,
+            I::Lstore1 => self.store(frame, 1, Long)?,
+            I::Lstore2 => self.store(frame, 2, Long)?,
+            I::Lstore3 => self.store(frame, 3, Long)?,
+            I::Fstore(index) => self.store(frame, *index as u16, Float)?,
+            I::FstoreWide(index) => self.store(frame, *index, Float)?,
+            I::Fstore0 => self.store(frame, 0, Float)?,
+            I::Fstore1 => self.store(frame, 1, Float)?,
+            I::Fstore2 => self.store(frame, 2, Float)?,
+            I::Fstore3 => self.store(frame, 3, Float)?,
+            I::Dstore(index) => self.store(frame, *index as u16, Double)?,
+            I::DstoreWide(index) => self.store(frame, *index, Double)?,
+            I::Dstore0 => self.store(frame, 0, Double)?,
+            I::Dstore1 => self.store(frame, 1, Double)?,
+            I::Dstore2 => self.store(frame, 2, Double)?,
+            I::Dstore3 => self.store(frame, 3, Double)?,
+            I::Astore(index) => self.store(frame, *index as u16, any_reference())?,
+            I::AstoreWide(index) => self.store(frame, *index, any_reference())?,
+            I::Astore0 => self.store(frame, 0, any_reference())?,
+            I::Astore1 => self.store(frame, 1, any_reference())?,
+            I::Astore2 => self.store(frame, 2, any_reference())?,
+            I::Astore3 => self.store(frame, 3, any_reference())?,
+            I::Iinc { index, .. } => {
+                self.load(frame, *index as u16, Integer)?;
+                frame.pop_slot()?;
+            }
+            I::IincWide { index, .. } => {
+                self.load(frame, *index, Integer)?;
+                frame.pop_slot()?;
+            }
+
+            I::Iaload | I::Baload | I::Caload | I::Saload => self.array_load(frame, Integer)?,
+            I::Laload => self.array_load(frame, Long)?,
+            I::Faload => self.array_load(frame, Float)?,
+            I::Daload => self.array_load(frame, Double)?,
+            I::Aaload => {
+                frame.pop(&Integer)?;
+                let element = match frame.pop_reference()? {
+                    Null => Null,
+                    Object(array) => match array.strip_prefix('[').and_then(component_class) {
+                        Some(component) => Object(component.to_string()),
+                        None => {
+                            return Err(mismatch(
+                                Object("[Ljava/lang/Object;".into()),
+                                Object(array),
+                            ));
+                        }
+                    },
+                    found => return Err(mismatch(Object("[Ljava/lang/Object;".into()), found)),
+                };
+                frame.push(element);
+            }
+            I::Iastore | I::Bastore | I::Castore | I::Sastore => {
+                self.array_store(frame, Integer)?
+            }
+            I::Lastore => self.array_store(frame, Long)?,
+            I::Fastore => self.array_store(frame, Float)?,
+            I::Dastore => self.array_store(frame, Double)?,
+            I::Aastore => self.array_store(frame, any_reference())?,
+
+            I::Pop => {
+                frame.check_boundary(1)?;
+                frame.pop_slot()?;
+            }
+            I::Pop2 => {
+                frame.check_boundary(2)?;
+                frame.pop_slot()?;
+                frame.pop_slot()?;
+            }
+            I::Dup => frame.dup(1, 0)?,
+            I::Dupx1 => frame.dup(1, 1)?,
+            I::Dupx2 => frame.dup(1, 2)?,
+            I::Dup2 => frame.dup(2, 0)?,
+            I::Dup2x1 => frame.dup(2, 1)?,
+            I::Dup2x2 => frame.dup(2, 2)?,
+            I::Swap => {
+                frame.check_boundary(1)?;
+                frame.check_boundary(2)?;
+                let len = frame.stack.len();
+                frame.stack.swap(len - 1, len - 2);
+            }
+
+            I::Iadd | I::Isub | I::Imul | I::Idiv | I::Irem | I::Iand | I::Ior | I::Ixor => {
+                self.binary(frame, Integer)?
+            }
+            I::Ishl | I::Ishr | I::Iushr => self.binary(frame, Integer)?,
+            I::Ladd | I::Lsub | I::Lmul | I::Ldiv | I::Lrem | I::Land | I::Lor | I::Lxor => {
+                self.binary(frame, Long)?
+            }
+            I::Lshl | I::Lshr | I::Lushr => {
+                frame.pop(&Integer)?;
+                frame.pop(&Long)?;
+                frame.push(Long);
+            }
+            I::Fadd | I::Fsub | I::Fmul | I::Fdiv | I::Frem => self.binary(frame, Float)?,
+            I::Dadd | I::Dsub | I::Dmul | I::Ddiv | I::Drem => self.binary(frame, Double)?,
+            I::Ineg => self.convert(frame, Integer, Integer)?,
+            I::Lneg => self.convert(frame, Long, Long)?,
+            I::Fneg => self.convert(frame, Float, Float)?,
+            I::Dneg => self.convert(frame, Double, Double)?,
+
+            I::I2l => self.convert(frame, Integer, Long)?,
+            I::I2f => self.convert(frame, Integer, Float)?,
+            I::I2d => self.convert(frame, Integer, Double)?,
+            I::L2i => self.convert(frame, Long, Integer)?,
+            I::L2f => self.convert(frame, Long, Float)?,
+            I::L2d => self.convert(frame, Long, Double)?,
+            I::F2i => self.convert(frame, Float, Integer)?,
+            I::F2l => self.convert(frame, Float, Long)?,
+            I::F2d => self.convert(frame, Float, Double)?,
+            I::D2i => self.convert(frame, Double, Integer)?,
+            I::D2l => self.convert(frame, Double, Long)?,
+            I::D2f => self.convert(frame, Double, Float)?,
+            I::I2b | I::I2c | I::I2s => self.convert(frame, Integer, Integer)?,
+
+            I::Lcmp => self.compare(frame, Long)?,
+            I::Fcmpl | I::Fcmpg => self.compare(frame, Float)?,
+            I::Dcmpl | I::Dcmpg => self.compare(frame, Double)?,
+
+            I::Ifeq(_) | I::Ifne(_) | I::Iflt(_) | I::Ifge(_) | I::Ifgt(_) | I::Ifle(_) => {
+                frame.pop(&Integer)?;
+            }
+            I::IfIcmpeq(_)
+            | I::IfIcmpne(_)
+            | I::IfIcmplt(_)
+            | I::IfIcmpge(_)
+            | I::IfIcmpgt(_)
+            | I::IfIcmple(_) => {
+                frame.pop(&Integer)?;
+                frame.pop(&Integer)?;
+            }
+            I::IfAcmpeq(_) | I::IfAcmpne(_) => {
+                frame.pop_reference()?;
+                frame.pop_reference()?;
+            }
+            I::Ifnull(_) | I::Ifnonnull(_) => {
+                frame.pop_reference()?;
+            }
+            I::Goto(_) | I::GotoW(_) => {}
+            I::Jsr(_) | I::JsrW(_) | I::Ret(_) | I::RetWide(_) => {
+                return Err(VerifyErrorKind::Subroutine);
+            }
+            I::Tableswitch { .. } | I::Lookupswitch { .. } => {
+                frame.pop(&Integer)?;
+            }
+
+            I::Ireturn => self.ret(frame, Some(Integer))?,
+            I::Lreturn => self.ret(frame, Some(Long))?,
+            I::Freturn => self.ret(frame, Some(Float))?,
+            I::Dreturn => self.ret(frame, Some(Double))?,
+            I::Areturn => self.ret(frame, Some(any_reference()))?,
+            I::Return => self.ret(frame, None)?,
+
+            I::Getstatic(index) => {
+                let (_, _, descriptor) = self.pool.resolve_member_ref(*index)?;
+                frame.push(self.field_type(&descriptor)?);
+            }
+            I::Putstatic(index) => {
+                let (_, _, descriptor) = self.pool.resolve_member_ref(*index)?;
+                frame.pop(&self.field_type(&descriptor)?)?;
+            }
+            I::Getfield(index) => {
+                let (class_name, _, descriptor) = self.pool.resolve_member_ref(*index)?;
+                let target = frame.pop_reference()?;
+                if matches!(target, Uninitialized(_)) {
+                    return Err(mismatch(Object(class_name.into_owned()), target));
+                }
+                frame.push(self.field_type(&descriptor)?);
+            }
+            I::Putfield(index) => {
+                let (class_name, _, descriptor) = self.pool.resolve_member_ref(*index)?;
+                frame.pop(&self.field_type(&descriptor)?)?;
+                // Constructors may assign fields of `this` before calling super()
+                let target = frame.pop_reference()?;
+                if matches!(target, Uninitialized(_)) {
+                    return Err(mismatch(Object(class_name.into_owned()), target));
+                }
+            }
+            I::Invokevirtual(index) => self.invoke(frame, *index, true, false)?,
+            I::Invokespecial(index) => self.invoke(frame, *index, true, true)?,
+            I::Invokestatic(index) => self.invoke(frame, *index, false, false)?,
+            I::Invokeinterface { index, .. } => self.invoke(frame, *index, true, false)?,
+            I::Invokedynamic(index) => {
+                let name_and_type_index = match self.pool.get(*index)? {
+                    ConstantInfo::InvokeDynamic(indy) => indy.name_and_type_index,
+                    other => return Err(wrong_kind(*index, "InvokeDynamic", other).into()),
+                };
+                let (_, descriptor) = self.pool.get_name_and_type(name_and_type_index)?;
+                let descriptor = self.method_descriptor(&descriptor)?;
+                self.pop_arguments(frame, &descriptor)?;
+                self.push_return(frame, &descriptor);
+            }
+
+            I::New(index) => {
+                self.pool.get_class_name(*index)?;
+                frame.push(Uninitialized(address as u16));
+            }
+            I::Newarray(atype) => {
+                frame.pop(&Integer)?;
+                frame.push(primitive_array(*atype));
+            }
+            I::Anewarray(index) => {
+                frame.pop(&Integer)?;
+                let component = self.pool.get_class_name(*index)?;
+                frame.push(Object(array_of(&component)));
+            }
+            I::Multianewarray { index, dimensions } => {
+                for _ in 0..*dimensions {
+                    frame.pop(&Integer)?;
+                }
+                frame.push(self.class_type(*index)?);
+            }
+            I::Arraylength => {
+                frame.pop(&any_reference())?;
+                frame.push(Integer);
+            }
+            I::Athrow => {
+                frame.pop(&Object("java/lang/Throwable".to_string()))?;
+            }
+            I::Checkcast(index) => {
+                frame.pop(&any_reference())?;
+                frame.push(self.class_type(*index)?);
+            }
+            I::Instanceof(_) => {
+                frame.pop(&any_reference())?;
+                frame.push(Integer);
+            }
+            I::Monitorenter | I::Monitorexit => {
+                frame.pop(&any_reference())?;
+            }
+        }
+        Ok(())
+    }
+}
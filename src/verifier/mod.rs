@@ -0,0 +1,231 @@
+//! A lightweight bytecode verifier.
+//!
+//! `verify_method` runs a data-flow analysis over a method's instructions, computing the types
+//! of the local variables and operand stack before each one. It reports operand stack
+//! underflows, stacks deeper than `max_stack`, locals past `max_locals`, operands of the wrong
+//! type and disagreements with the method's StackMapTable frames. Unlike the JVM's verifier it
+//! has no class hierarchy to consult, so any class type is accepted where another is expected.
+//!
+//! The same analysis backs `compute_frames`, which builds a new StackMapTable for rewritten
+//! code.
+
+mod analyzer;
+mod frames;
+mod interpreter;
+mod types;
+
+use std::collections::HashMap;
+
+use crate::ClassParseError;
+use crate::attribute_info::TypedAttribute;
+use crate::code_attribute::Instruction;
+use crate::constant_info::ConstantPoolError;
+use crate::descriptor::MethodDescriptor;
+use crate::method_info::{MethodAccessFlags, MethodInfo};
+use crate::types::ClassFile;
+
+use self::analyzer::{Analyzer, to_slots};
+use self::interpreter::Interpreter;
+
+pub(crate) use self::interpreter::array_of;
+
+pub use self::frames::compute_frames;
+pub use self::types::*;
+
+/// The first class file version whose methods need a StackMapTable frame at every branch
+/// target.
+const STACK_MAP_MAJOR_VERSION: u16 = 50;
+
+/// Pad slot-form locals with `Top` up to `max_locals`.
+fn pad_locals(mut locals: Vec<VerificationType>, max_locals: u16) -> Vec<VerificationType> {
+    if locals.len() < max_locals as usize {
+        locals.resize(max_locals as usize, VerificationType::Top);
+    }
+    locals
+}
+
+/// The locals on entry to a method, one entry per value: `this` unless the method is
+/// static, followed by the parameters.
+fn entry_locals(
+    this_class: &str,
+    name: &str,
+    is_static: bool,
+    descriptor: &MethodDescriptor,
+) -> Vec<VerificationType> {
+    let mut locals = Vec::with_capacity(descriptor.params.len() + 1);
+    if !is_static {
+        if name == "<init>" && this_class != "java/lang/Object" {
+            locals.push(VerificationType::UninitializedThis);
+        } else {
+            locals.push(VerificationType::Object(this_class.to_string()));
+        }
+    }
+    locals.extend(
+        descriptor
+            .params
+            .iter()
+            .map(VerificationType::from_field_type),
+    );
+    locals
+}
+
+/// The locals of the implicit frame at the start of `method`, one of the methods of
+/// `class_file`: `this` unless the method is static, followed by the parameters, one entry
+/// per value. In a constructor `this` is uninitialized until the superclass constructor has
+/// been called.
+///
+/// ```rust
+/// use classfile_parser::verifier::{VerificationType, initial_locals};
+///
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+/// let locals = initial_locals(&class_file, &class_file.methods[1]).unwrap();
+/// assert_eq!(locals, [VerificationType::Integer]);
+/// ```
+pub fn initial_locals(
+    class_file: &ClassFile,
+    method: &MethodInfo,
+) -> Result<Vec<VerificationType>, ClassParseError> {
+    let pool = class_file.constant_pool();
+    let descriptor = pool
+        .get_utf8(method.descriptor_index)?
+        .parse::<MethodDescriptor>()?;
+    Ok(entry_locals(
+        &class_file.this_class_name()?,
+        &pool.get_utf8(method.name_index)?,
+        method.access_flags.contains(MethodAccessFlags::STATIC),
+        &descriptor,
+    ))
+}
+
+/// The class index of each `new` instruction, by address.
+fn news(instructions: &[(usize, Instruction)]) -> HashMap<u16, u16> {
+    instructions
+        .iter()
+        .filter_map(|(address, instruction)| match instruction {
+            Instruction::New(index) => Some((*address as u16, *index)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Verify the bytecode of `method`, one of the methods of `class_file`.
+///
+/// Methods without a Code attribute verify trivially. Problems with the bytecode are
+/// reported in the result; an error is only returned if the method's attributes or
+/// instructions cannot be decoded.
+///
+/// ```rust
+/// use classfile_parser::verifier::verify_method;
+///
+/// let class_file = classfile_parser::parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+/// let verification = verify_method(&class_file, &class_file.methods[1]).unwrap();
+/// assert!(verification.is_ok());
+/// assert_eq!(verification.max_stack, 3);
+/// assert_eq!(verification.frames[&0].locals, [classfile_parser::verifier::VerificationType::Integer]);
+/// ```
+pub fn verify_method(
+    class_file: &ClassFile,
+    method: &MethodInfo,
+) -> Result<MethodVerification, ClassParseError> {
+    let Some(code) = method
+        .typed_attributes(&class_file.const_pool)?
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::Code(code) => Some(code),
+            _ => None,
+        })
+    else {
+        return Ok(MethodVerification::default());
+    };
+    let instructions = code.instructions()?;
+
+    let pool = class_file.constant_pool();
+    let this_class = class_file.this_class_name()?.into_owned();
+    let name = pool.get_utf8(method.name_index)?;
+    let descriptor = pool.get_utf8(method.descriptor_index)?;
+    let Ok(descriptor) = descriptor.parse::<MethodDescriptor>() else {
+        return Ok(MethodVerification {
+            errors: vec![VerifyError {
+                address: 0,
+                kind: VerifyErrorKind::InvalidDescriptor(descriptor.into_owned()),
+            }],
+            ..MethodVerification::default()
+        });
+    };
+    let is_static = method.access_flags.contains(MethodAccessFlags::STATIC);
+    let locals = entry_locals(&this_class, &name, is_static, &descriptor);
+
+    let mut errors = Vec::new();
+    let mut declared = Default::default();
+    let stack_map = code
+        .typed_attributes(&class_file.const_pool)?
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::StackMapTable(table) => Some(table),
+            _ => None,
+        });
+    if let Some(table) = stack_map {
+        let frames = table
+            .frames(locals.clone(), pool)
+            .map(|frame| {
+                let (address, locals, stack) = frame?;
+                let frame = Frame {
+                    locals: pad_locals(to_slots(&locals), code.max_locals),
+                    stack: to_slots(&stack),
+                };
+                Ok((address, frame))
+            })
+            .collect::<Result<_, ConstantPoolError>>();
+        match frames {
+            Ok(frames) => declared = frames,
+            Err(e) => errors.push(VerifyError {
+                address: 0,
+                kind: e.into(),
+            }),
+        }
+    }
+
+    let initial = Frame {
+        locals: to_slots(&locals),
+        stack: Vec::new(),
+    };
+    if initial.locals.len() > code.max_locals as usize {
+        errors.push(VerifyError {
+            address: 0,
+            kind: VerifyErrorKind::InvalidLocal {
+                index: initial.locals.len() as u16 - 1,
+                max_locals: code.max_locals,
+            },
+        });
+        return Ok(MethodVerification {
+            errors,
+            ..MethodVerification::default()
+        });
+    }
+    let initial = Frame {
+        locals: pad_locals(initial.locals, code.max_locals),
+        stack: Vec::new(),
+    };
+
+    let analyzer = Analyzer {
+        interpreter: Interpreter {
+            pool,
+            this_class,
+            return_type: VerificationType::from_return_type(&descriptor.return_type),
+            max_locals: code.max_locals,
+            news: news(&instructions),
+        },
+        instructions: &instructions,
+        exception_table: &code.exception_table,
+        max_stack: code.max_stack,
+        hierarchy: &|_: &str, _: &str| "java/lang/Object".to_string(),
+        declared,
+        frames_required: class_file.major_version >= STACK_MAP_MAJOR_VERSION,
+    };
+    let mut verification = analyzer.run(initial);
+    if !errors.is_empty() {
+        errors.append(&mut verification.errors);
+        verification.errors = errors;
+    }
+    Ok(verification)
+}
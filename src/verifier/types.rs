@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::constant_info::ConstantPoolError;
+use crate::descriptor::{BaseType, FieldType, ReturnType};
+
+/// The type of a local variable or operand stack slot, as tracked by the verifier.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VerificationType {
+    /// An unusable slot, including the second slot of a long or double.
+    Top,
+    /// An `int`, `short`, `char`, `byte` or `boolean`.
+    Integer,
+    Float,
+    Long,
+    Double,
+    Null,
+    /// `this` in a constructor before the superclass constructor has been called.
+    UninitializedThis,
+    /// A class or array type, by internal name (`java/lang/String`) or array descriptor (`[I`).
+    Object(String),
+    /// An object created by the `new` at this address whose constructor has not run yet.
+    Uninitialized(u16),
+}
+
+impl VerificationType {
+    /// The type of a value of the given field type, once loaded onto the operand stack.
+    pub fn from_field_type(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Base(BaseType::Float) => VerificationType::Float,
+            FieldType::Base(BaseType::Long) => VerificationType::Long,
+            FieldType::Base(BaseType::Double) => VerificationType::Double,
+            FieldType::Base(_) => VerificationType::Integer,
+            FieldType::Object(class_name) => VerificationType::Object(class_name.clone()),
+            array => VerificationType::Object(array.to_string()),
+        }
+    }
+
+    /// The type of a value returned by a method, `None` for `void`.
+    pub fn from_return_type(return_type: &ReturnType) -> Option<Self> {
+        match return_type {
+            ReturnType::Void => None,
+            ReturnType::Type(field_type) => Some(Self::from_field_type(field_type)),
+        }
+    }
+
+    /// Whether the type takes up two slots.
+    pub fn is_category2(&self) -> bool {
+        matches!(self, VerificationType::Long | VerificationType::Double)
+    }
+
+    /// Whether the type is a reference, initialized or not.
+    pub fn is_reference(&self) -> bool {
+        matches!(
+            self,
+            VerificationType::Null
+                | VerificationType::UninitializedThis
+                | VerificationType::Object(_)
+                | VerificationType::Uninitialized(_)
+        )
+    }
+
+    /// Whether a value of this type can be used where `other` is expected.
+    ///
+    /// Without a class hierarchy to consult, a class type is taken to be assignable to any
+    /// other class type; only arrays and primitive types are checked precisely.
+    pub fn is_assignable_to(&self, other: &VerificationType) -> bool {
+        use VerificationType::*;
+        match (self, other) {
+            (_, Top) => true,
+            (Null, Object(_)) => true,
+            (Object(from), Object(to)) => object_assignable(from, to),
+            (from, to) => from == to,
+        }
+    }
+}
+
+fn object_assignable(from: &str, to: &str) -> bool {
+    if from == to || to == "java/lang/Object" {
+        return true;
+    }
+    match (from.strip_prefix('['), to.strip_prefix('[')) {
+        (Some(from), Some(to)) => match (component_class(from), component_class(to)) {
+            (Some(from), Some(to)) => object_assignable(from, to),
+            _ => false,
+        },
+        (Some(_), None) => to == "java/lang/Cloneable" || to == "java/io/Serializable",
+        (None, Some(_)) => false,
+        (None, None) => true,
+    }
+}
+
+/// The class or array type an array component descriptor names, `None` for primitives.
+pub(crate) fn component_class(component: &str) -> Option<&str> {
+    if component.starts_with('[') {
+        Some(component)
+    } else {
+        component.strip_prefix('L')?.strip_suffix(';')
+    }
+}
+
+/// The class hierarchy queries needed to merge the types of two control-flow paths.
+/// `ClassHierarchy` implements this for a set of parsed classes.
+pub trait TypeHierarchy {
+    /// The internal name of the most specific class that both classes, given by internal
+    /// name, extend.
+    ///
+    /// As in the JVM's verifier interfaces are not considered, so this is
+    /// `java/lang/Object` if either class is an interface.
+    fn common_superclass(&self, a: &str, b: &str) -> String;
+}
+
+impl<F: Fn(&str, &str) -> String> TypeHierarchy for F {
+    fn common_superclass(&self, a: &str, b: &str) -> String {
+        self(a, b)
+    }
+}
+
+impl fmt::Display for VerificationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationType::Top => write!(f, "top"),
+            VerificationType::Integer => write!(f, "int"),
+            VerificationType::Float => write!(f, "float"),
+            VerificationType::Long => write!(f, "long"),
+            VerificationType::Double => write!(f, "double"),
+            VerificationType::Null => write!(f, "null"),
+            VerificationType::UninitializedThis => write!(f, "uninitializedThis"),
+            VerificationType::Object(class_name) => write!(f, "{}", class_name),
+            VerificationType::Uninitialized(offset) => write!(f, "uninitialized({})", offset),
+        }
+    }
+}
+
+/// The types of the local variables and operand stack before an instruction.
+///
+/// Both are laid out in slots: a long or double takes two, the second of which is `Top`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Frame {
+    pub locals: Vec<VerificationType>,
+    /// The operand stack from bottom to top.
+    pub stack: Vec<VerificationType>,
+}
+
+impl Frame {
+    /// Whether every slot of this frame is assignable to the same slot of `other`, and both
+    /// have the same stack depth.
+    pub fn is_assignable_to(&self, other: &Frame) -> bool {
+        let locals = self
+            .locals
+            .iter()
+            .chain(std::iter::repeat(&VerificationType::Top))
+            .zip(&other.locals)
+            .all(|(from, to)| from.is_assignable_to(to));
+        locals
+            && self.stack.len() == other.stack.len()
+            && self
+                .stack
+                .iter()
+                .zip(&other.stack)
+                .all(|(from, to)| from.is_assignable_to(to))
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |types: &[VerificationType]| {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "locals [{}], stack [{}]",
+            join(&self.locals),
+            join(&self.stack)
+        )
+    }
+}
+
+/// What the verifier found wrong with an instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifyErrorKind {
+    /// The instruction pops more values than the operand stack holds.
+    StackUnderflow,
+    /// The operand stack grows deeper than `max_stack`.
+    StackOverflow { depth: usize, max_stack: u16 },
+    /// A local variable index at or past `max_locals`.
+    InvalidLocal { index: u16, max_locals: u16 },
+    /// A value of the wrong type was found on the stack or in a local variable.
+    TypeMismatch {
+        expected: VerificationType,
+        found: VerificationType,
+    },
+    /// A return instruction that does not match the method's return type.
+    BadReturn,
+    /// Paths with stacks of different depths or incompatible types join at this instruction.
+    InconsistentStack,
+    /// The computed frame does not match the StackMapTable frame for this instruction.
+    FrameMismatch { computed: Frame, declared: Frame },
+    /// A branch target or exception handler without a StackMapTable frame.
+    MissingFrame,
+    /// A StackMapTable frame whose offset is not the address of an instruction.
+    FrameOffset,
+    /// A branch to an address that is not an instruction.
+    InvalidBranchTarget(i64),
+    /// Execution can continue past the last instruction.
+    FallsOffEnd,
+    /// `jsr` and `ret` are not supported.
+    Subroutine,
+    /// An instruction that can never be reached, so it has no frame.
+    Unreachable,
+    /// A constant pool reference that could not be resolved.
+    Constant(ConstantPoolError),
+    /// A malformed descriptor in the constant pool.
+    InvalidDescriptor(String),
+}
+
+impl fmt::Display for VerifyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyErrorKind::StackUnderflow => write!(f, "operand stack underflow"),
+            VerifyErrorKind::StackOverflow { depth, max_stack } => write!(
+                f,
+                "operand stack depth {} exceeds max_stack {}",
+                depth, max_stack
+            ),
+            VerifyErrorKind::InvalidLocal { index, max_locals } => write!(
+                f,
+                "local variable {} is out of range for max_locals {}",
+                index, max_locals
+            ),
+            VerifyErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            VerifyErrorKind::BadReturn => write!(f, "return does not match the method type"),
+            VerifyErrorKind::InconsistentStack => write!(f, "inconsistent operand stack"),
+            VerifyErrorKind::FrameMismatch { computed, declared } => write!(
+                f,
+                "computed frame ({}) is not assignable to stack map frame ({})",
+                computed, declared
+            ),
+            VerifyErrorKind::MissingFrame => write!(f, "missing stack map frame"),
+            VerifyErrorKind::FrameOffset => {
+                write!(f, "stack map frame offset is not an instruction")
+            }
+            VerifyErrorKind::InvalidBranchTarget(target) => {
+                write!(f, "branch target {} is not an instruction", target)
+            }
+            VerifyErrorKind::FallsOffEnd => write!(f, "execution falls off the end of the code"),
+            VerifyErrorKind::Subroutine => write!(f, "jsr and ret are not supported"),
+            VerifyErrorKind::Unreachable => write!(f, "unreachable instruction"),
+            VerifyErrorKind::Constant(e) => write!(f, "{}", e),
+            VerifyErrorKind::InvalidDescriptor(descriptor) => {
+                write!(f, "invalid descriptor {}", descriptor)
+            }
+        }
+    }
+}
+
+impl From<ConstantPoolError> for VerifyErrorKind {
+    fn from(e: ConstantPoolError) -> Self {
+        VerifyErrorKind::Constant(e)
+    }
+}
+
+/// A problem found at the instruction at `address`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyError {
+    pub address: usize,
+    pub kind: VerifyErrorKind,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.kind, self.address)
+    }
+}
+
+impl Error for VerifyError {}
+
+/// The result of verifying a method.
+#[derive(Clone, Debug, Default)]
+pub struct MethodVerification {
+    /// The frame before each reachable instruction, by address.
+    pub frames: BTreeMap<usize, Frame>,
+    /// The deepest the operand stack gets, in slots.
+    pub max_stack: u16,
+    /// The problems found, ordered by address.
+    pub errors: Vec<VerifyError>,
+}
+
+impl MethodVerification {
+    /// Whether no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
@@ -0,0 +1,312 @@
+extern crate classfile_parser;
+
+mod common;
+
+use classfile_parser::code_attribute::{
+    Assembler, AssemblerError, Branch, CodeItem, Instruction, code_encoder, code_parser,
+    instruction_encoder, instruction_parser,
+};
+use classfile_parser::parse_class;
+
+fn method_code(class_name: &str) -> Vec<Vec<u8>> {
+    let class_file =
+        parse_class(&format!("./java-assets/compiled-classes/{}", class_name)).unwrap();
+    class_file
+        .methods
+        .iter()
+        .filter_map(|method| common::code(&class_file, method))
+        .map(|code| code.code)
+        .collect()
+}
+
+#[test]
+fn encode_compiled_methods() {
+    for class_name in [
+        "BasicClass",
+        "Instructions",
+        "Factorial",
+        "LocalVariableTable",
+    ] {
+        for code in method_code(class_name) {
+            let (rest, instructions) = code_parser(&code).unwrap();
+            assert!(rest.is_empty());
+
+            let plain: Vec<_> = instructions.iter().map(|(_, i)| i.clone()).collect();
+            assert_eq!(code_encoder(&plain), code);
+
+            let (assembler, _) = Assembler::from_instructions(&instructions).unwrap();
+            assert_eq!(assembler.assemble().unwrap().code, code);
+        }
+    }
+}
+
+#[test]
+fn switch_padding() {
+    let tableswitch = Instruction::Tableswitch {
+        default: 20,
+        low: 1,
+        high: 2,
+        offsets: vec![30, 40],
+    };
+    let lookupswitch = Instruction::Lookupswitch {
+        default: 20,
+        pairs: vec![(-1, 30), (7, 40)],
+    };
+    for address in 0..4 {
+        for instruction in [&tableswitch, &lookupswitch] {
+            let mut encoded = Vec::new();
+            instruction_encoder(instruction, address, &mut encoded);
+            assert_eq!(
+                instruction_parser(&encoded, address),
+                Ok((&[][..], instruction.clone()))
+            );
+        }
+    }
+}
+
+#[test]
+fn wide_instructions() {
+    let instructions = vec![
+        Instruction::IloadWide(0x1234),
+        Instruction::IincWide {
+            index: 0x0102,
+            value: -2,
+        },
+        Instruction::RetWide(300),
+        Instruction::GotoW(-9),
+    ];
+    let code = code_encoder(&instructions);
+    assert_eq!(
+        code,
+        vec![
+            0xc4, 0x15, 0x12, 0x34, 0xc4, 0x84, 0x01, 0x02, 0xff, 0xfe, 0xc4, 0xa9, 0x01, 0x2c,
+            0xc8, 0xff, 0xff, 0xff, 0xf7
+        ]
+    );
+    let decoded: Vec<_> = code_parser(&code)
+        .unwrap()
+        .1
+        .into_iter()
+        .map(|(_, i)| i)
+        .collect();
+    assert_eq!(decoded, instructions);
+}
+
+#[test]
+fn widen_long_branches() {
+    let mut assembler = Assembler::new();
+    let end = assembler.new_label();
+    let start = assembler.new_label();
+    assembler.place(start);
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifne, end);
+    assembler.branch(Branch::Goto, end);
+    assembler.branch(Branch::Goto, start);
+    for _ in 0..40000 {
+        assembler.emit(Instruction::Nop);
+    }
+    assembler.place(end);
+    assembler.emit(Instruction::Return);
+
+    let assembled = assembler.assemble().unwrap();
+    let end_offset = assembled.label_offset(end).unwrap();
+    let (_, instructions) = code_parser(&assembled.code).unwrap();
+
+    // ifne is rewritten as ifeq over a goto_w
+    assert_eq!(instructions[1], (1, Instruction::Ifeq(8)));
+    assert_eq!(
+        instructions[2],
+        (4, Instruction::GotoW(end_offset as i32 - 4))
+    );
+    assert_eq!(
+        instructions[3],
+        (9, Instruction::GotoW(end_offset as i32 - 9))
+    );
+    // the backwards goto is still in range and stays short
+    assert_eq!(instructions[4], (14, Instruction::Goto(-14)));
+}
+
+#[test]
+fn insert_into_decoded_code() {
+    // iload_0; ifeq +6; iinc 0 -1; return
+    let code = vec![0x1a, 0x99, 0x00, 0x06, 0x84, 0x00, 0xff, 0xb1];
+    let (_, instructions) = code_parser(&code).unwrap();
+    let (mut assembler, labels) = Assembler::from_instructions(&instructions).unwrap();
+
+    // insert a nop after the label of the iinc, so the ifeq jumps to the nop
+    let position = assembler
+        .items()
+        .iter()
+        .position(|item| *item == CodeItem::Label(labels[&4]))
+        .unwrap();
+    assembler
+        .items_mut()
+        .insert(position + 1, CodeItem::Instruction(Instruction::Nop));
+
+    let assembled = assembler.assemble().unwrap();
+    assert_eq!(
+        assembled.code,
+        vec![0x1a, 0x99, 0x00, 0x07, 0x00, 0x84, 0x00, 0xff, 0xb1]
+    );
+    assert_eq!(assembled.label_offset(labels[&7]), Some(8));
+    assert_eq!(assembled.label_offset(labels[&8]), Some(9));
+}
+
+#[test]
+fn switches_with_labels() {
+    let mut assembler = Assembler::new();
+    let (a, b, default) = (
+        assembler.new_label(),
+        assembler.new_label(),
+        assembler.new_label(),
+    );
+    assembler.emit(Instruction::Iload0);
+    assembler.lookupswitch(default, vec![(10, b), (-5, a)]);
+    assembler.place(a);
+    assembler.emit(Instruction::Iconst0);
+    assembler.place(b);
+    assembler.emit(Instruction::Iconst1);
+    assembler.place(default);
+    assembler.tableswitch(0, default, vec![a, b]);
+
+    let assembled = assembler.assemble().unwrap();
+    let (_, instructions) = code_parser(&assembled.code).unwrap();
+    assert_eq!(
+        instructions[1],
+        (
+            1,
+            Instruction::Lookupswitch {
+                default: 29,
+                pairs: vec![(-5, 27), (10, 28)]
+            }
+        )
+    );
+    assert_eq!(
+        instructions[4],
+        (
+            30,
+            Instruction::Tableswitch {
+                default: 0,
+                low: 0,
+                high: 1,
+                offsets: vec![-2, -1]
+            }
+        )
+    );
+}
+
+#[test]
+fn assembler_errors() {
+    let mut assembler = Assembler::new();
+    let label = assembler.new_label();
+    assembler.branch(Branch::Goto, label);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::UnplacedLabel(label)
+    );
+
+    assembler.place(label);
+    assembler.place(label);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::LabelPlacedTwice(label)
+    );
+
+    let mut assembler = Assembler::new();
+    for _ in 0..70000 {
+        assembler.emit(Instruction::Nop);
+    }
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::CodeTooLarge(70000)
+    );
+
+    let broken = vec![(0, Instruction::Goto(2)), (3, Instruction::Return)];
+    assert_eq!(
+        Assembler::from_instructions(&broken).unwrap_err(),
+        AssemblerError::InvalidBranchTarget {
+            address: 0,
+            target: 2
+        }
+    );
+}
+
+#[test]
+fn foreign_labels() {
+    let mut other = Assembler::new();
+    other.new_label();
+    let foreign = other.new_label();
+
+    let mut assembler = Assembler::new();
+    assembler.branch(Branch::Goto, foreign);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::UnknownLabel(foreign)
+    );
+
+    let mut assembler = Assembler::new();
+    assembler.place(foreign);
+    assembler.emit(Instruction::Return);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::UnknownLabel(foreign)
+    );
+}
+
+#[test]
+fn invalid_tableswitch_ranges() {
+    let mut assembler = Assembler::new();
+    let default = assembler.new_label();
+    assembler.tableswitch(0, default, vec![]);
+    assembler.place(default);
+    assembler.emit(Instruction::Return);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::InvalidTableswitch { low: 0, targets: 0 }
+    );
+
+    let mut assembler = Assembler::new();
+    let default = assembler.new_label();
+    assembler.tableswitch(i32::MAX, default, vec![default, default]);
+    assembler.place(default);
+    assembler.emit(Instruction::Return);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::InvalidTableswitch {
+            low: i32::MAX,
+            targets: 2
+        }
+    );
+
+    // A single key at i32::MAX is fine
+    let mut assembler = Assembler::new();
+    let default = assembler.new_label();
+    assembler.tableswitch(i32::MAX, default, vec![default]);
+    assembler.place(default);
+    assembler.emit(Instruction::Return);
+    let code = assembler.assemble().unwrap().code;
+    let (_, instructions) = code_parser(&code).unwrap();
+    assert!(matches!(
+        instructions[0].1,
+        Instruction::Tableswitch {
+            low: i32::MAX,
+            high: i32::MAX,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn duplicate_lookupswitch_keys() {
+    let mut assembler = Assembler::new();
+    let default = assembler.new_label();
+    let target = assembler.new_label();
+    assembler.lookupswitch(default, vec![(3, target), (1, default), (3, default)]);
+    assembler.place(target);
+    assembler.place(default);
+    assembler.emit(Instruction::Return);
+    assert_eq!(
+        assembler.assemble().unwrap_err(),
+        AssemblerError::DuplicateLookupswitchKey(3)
+    );
+}
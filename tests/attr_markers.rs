@@ -0,0 +1,116 @@
+extern crate classfile_parser;
+
+use classfile_parser::attribute_info::{AttributeInfo, TypedAttribute};
+use classfile_parser::class_parser;
+use classfile_parser::constant_info::{ConstantInfo, ConstantPool, Utf8Constant};
+
+fn attribute_names(pool: ConstantPool, attributes: &[AttributeInfo]) -> Vec<String> {
+    attributes
+        .iter()
+        .map(|attribute| {
+            pool.get_utf8(attribute.attribute_name_index)
+                .unwrap()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn deprecated_members() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/DeprecatedAnnotation.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    assert!(class.is_deprecated());
+    assert!(
+        class
+            .typed_attributes()
+            .unwrap()
+            .iter()
+            .any(|attribute| matches!(attribute, TypedAttribute::Deprecated(_)))
+    );
+
+    assert!(class.fields[0].is_deprecated(&class.const_pool));
+    let (constructor, method) = (&class.methods[0], &class.methods[1]);
+    assert!(!constructor.is_deprecated(&class.const_pool));
+    assert!(method.is_deprecated(&class.const_pool));
+
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    assert!(!class.is_deprecated());
+}
+
+#[test]
+fn deprecated_by_annotation_only() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/DeprecatedAnnotation.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let pool = class.constant_pool();
+    let mut method = class.methods[1].clone();
+
+    method
+        .attributes
+        .retain(|attribute| pool.get_utf8(attribute.attribute_name_index).unwrap() != "Deprecated");
+    assert_eq!(
+        attribute_names(pool, &method.attributes),
+        ["Code", "RuntimeVisibleAnnotations"]
+    );
+    assert!(method.is_deprecated(&class.const_pool));
+
+    method.attributes.pop();
+    assert!(!method.is_deprecated(&class.const_pool));
+}
+
+#[test]
+fn synthetic_members() {
+    // The reference to the enclosing instance is a synthetic field
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates$Inner.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    assert!(!class.is_synthetic());
+    let pool = class.constant_pool();
+    let this = class
+        .fields
+        .iter()
+        .find(|field| pool.get_utf8(field.name_index).unwrap() == "this$0")
+        .unwrap();
+    assert!(this.is_synthetic(&class.const_pool));
+
+    // Lambda bodies are compiled to synthetic methods
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BootstrapMethods.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let pool = class.constant_pool();
+    for method in &class.methods {
+        let name = pool.get_utf8(method.name_index).unwrap();
+        assert_eq!(
+            method.is_synthetic(&class.const_pool),
+            name.starts_with("lambda$"),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn synthetic_attribute() {
+    // javac only sets ACC_SYNTHETIC, so add a Synthetic attribute the way older compilers did
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let (_, mut class) = class_parser(class_bytes).unwrap();
+    class
+        .const_pool
+        .push(ConstantInfo::Utf8(Utf8Constant::from("Synthetic")));
+    let synthetic = AttributeInfo {
+        attribute_name_index: class.const_pool.len() as u16,
+        attribute_length: 0,
+        info: vec![],
+    };
+
+    let mut method = class.methods[0].clone();
+    assert!(!method.is_synthetic(&class.const_pool));
+    method.attributes.push(synthetic.clone());
+    assert!(method.is_synthetic(&class.const_pool));
+    assert!(matches!(
+        method.typed_attributes(&class.const_pool).unwrap().last(),
+        Some(TypedAttribute::Synthetic(_))
+    ));
+
+    assert!(!class.is_synthetic());
+    class.attributes.push(synthetic);
+    assert!(class.is_synthetic());
+}
@@ -0,0 +1,153 @@
+extern crate classfile_parser;
+
+use classfile_parser::ClassParseError;
+use classfile_parser::attribute_info::{
+    ModuleAttribute, ModuleDescriptor, ModuleExports, ModuleOpens, ModuleProvides, ModuleRequires,
+    TypedAttribute, module_main_class_attribute_parser, module_packages_attribute_parser,
+};
+use classfile_parser::class_parser;
+use classfile_parser::constant_info::ConstantPoolError;
+
+#[test]
+fn packages_and_main_class() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/app-module-info.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let pool = class.constant_pool();
+    let typed = class.typed_attributes().unwrap();
+
+    let packages = typed
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::ModulePackages(packages) => Some(packages),
+            _ => None,
+        })
+        .expect("jar should add a ModulePackages attribute");
+    assert_eq!(packages.package_count, 3);
+    let names: Vec<_> = packages
+        .package_index
+        .iter()
+        .map(|&index| pool.get_package_name(index).unwrap())
+        .collect();
+    assert_eq!(names, ["com/app", "com/app/api", "com/app/internal"]);
+
+    let main_class = typed
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::ModuleMainClass(main_class) => Some(main_class),
+            _ => None,
+        })
+        .expect("jar --main-class should add a ModuleMainClass attribute");
+    assert_eq!(
+        pool.get_class_name(main_class.main_class_index).unwrap(),
+        "com/app/Main"
+    );
+}
+
+#[test]
+fn module_descriptor() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/app-module-info.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let module = class.module_descriptor().unwrap().unwrap();
+
+    assert_eq!(module.name, "my.app");
+    assert_eq!(module.flags, 0);
+    assert_eq!(module.version, None);
+    assert_eq!(module.requires.len(), 2);
+    assert_eq!(module.requires[0].module, "java.base");
+    assert_eq!(module.requires[0].flags, 0x8000);
+    assert_eq!(
+        module.requires[1],
+        ModuleRequires {
+            module: "java.logging".into(),
+            flags: 0x0020,
+            version: module.requires[0].version.clone(),
+        }
+    );
+    assert!(module.requires[1].version.is_some());
+    assert_eq!(
+        module.exports,
+        [ModuleExports {
+            package: "com/app/api".into(),
+            flags: 0,
+            to: vec![],
+        }]
+    );
+    assert_eq!(
+        module.opens,
+        [ModuleOpens {
+            package: "com/app/internal".into(),
+            flags: 0,
+            to: vec!["java.base".into()],
+        }]
+    );
+    assert_eq!(module.uses, ["com/app/api/Service"]);
+    assert_eq!(
+        module.provides,
+        [ModuleProvides {
+            service: "com/app/api/Service".into(),
+            with: vec!["com/app/internal/DefaultService".into()],
+        }]
+    );
+    assert_eq!(
+        module.packages,
+        ["com/app", "com/app/api", "com/app/internal"]
+    );
+    assert_eq!(module.main_class.as_deref(), Some("com/app/Main"));
+}
+
+#[test]
+fn module_descriptor_without_jar_attributes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/module-info.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let module = class.module_descriptor().unwrap().unwrap();
+    assert_eq!(module.name, "my.module");
+    assert!(module.packages.is_empty());
+    assert_eq!(module.main_class, None);
+
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    assert!(class.module_descriptor().unwrap().is_none());
+}
+
+#[test]
+fn module_descriptor_bad_index() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/module-info.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let module = ModuleAttribute {
+        module_name_index: class.this_class,
+        module_flags: 0,
+        module_version_index: 0,
+        requires: vec![],
+        exports: vec![],
+        opens: vec![],
+        uses: vec![],
+        provides: vec![],
+    };
+    let error = ModuleDescriptor::new(&module, None, None, &class.constant_pool()).unwrap_err();
+    assert!(matches!(
+        error,
+        ConstantPoolError::WrongKind {
+            expected: "Module",
+            found: "Class",
+            ..
+        }
+    ));
+
+    let error = ClassParseError::from(error);
+    assert_eq!(error.offset(), None);
+    assert!(error.to_string().contains("expected Module"));
+}
+
+#[test]
+fn truncated_module_attributes() {
+    let input = [0x00, 0x02, 0x00, 0x09];
+    assert!(module_packages_attribute_parser(&input).is_err());
+    let input = [0x00, 0x02, 0x00, 0x09, 0x00, 0x0b];
+    let (rest, packages) = module_packages_attribute_parser(&input).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(packages.package_index, [9, 11]);
+
+    assert!(module_main_class_attribute_parser(&[0x00]).is_err());
+    let (_, main_class) = module_main_class_attribute_parser(&[0x00, 0x07]).unwrap();
+    assert_eq!(main_class.main_class_index, 7);
+}
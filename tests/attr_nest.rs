@@ -0,0 +1,114 @@
+extern crate classfile_parser;
+
+use classfile_parser::ClassFile;
+use classfile_parser::attribute_info::{
+    TypedAttribute, nest_host_attribute_parser, nest_members_attribute_parser,
+    permitted_subclasses_attribute_parser,
+};
+use classfile_parser::class_parser;
+
+fn class_names(class: &ClassFile, indices: &[u16]) -> Vec<String> {
+    let pool = class.constant_pool();
+    indices
+        .iter()
+        .map(|&index| pool.get_class_name(index).unwrap().into_owned())
+        .collect()
+}
+
+#[test]
+fn nest_host_and_members() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates.class");
+    let (_, host) = class_parser(class_bytes).unwrap();
+    let members = host
+        .typed_attributes()
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::NestMembers(members) => Some(members),
+            _ => None,
+        })
+        .expect("Nestmates should have a NestMembers attribute");
+    assert_eq!(members.number_of_classes, 4);
+    let mut names = class_names(&host, &members.classes);
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "Nestmates$Circle",
+            "Nestmates$Inner",
+            "Nestmates$Shape",
+            "Nestmates$Square"
+        ]
+    );
+
+    // The inner class reads the host's private field, so both sides must name each other
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates$Inner.class");
+    let (_, inner) = class_parser(class_bytes).unwrap();
+    let nest_host = inner
+        .typed_attributes()
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::NestHost(nest_host) => Some(nest_host),
+            _ => None,
+        })
+        .expect("Nestmates$Inner should have a NestHost attribute");
+    assert_eq!(
+        class_names(&inner, &[nest_host.host_class_index]),
+        ["Nestmates"]
+    );
+}
+
+#[test]
+fn permitted_subclasses() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates$Shape.class");
+    let (_, shape) = class_parser(class_bytes).unwrap();
+    let typed = shape.typed_attributes().unwrap();
+    let permitted = typed
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::PermittedSubclasses(permitted) => Some(permitted),
+            _ => None,
+        })
+        .expect("Nestmates$Shape should have a PermittedSubclasses attribute");
+    assert_eq!(permitted.number_of_classes, 2);
+    assert_eq!(
+        class_names(&shape, &permitted.classes),
+        ["Nestmates$Circle", "Nestmates$Square"]
+    );
+    assert!(
+        typed
+            .iter()
+            .any(|attribute| matches!(attribute, TypedAttribute::NestHost(_)))
+    );
+
+    // Subclasses of a sealed interface are not sealed themselves
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates$Square.class");
+    let (_, square) = class_parser(class_bytes).unwrap();
+    assert!(
+        !square
+            .typed_attributes()
+            .unwrap()
+            .iter()
+            .any(|attribute| matches!(attribute, TypedAttribute::PermittedSubclasses(_)))
+    );
+}
+
+#[test]
+fn truncated_nest_attributes() {
+    assert!(nest_host_attribute_parser(&[0x00]).is_err());
+    let (rest, nest_host) = nest_host_attribute_parser(&[0x00, 0x07]).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(nest_host.host_class_index, 7);
+
+    // Two classes declared, only one present
+    let input = [0x00, 0x02, 0x00, 0x03];
+    assert!(nest_members_attribute_parser(&input).is_err());
+    assert!(permitted_subclasses_attribute_parser(&input).is_err());
+
+    let input = [0x00, 0x02, 0x00, 0x03, 0x00, 0x05];
+    let (_, members) = nest_members_attribute_parser(&input).unwrap();
+    assert_eq!(members.classes, [3, 5]);
+    let (_, permitted) = permitted_subclasses_attribute_parser(&input).unwrap();
+    assert_eq!(permitted.classes, [3, 5]);
+}
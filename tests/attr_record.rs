@@ -0,0 +1,66 @@
+extern crate classfile_parser;
+
+use classfile_parser::attribute_info::{TypedAttribute, record_attribute_parser};
+use classfile_parser::class_parser;
+
+#[test]
+fn record_components() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Point.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+    let pool = class.constant_pool();
+
+    let record = class
+        .typed_attributes()
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::Record(record) => Some(record),
+            _ => None,
+        })
+        .expect("Point should have a Record attribute");
+    assert_eq!(record.components_count, 4);
+
+    let components: Vec<_> = record
+        .components
+        .iter()
+        .map(|component| {
+            (
+                pool.get_utf8(component.name_index).unwrap(),
+                pool.get_utf8(component.descriptor_index).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        components,
+        [
+            ("x".into(), "I".into()),
+            ("y".into(), "I".into()),
+            ("label".into(), "Ljava/lang/Object;".into()),
+            ("tags".into(), "Ljava/util/List;".into()),
+        ]
+    );
+
+    // Generic components carry a Signature, annotated ones their annotations
+    let label = record.components[2]
+        .typed_attributes(&class.const_pool)
+        .unwrap();
+    assert!(matches!(label[0], TypedAttribute::Signature(_)));
+    assert!(matches!(
+        label[1],
+        TypedAttribute::RuntimeVisibleAnnotations(_)
+    ));
+    assert_eq!(record.components[0].attributes_count, 0);
+}
+
+#[test]
+fn truncated_record() {
+    // One component whose attribute count is cut off
+    let input = [0x00, 0x01, 0x00, 0x0b, 0x00, 0x0c];
+    assert!(record_attribute_parser(&input).is_err());
+
+    let input = [0x00, 0x01, 0x00, 0x0b, 0x00, 0x0c, 0x00, 0x00];
+    let (rest, record) = record_attribute_parser(&input).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(record.components[0].name_index, 0x0b);
+    assert_eq!(record.components[0].descriptor_index, 0x0c);
+}
@@ -1,9 +1,10 @@
 extern crate classfile_parser;
 extern crate nom;
 
-use classfile_parser::attribute_info::AttributeInfo;
-use classfile_parser::class_parser;
-use classfile_parser::constant_info::ConstantInfo;
+use classfile_parser::attribute_info::{AttributeInfo, StackMapTableAttribute, TypedAttribute};
+use classfile_parser::constant_info::{ConstantInfo, Utf8Constant};
+use classfile_parser::verifier::{VerificationType, initial_locals};
+use classfile_parser::{ClassFile, ClassParseError, class_parser, parse_class};
 
 #[test]
 fn test_attribute_stack_map_table() {
@@ -84,3 +85,124 @@ fn test_attribute_stack_map_table() {
         _ => panic!("not a class file"),
     };
 }
+
+fn method_stack_map(
+    class_file: &ClassFile,
+    name: &str,
+) -> (Vec<VerificationType>, StackMapTableAttribute) {
+    let pool = class_file.constant_pool();
+    let method = class_file
+        .methods
+        .iter()
+        .find(|method| pool.get_utf8(method.name_index).unwrap() == name)
+        .unwrap();
+    let code = method
+        .typed_attributes(&class_file.const_pool)
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::Code(code) => Some(code),
+            _ => None,
+        })
+        .unwrap();
+    let table = code
+        .typed_attributes(&class_file.const_pool)
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::StackMapTable(table) => Some(table),
+            _ => None,
+        })
+        .unwrap();
+    (initial_locals(class_file, method).unwrap(), table)
+}
+
+#[test]
+fn absolute_frame_offsets() {
+    use VerificationType::*;
+
+    let class_file = parse_class("./java-assets/compiled-classes/Disassembly").unwrap();
+    let object = |name: &str| Object(name.to_string());
+
+    // private int sw(String): an append frame, then same frames
+    let (initial_locals, table) = method_stack_map(&class_file, "sw");
+    assert_eq!(
+        initial_locals,
+        [object("Disassembly"), object("java/lang/String")]
+    );
+    let frames = table
+        .frames(initial_locals, class_file.constant_pool())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let offsets: Vec<_> = frames.iter().map(|(offset, _, _)| *offset).collect();
+    assert_eq!(offsets, [36, 50, 61, 88, 90, 92]);
+    for (_, locals, stack) in &frames {
+        assert_eq!(
+            locals[..],
+            [
+                object("Disassembly"),
+                object("java/lang/String"),
+                object("java/lang/String"),
+                Integer
+            ]
+        );
+        assert!(stack.is_empty());
+    }
+
+    // static void varargs(String...): a full frame, then a chop frame
+    let (initial_locals, table) = method_stack_map(&class_file, "varargs");
+    assert_eq!(initial_locals, [object("[Ljava/lang/String;")]);
+    let frames = table
+        .frames(initial_locals, class_file.constant_pool())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        frames,
+        [
+            (
+                21,
+                vec![
+                    object("[Ljava/lang/String;"),
+                    Long,
+                    Double,
+                    Float,
+                    object("[Ljava/lang/String;"),
+                    Integer,
+                    Integer
+                ],
+                vec![]
+            ),
+            (
+                50,
+                vec![object("[Ljava/lang/String;"), Long, Double, Float],
+                vec![]
+            )
+        ]
+    );
+}
+
+#[test]
+fn constructor_initial_locals() {
+    use VerificationType::*;
+
+    let class_file = parse_class("./java-assets/compiled-classes/Disassembly").unwrap();
+    let pool = class_file.constant_pool();
+    let constructor = class_file
+        .methods
+        .iter()
+        .find(|method| pool.get_utf8(method.name_index).unwrap() == "<init>")
+        .unwrap();
+    assert_eq!(
+        initial_locals(&class_file, constructor).unwrap(),
+        [UninitializedThis]
+    );
+
+    let mut class_file = class_file.clone();
+    let descriptor_index = class_file.methods[0].descriptor_index;
+    class_file.const_pool[descriptor_index as usize - 1] =
+        ConstantInfo::Utf8(Utf8Constant::from("(X)V"));
+    assert!(matches!(
+        initial_locals(&class_file, &class_file.methods[0]),
+        Err(ClassParseError::Descriptor(_))
+    ));
+}
@@ -0,0 +1,216 @@
+extern crate classfile_parser;
+
+mod common;
+
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+
+use classfile_parser::attribute_info::TypedAttribute;
+use classfile_parser::field_info::field_parser;
+use classfile_parser::method_info::method_parser;
+use classfile_parser::{
+    ParseContext, ParseErrorCause, parse_class_from_bytes, parse_class_ref, parse_header,
+    parse_until_constant_pool, scan_class,
+};
+
+fn compiled_classes() -> Vec<Vec<u8>> {
+    let mut paths = Vec::new();
+    common::class_files(Path::new("java-assets/compiled-classes"), &mut paths);
+    paths.sort();
+    paths
+        .into_iter()
+        .filter(|path| !path.ends_with("malformed.class"))
+        .map(|path| fs::read(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn matches_owned_parse() {
+    for class_bytes in compiled_classes() {
+        let owned = parse_class_from_bytes(&class_bytes).unwrap();
+        let borrowed = parse_class_ref(&class_bytes).unwrap().to_class_file();
+        assert_eq!(format!("{:?}", borrowed), format!("{:?}", owned));
+        assert_eq!(borrowed.to_bytes().unwrap(), class_bytes);
+    }
+}
+
+#[test]
+fn strings_borrow_from_input() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let class_file = parse_class_ref(class_bytes).unwrap();
+    let pool = &class_file.header.const_pool;
+
+    let name = class_file.this_class_name().unwrap();
+    assert_eq!(name, "HelloWorld");
+    assert!(matches!(name, Cow::Borrowed(_)));
+    assert_eq!(
+        class_file.super_class_name().unwrap().as_deref(),
+        Some("java/lang/Object")
+    );
+
+    let method = &class_file.methods[1];
+    assert_eq!(pool.get_utf8(method.name_index).unwrap(), "main");
+    let range = class_bytes.as_ptr_range();
+    let code = method.code(pool).unwrap().unwrap();
+    assert!(range.contains(&code.code.as_ptr()));
+    assert!(range.contains(&method.attributes[0].info.as_ptr()));
+}
+
+#[test]
+fn modified_utf8_strings() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/UnicodeStrings.class");
+    let owned = parse_class_from_bytes(class_bytes).unwrap();
+    let class_file = parse_class_ref(class_bytes).unwrap();
+    for index in 1..=class_file.header.const_pool.len() as u16 {
+        if let Ok(string) = class_file.header.const_pool.get_utf8(index) {
+            assert_eq!(string, owned.constant_pool().get_utf8(index).unwrap());
+        }
+    }
+}
+
+#[test]
+fn code_matches_owned() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Instructions.class");
+    let owned = parse_class_from_bytes(class_bytes).unwrap();
+    let class_file = parse_class_ref(class_bytes).unwrap();
+    let pool = &class_file.header.const_pool;
+
+    for (method, owned_method) in class_file.methods.iter().zip(&owned.methods) {
+        let code = method.code(pool).unwrap().unwrap();
+        let owned_code = owned_method
+            .typed_attributes(&owned.const_pool)
+            .unwrap()
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                TypedAttribute::Code(code) => Some(code),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(code.max_stack, owned_code.max_stack);
+        assert_eq!(code.max_locals, owned_code.max_locals);
+        assert_eq!(code.code, owned_code.code);
+        assert_eq!(
+            code.instructions().unwrap(),
+            owned_code.instructions().unwrap()
+        );
+        assert!(code.attribute(pool, "LineNumberTable").is_some());
+    }
+}
+
+#[test]
+fn member_refs() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let owned = parse_class_from_bytes(class_bytes).unwrap();
+    let class_file = parse_class_ref(class_bytes).unwrap();
+    for index in 1..=class_file.header.const_pool.len() as u16 {
+        assert_eq!(
+            class_file.header.const_pool.resolve_member_ref(index).ok(),
+            owned.constant_pool().resolve_member_ref(index).ok()
+        );
+    }
+}
+
+#[test]
+fn errors_match_owned_parse() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    for cut in [4, 20, class_bytes.len() - 40, class_bytes.len() - 1] {
+        let owned = parse_class_from_bytes(&class_bytes[..cut]).unwrap_err();
+        let borrowed = parse_class_ref(&class_bytes[..cut]).unwrap_err();
+        assert_eq!(borrowed.cause(), Some(&ParseErrorCause::Truncated));
+        assert_eq!(borrowed.context(), owned.context(), "cut at {}", cut);
+    }
+
+    let mut class_bytes = class_bytes.to_vec();
+    class_bytes[10] = 2;
+    let error = parse_class_ref(&class_bytes).unwrap_err();
+    assert_eq!(error.offset(), Some(10));
+    assert_eq!(error.context(), &[ParseContext::Constant(1)]);
+    assert_eq!(error.cause(), Some(&ParseErrorCause::UnknownConstantTag(2)));
+}
+
+#[test]
+fn header_matches_owned_parse() {
+    for class_bytes in compiled_classes() {
+        let owned = parse_class_from_bytes(&class_bytes).unwrap();
+        let header = parse_header(&class_bytes).unwrap();
+        assert_eq!(header.major_version, owned.major_version);
+        assert_eq!(header.access_flags, owned.access_flags);
+        assert_eq!(
+            header.this_class_name().unwrap(),
+            owned.this_class_name().unwrap()
+        );
+        assert_eq!(
+            header.super_class_name().unwrap(),
+            owned.super_class_name().unwrap()
+        );
+        assert_eq!(
+            header.interface_names().unwrap(),
+            owned.interface_names().unwrap()
+        );
+
+        let prefix = parse_until_constant_pool(&class_bytes).unwrap();
+        assert_eq!(
+            prefix.const_pool.to_constants().len(),
+            owned.const_pool.len()
+        );
+    }
+}
+
+#[test]
+fn header_ignores_members() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/Nestmates$Square.class");
+    let layout = scan_class(class_bytes).unwrap();
+    // The header ends before the fields and methods counts
+    assert!(layout.fields.is_empty());
+    let end = layout.methods[0].range.start - 4;
+
+    let header = parse_header(&class_bytes[..end]).unwrap();
+    assert_eq!(header.this_class_name().unwrap(), "Nestmates$Square");
+    assert_eq!(header.interface_names().unwrap(), ["Nestmates$Shape"]);
+
+    let error = parse_header(&class_bytes[..end - 1]).unwrap_err();
+    assert_eq!(error.context(), &[ParseContext::ClassInfo]);
+}
+
+#[test]
+fn layout_ranges() {
+    for class_bytes in compiled_classes() {
+        let owned = parse_class_from_bytes(&class_bytes).unwrap();
+        let layout = scan_class(&class_bytes).unwrap();
+        assert_eq!(layout.fields.len(), owned.fields.len());
+        assert_eq!(layout.methods.len(), owned.methods.len());
+
+        for (field, owned_field) in layout.fields.iter().zip(&owned.fields) {
+            let (rest, parsed) = field_parser(&class_bytes[field.range.clone()]).unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(field.access_flags, owned_field.access_flags);
+            assert_eq!(format!("{:?}", parsed), format!("{:?}", owned_field));
+        }
+        for (method, owned_method) in layout.methods.iter().zip(&owned.methods) {
+            let (rest, parsed) = method_parser(&class_bytes[method.range.clone()]).unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(method.name_index, owned_method.name_index);
+            assert_eq!(method.descriptor_index, owned_method.descriptor_index);
+            assert_eq!(format!("{:?}", parsed), format!("{:?}", owned_method));
+        }
+
+        let attributes = &class_bytes[layout.attributes.clone()];
+        assert_eq!(
+            u16::from_be_bytes([attributes[0], attributes[1]]),
+            owned.attributes_count
+        );
+        assert_eq!(layout.attributes.end, class_bytes.len());
+    }
+}
+
+#[test]
+fn layout_errors_match_owned_parse() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    for cut in [20, class_bytes.len() - 40, class_bytes.len() - 1] {
+        let owned = parse_class_from_bytes(&class_bytes[..cut]).unwrap_err();
+        let scanned = scan_class(&class_bytes[..cut]).unwrap_err();
+        assert_eq!(scanned.cause(), Some(&ParseErrorCause::Truncated));
+        assert_eq!(scanned.context(), owned.context(), "cut at {}", cut);
+    }
+}
@@ -0,0 +1,239 @@
+extern crate classfile_parser;
+
+use classfile_parser::call_graph::{CallGraph, DynamicCallSite, InvokeKind, MethodId};
+use classfile_parser::hierarchy::ClassHierarchy;
+use classfile_parser::{ClassFile, parse_class};
+
+fn classes(names: &[&str]) -> Vec<ClassFile> {
+    names
+        .iter()
+        .map(|name| parse_class(&format!("./java-assets/compiled-classes/{}", name)).unwrap())
+        .collect()
+}
+
+fn dispatch_classes() -> Vec<ClassFile> {
+    classes(&[
+        "Dispatch",
+        "Dispatch$Greeter",
+        "Dispatch$Base",
+        "Dispatch$Loud",
+        "Dispatch$Quiet",
+    ])
+}
+
+fn targets(graph: &CallGraph, caller: &MethodId) -> Vec<Vec<String>> {
+    graph
+        .call_sites(caller)
+        .iter()
+        .map(|call_site| call_site.targets.iter().map(MethodId::to_string).collect())
+        .collect()
+}
+
+#[test]
+fn call_sites() {
+    let graph = CallGraph::new(&dispatch_classes(), None).unwrap();
+    let run = MethodId::new("Dispatch", "run", "(LDispatch$Greeter;LDispatch$Base;)V");
+    let call_sites = graph.call_sites(&run);
+
+    let summary: Vec<_> = call_sites
+        .iter()
+        .map(|call_site| {
+            (
+                call_site.offset,
+                call_site.line,
+                call_site.kind,
+                call_site.target.as_ref().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                4,
+                Some(39),
+                InvokeKind::Interface,
+                "Dispatch$Greeter.greet()Ljava/lang/String;".to_string()
+            ),
+            (
+                9,
+                Some(39),
+                InvokeKind::Virtual,
+                "java/io/PrintStream.println(Ljava/lang/String;)V".to_string()
+            ),
+            (
+                16,
+                Some(40),
+                InvokeKind::Virtual,
+                "Dispatch$Base.size()I".to_string()
+            ),
+            (
+                19,
+                Some(40),
+                InvokeKind::Virtual,
+                "java/io/PrintStream.println(I)V".to_string()
+            ),
+            (
+                26,
+                Some(41),
+                InvokeKind::Interface,
+                "Dispatch$Greeter.name()Ljava/lang/String;".to_string()
+            ),
+            (
+                31,
+                Some(41),
+                InvokeKind::Virtual,
+                "java/io/PrintStream.println(Ljava/lang/String;)V".to_string()
+            ),
+        ]
+    );
+    for call_site in call_sites {
+        assert_eq!(call_site.targets, call_site.target.as_slice());
+        assert_eq!(call_site.dynamic, None);
+    }
+
+    // Abstract methods have no code, so no call sites
+    assert!(
+        graph
+            .methods
+            .contains_key(&MethodId::new("Dispatch$Loud", "size", "()I"))
+    );
+    assert!(
+        !graph
+            .methods
+            .contains_key(&MethodId::new("Dispatch$Base", "size", "()I"))
+    );
+}
+
+#[test]
+fn class_hierarchy_analysis() {
+    let classes = dispatch_classes();
+    let hierarchy = ClassHierarchy::from_classes(&classes).unwrap();
+    let graph = CallGraph::new(&classes, Some(&hierarchy)).unwrap();
+
+    let run = MethodId::new("Dispatch", "run", "(LDispatch$Greeter;LDispatch$Base;)V");
+    assert_eq!(
+        targets(&graph, &run),
+        [
+            vec![
+                "Dispatch$Base.greet()Ljava/lang/String;",
+                "Dispatch$Loud.greet()Ljava/lang/String;"
+            ],
+            vec!["java/io/PrintStream.println(Ljava/lang/String;)V"],
+            vec!["Dispatch$Loud.size()I", "Dispatch$Quiet.size()I"],
+            vec!["java/io/PrintStream.println(I)V"],
+            vec![
+                "Dispatch$Greeter.name()Ljava/lang/String;",
+                "Dispatch$Quiet.name()Ljava/lang/String;"
+            ],
+            vec!["java/io/PrintStream.println(Ljava/lang/String;)V"],
+        ]
+    );
+
+    // super.greet() is not dispatched
+    let loud_greet = MethodId::new("Dispatch$Loud", "greet", "()Ljava/lang/String;");
+    let call_site = &graph.call_sites(&loud_greet)[0];
+    assert_eq!(call_site.kind, InvokeKind::Special);
+    assert_eq!(
+        call_site.targets,
+        [MethodId::new(
+            "Dispatch$Base",
+            "greet",
+            "()Ljava/lang/String;"
+        )]
+    );
+
+    let size = MethodId::new("Dispatch$Quiet", "size", "()I");
+    let callers: Vec<_> = graph
+        .callers(&size)
+        .map(|(caller, call_site)| (caller.name.as_str(), call_site.offset))
+        .collect();
+    assert_eq!(callers, [("run", 16)]);
+    assert!(
+        graph
+            .edges()
+            .any(|(caller, callee)| caller == &run && callee == &size)
+    );
+}
+
+#[test]
+fn receivers_inheriting_from_outside_the_analysed_classes() {
+    let all = dispatch_classes();
+    let hierarchy = ClassHierarchy::from_classes(&all).unwrap();
+    // Dispatch$Base is known to the hierarchy but its methods are not analysed
+    let classes = classes(&[
+        "Dispatch",
+        "Dispatch$Greeter",
+        "Dispatch$Loud",
+        "Dispatch$Quiet",
+    ]);
+    let graph = CallGraph::new(&classes, Some(&hierarchy)).unwrap();
+
+    // Loud overrides greet() and Quiet inherits it from Base
+    let run = MethodId::new("Dispatch", "run", "(LDispatch$Greeter;LDispatch$Base;)V");
+    assert_eq!(
+        targets(&graph, &run)[0],
+        [
+            "Dispatch$Base.greet()Ljava/lang/String;",
+            "Dispatch$Loud.greet()Ljava/lang/String;"
+        ]
+    );
+}
+
+#[test]
+fn invokedynamic() {
+    let graph = CallGraph::new(&classes(&["BootstrapMethods"]), None).unwrap();
+    let main = MethodId::new("BootstrapMethods", "main", "([Ljava/lang/String;)V");
+    let call_site = &graph.call_sites(&main)[0];
+    assert_eq!(call_site.kind, InvokeKind::Dynamic);
+    assert_eq!(call_site.target, None);
+
+    let bootstrap = MethodId::new(
+        "java/lang/invoke/LambdaMetafactory",
+        "metafactory",
+        "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;",
+    );
+    assert_eq!(
+        call_site.dynamic,
+        Some(DynamicCallSite {
+            name: "get".to_string(),
+            descriptor: "()Ljava/util/function/Supplier;".to_string(),
+            bootstrap: Some(bootstrap.clone()),
+        })
+    );
+
+    // The lambda's body is reached through the call site
+    let lambda = MethodId::new("BootstrapMethods", "lambda$main$0", "()Ljava/lang/String;");
+    assert_eq!(call_site.targets, [bootstrap, lambda.clone()]);
+    let callers: Vec<_> = graph.callers(&lambda).map(|(caller, _)| caller).collect();
+    assert_eq!(callers, [&main]);
+}
+
+#[test]
+fn interface_calls_across_anonymous_classes() {
+    let classes = classes(&[
+        "InnerClasses",
+        "InnerClasses$HelloWorld",
+        "InnerClasses$1",
+        "InnerClasses$2",
+        "InnerClasses$1EnglishGreeting",
+    ]);
+    let hierarchy = ClassHierarchy::from_classes(&classes).unwrap();
+    let graph = CallGraph::new(&classes, Some(&hierarchy)).unwrap();
+
+    let say_hello = MethodId::new("InnerClasses", "sayHello", "()V");
+    let greet = graph
+        .call_sites(&say_hello)
+        .iter()
+        .find(|call_site| call_site.target.as_ref().unwrap().name == "greet")
+        .unwrap();
+    let owners: Vec<_> = greet.targets.iter().map(|t| t.owner.as_str()).collect();
+    assert_eq!(
+        owners,
+        [
+            "InnerClasses$1",
+            "InnerClasses$1EnglishGreeting",
+            "InnerClasses$2"
+        ]
+    );
+}
@@ -0,0 +1,242 @@
+extern crate classfile_parser;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use classfile_parser::class_path::{ClassPath, ClassPathError, ClassSource};
+use classfile_parser::parse_class;
+
+const ASSETS: &str = "java-assets/compiled-classes";
+
+/// A fresh, empty directory for `test` under the system temporary directory.
+fn temp_dir(test: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("classfile-parser-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+/// Copy the compiled asset `class_name` to `directory/target.class`.
+fn copy_class(class_name: &str, directory: &Path, target: &str) {
+    let path = directory.join(format!("{}.class", target));
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::copy(format!("{}/{}.class", ASSETS, class_name), path).unwrap();
+}
+
+#[test]
+fn finds_and_caches_classes() {
+    let mut class_path = ClassPath::new();
+    assert!(class_path.is_empty());
+    class_path.push(ASSETS).unwrap();
+    assert_eq!(class_path.len(), 1);
+
+    let first = class_path.class("HelloWorld").unwrap().unwrap() as *const _;
+    let second = class_path.class("HelloWorld").unwrap().unwrap() as *const _;
+    assert_eq!(first, second);
+    assert_eq!(
+        class_path.find("HelloWorld"),
+        Some(ClassSource::File(
+            Path::new(ASSETS).join("HelloWorld.class")
+        ))
+    );
+
+    let class_file = class_path.class("com/some/Thing");
+    assert!(class_file.unwrap().is_some());
+    assert!(class_path.class("java/lang/Object").unwrap().is_none());
+    assert_eq!(class_path.find("java/lang/Object"), None);
+}
+
+#[test]
+fn first_entry_wins() {
+    let first = temp_dir("first_entry_wins-a");
+    let second = temp_dir("first_entry_wins-b");
+    copy_class("Factorial", &first, "com/example/Main");
+    copy_class("HelloWorld", &second, "com/example/Main");
+    copy_class("HelloWorld", &second, "com/example/Other");
+
+    let joined = std::env::join_paths([&first, &second]).unwrap();
+    let mut class_path = ClassPath::parse(joined.to_str().unwrap()).unwrap();
+    assert_eq!(class_path.len(), 2);
+
+    let main = class_path.class("com/example/Main").unwrap().unwrap();
+    assert_eq!(main.this_class_name().unwrap(), "Factorial");
+    assert_eq!(
+        class_path.find_all("com/example/Main"),
+        [
+            ClassSource::File(first.join("com/example/Main.class")),
+            ClassSource::File(second.join("com/example/Main.class")),
+        ]
+    );
+
+    let shadowed = class_path.shadowed().unwrap();
+    assert_eq!(shadowed.len(), 1);
+    assert_eq!(shadowed["com/example/Main"].len(), 2);
+
+    fs::remove_dir_all(first).unwrap();
+    fs::remove_dir_all(second).unwrap();
+}
+
+#[test]
+fn later_entries_fill_missing_classes() {
+    let first = temp_dir("later_entries-a");
+    let second = temp_dir("later_entries-b");
+    copy_class("HelloWorld", &second, "HelloWorld");
+
+    let mut class_path = ClassPath::new();
+    class_path.push(&first).unwrap();
+    assert!(class_path.class("HelloWorld").unwrap().is_none());
+    class_path.push(&second).unwrap();
+    assert!(class_path.class("HelloWorld").unwrap().is_some());
+
+    fs::remove_dir_all(first).unwrap();
+    fs::remove_dir_all(second).unwrap();
+}
+
+#[test]
+fn supertypes() {
+    let mut class_path = ClassPath::new();
+    class_path.push(ASSETS).unwrap();
+
+    let (supertypes, missing) = class_path
+        .supertypes("InnerClasses$1EnglishGreeting")
+        .unwrap();
+    assert_eq!(supertypes, ["java/lang/Object", "InnerClasses$HelloWorld"]);
+    assert_eq!(missing, ["java/lang/Object"]);
+
+    // A class that is not on the classpath is reported, unlike a root class
+    let (supertypes, missing) = class_path.supertypes("java/lang/String").unwrap();
+    assert!(supertypes.is_empty());
+    assert_eq!(missing, ["java/lang/String"]);
+}
+
+#[test]
+fn supertypes_of_a_removed_class() {
+    let directory = temp_dir("supertypes_of_a_removed_class");
+    // Point the superclass past the end of the constant pool
+    let mut class_file = parse_class(&format!("{}/HelloWorld", ASSETS)).unwrap();
+    class_file.super_class = u16::MAX;
+    let path = directory.join("HelloWorld.class");
+    fs::write(&path, class_file.to_bytes().unwrap()).unwrap();
+
+    let mut class_path = ClassPath::new();
+    class_path.push(&directory).unwrap();
+    assert!(class_path.class("HelloWorld").unwrap().is_some());
+    fs::remove_file(&path).unwrap();
+
+    // The class is still cached, and the error names where it was read from
+    match class_path.supertypes("HelloWorld") {
+        Err(ClassPathError::Class { source, .. }) => assert_eq!(source, ClassSource::File(path)),
+        other => panic!("expected a class error, got {:?}", other),
+    }
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn errors() {
+    let mut class_path = ClassPath::new();
+    class_path.push(ASSETS).unwrap();
+    match class_path.class("malformed") {
+        Err(ClassPathError::Class { source, error }) => {
+            assert_eq!(
+                source,
+                ClassSource::File(Path::new(ASSETS).join("malformed.class"))
+            );
+            assert!(error.cause().is_some());
+        }
+        other => panic!(
+            "expected a class error, got {:?}",
+            other.map(|c| c.is_some())
+        ),
+    }
+
+    let missing = Path::new(ASSETS).join("missing");
+    assert!(matches!(
+        class_path.push(&missing),
+        Err(ClassPathError::Io { path, .. }) if path == missing
+    ));
+}
+
+#[test]
+fn names_outside_the_directory() {
+    let directory = temp_dir("names_outside_the_directory");
+    let root = directory.join("root");
+    copy_class("HelloWorld", &root, "HelloWorld");
+    copy_class("HelloWorld", &directory, "x");
+
+    let mut class_path = ClassPath::new();
+    class_path.push(&root).unwrap();
+    let absolute = directory.join("x");
+    for name in [
+        "../x",
+        absolute.to_str().unwrap(),
+        "",
+        "./HelloWorld",
+        "a//b",
+    ] {
+        assert_eq!(class_path.find(name), None, "{}", name);
+        assert!(class_path.class(name).unwrap().is_none(), "{}", name);
+    }
+    assert!(class_path.class("HelloWorld").unwrap().is_some());
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[cfg(not(feature = "jar"))]
+#[test]
+fn jars_need_the_jar_feature() {
+    let mut class_path = ClassPath::new();
+    let file = Path::new(ASSETS).join("HelloWorld.class");
+    assert!(matches!(
+        class_path.push(&file),
+        Err(ClassPathError::UnsupportedEntry(path)) if path == file
+    ));
+}
+
+#[cfg(feature = "jar")]
+#[test]
+fn jars_and_directories() {
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    let directory = temp_dir("jars_and_directories");
+    copy_class("Factorial", &directory, "com/example/Main");
+    let jar_path = directory.join("lib.jar");
+    let mut writer = ZipWriter::new(fs::File::create(&jar_path).unwrap());
+    for name in ["com/example/Main.class", "com/example/Util.class"] {
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .write_all(&fs::read(format!("{}/HelloWorld.class", ASSETS)).unwrap())
+            .unwrap();
+    }
+    writer.finish().unwrap();
+
+    let mut class_path = ClassPath::new();
+    class_path.push(&directory).unwrap();
+    class_path.push(&jar_path).unwrap();
+
+    let util = class_path.class("com/example/Util").unwrap().unwrap();
+    assert_eq!(util.this_class_name().unwrap(), "HelloWorld");
+    assert_eq!(
+        class_path.find_all("com/example/Main")[1],
+        ClassSource::Jar {
+            jar: jar_path.clone(),
+            entry: "com/example/Main.class".to_string(),
+        }
+    );
+    assert_eq!(
+        class_path.find_all("com/example/Main")[1].to_string(),
+        format!("{}!/com/example/Main.class", jar_path.display())
+    );
+    let shadowed = class_path.shadowed().unwrap();
+    assert_eq!(shadowed.keys().collect::<Vec<_>>(), ["com/example/Main"]);
+
+    assert!(matches!(
+        class_path.push(Path::new(ASSETS).join("HelloWorld.class")),
+        Err(ClassPathError::Jar { .. })
+    ));
+
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,33 @@
+// Each test crate includes this module and uses only some of its helpers
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use classfile_parser::attribute_info::{CodeAttribute, TypedAttribute};
+use classfile_parser::method_info::MethodInfo;
+use classfile_parser::types::ClassFile;
+
+/// Collect every `.class` file below `dir` into `found`.
+pub fn class_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            class_files(&path, found);
+        } else if path.extension().is_some_and(|e| e == "class") {
+            found.push(path);
+        }
+    }
+}
+
+/// The Code attribute of `method`, `None` for abstract and native methods.
+pub fn code(class_file: &ClassFile, method: &MethodInfo) -> Option<CodeAttribute> {
+    method
+        .typed_attributes(&class_file.const_pool)
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::Code(code) => Some(code),
+            _ => None,
+        })
+}
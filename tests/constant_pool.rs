@@ -0,0 +1,86 @@
+extern crate classfile_parser;
+
+use classfile_parser::constant_info::{ConstantInfo, ConstantPoolError};
+use classfile_parser::parse_class_from_bytes;
+
+#[test]
+fn resolve_hello_world() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let class_file = parse_class_from_bytes(class_bytes).unwrap();
+    let pool = class_file.constant_pool();
+
+    assert_eq!(class_file.this_class_name().unwrap(), "HelloWorld");
+    assert_eq!(
+        class_file.super_class_name().unwrap().as_deref(),
+        Some("java/lang/Object")
+    );
+    assert!(class_file.interface_names().unwrap().is_empty());
+
+    assert_eq!(pool.get_utf8(5).unwrap(), "<init>");
+    assert_eq!(pool.get_class_name(8).unwrap(), "java/lang/System");
+    assert_eq!(pool.get_string(13).unwrap(), "Hello World!");
+    assert_eq!(
+        pool.get_name_and_type(3).unwrap(),
+        ("<init>".into(), "()V".into())
+    );
+    assert_eq!(
+        pool.resolve_member_ref(7).unwrap(),
+        (
+            "java/lang/System".into(),
+            "out".into(),
+            "Ljava/io/PrintStream;".into()
+        )
+    );
+    assert_eq!(
+        pool.resolve_member_ref(15).unwrap(),
+        (
+            "java/io/PrintStream".into(),
+            "println".into(),
+            "(Ljava/lang/String;)V".into()
+        )
+    );
+}
+
+#[test]
+fn checked_indexes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let class_file = parse_class_from_bytes(class_bytes).unwrap();
+    let pool = class_file.constant_pool();
+
+    assert_eq!(pool.get(0).unwrap_err(), ConstantPoolError::OutOfRange(0));
+    assert_eq!(
+        pool.get_utf8(class_file.const_pool_size).unwrap_err(),
+        ConstantPoolError::OutOfRange(class_file.const_pool_size)
+    );
+    assert_eq!(
+        pool.get_utf8(2).unwrap_err(),
+        ConstantPoolError::WrongKind {
+            index: 2,
+            expected: "Utf8",
+            found: "Class"
+        }
+    );
+    assert_eq!(
+        pool.resolve_member_ref(4).unwrap_err().to_string(),
+        "constant #4 is a Utf8 constant, expected FieldRef, MethodRef or InterfaceMethodRef"
+    );
+}
+
+#[test]
+fn unusable_slots() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+    let class_file = parse_class_from_bytes(class_bytes).unwrap();
+    let pool = class_file.constant_pool();
+
+    let (index, _) = pool
+        .iter()
+        .find(|(_, c)| matches!(c, ConstantInfo::Long(_)))
+        .unwrap();
+    assert!(pool.get(index).is_ok());
+    assert_eq!(
+        pool.get(index + 1).unwrap_err(),
+        ConstantPoolError::OutOfRange(index + 1)
+    );
+    assert!(pool.iter().all(|(i, _)| i != index + 1));
+    assert_eq!(pool.entries().len(), class_file.const_pool.len());
+}
@@ -0,0 +1,130 @@
+extern crate classfile_parser;
+
+use classfile_parser::constant_info::{ConstantInfo, ConstantPoolBuilder, ConstantPoolError};
+use classfile_parser::parse_class_from_bytes;
+
+#[test]
+fn duplicates_reuse_the_existing_index() {
+    let mut builder = ConstantPoolBuilder::new();
+    let method = builder
+        .add_method_ref("java/lang/Object", "<init>", "()V")
+        .unwrap();
+    // Utf8 x3, Class, NameAndType, MethodRef
+    assert_eq!(method, 6);
+    assert_eq!(builder.add_class("java/lang/Object").unwrap(), 2);
+    assert_eq!(builder.add_utf8("<init>").unwrap(), 3);
+    assert_eq!(
+        builder
+            .add_method_ref("java/lang/Object", "<init>", "()V")
+            .unwrap(),
+        method
+    );
+    assert_eq!(builder.const_pool_size(), 7);
+
+    // A MethodRef and an InterfaceMethodRef with the same operands are different constants
+    let interface = builder
+        .add_interface_method_ref("java/lang/Object", "<init>", "()V")
+        .unwrap();
+    assert_eq!(interface, 7);
+
+    let pool = builder.pool();
+    assert_eq!(
+        pool.resolve_member_ref(method).unwrap(),
+        ("java/lang/Object".into(), "<init>".into(), "()V".into())
+    );
+}
+
+#[test]
+fn wide_constants_take_two_slots() {
+    let mut builder = ConstantPoolBuilder::new();
+    assert_eq!(builder.add_long(1).unwrap(), 1);
+    assert_eq!(builder.add_double(2.0).unwrap(), 3);
+    assert_eq!(builder.add_integer(3).unwrap(), 5);
+    assert_eq!(builder.add_long(1).unwrap(), 1);
+    assert_eq!(builder.add_double(f64::NAN).unwrap(), 6);
+    assert_eq!(builder.add_double(f64::NAN).unwrap(), 6);
+    assert_eq!(builder.const_pool_size(), 8);
+
+    let entries = builder.build();
+    assert!(matches!(entries[1], ConstantInfo::Unusable));
+    assert!(matches!(entries[3], ConstantInfo::Unusable));
+    assert!(matches!(entries[6], ConstantInfo::Unusable));
+}
+
+#[test]
+fn unusable_cannot_be_added() {
+    let mut builder = ConstantPoolBuilder::new();
+    assert_eq!(
+        builder.add(ConstantInfo::Unusable),
+        Err(ConstantPoolError::Unusable)
+    );
+    assert!(builder.build().is_empty());
+}
+
+#[test]
+fn seeded_pool_keeps_original_indexes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let mut class_file = parse_class_from_bytes(class_bytes).unwrap();
+    let original = class_file.const_pool.len();
+
+    let mut builder = ConstantPoolBuilder::from_entries(&class_file.const_pool);
+    assert_eq!(builder.add_string("Hello World!").unwrap(), 13);
+    assert_eq!(builder.add_class("java/lang/System").unwrap(), 8);
+    assert_eq!(
+        builder
+            .add_method_ref("java/io/PrintStream", "println", "(Ljava/lang/String;)V")
+            .unwrap(),
+        15
+    );
+    assert_eq!(builder.const_pool_size() as usize, original + 1);
+
+    let handle_target = builder
+        .add_method_ref("java/lang/String", "valueOf", "(I)Ljava/lang/String;")
+        .unwrap();
+    let handle = builder.add_method_handle(6, handle_target).unwrap();
+    let method_type = builder.add_method_type("(I)Ljava/lang/String;").unwrap();
+    let module = builder.add_module("java.base").unwrap();
+    let package = builder.add_package("java/lang").unwrap();
+    assert!(handle as usize > original);
+    builder.apply_to(&mut class_file);
+
+    assert_eq!(
+        class_file.const_pool_size as usize,
+        class_file.const_pool.len() + 1
+    );
+    let pool = class_file.constant_pool();
+    assert!(
+        matches!(pool.get(handle).unwrap(), ConstantInfo::MethodHandle(h) if h.reference_kind == 6)
+    );
+    assert!(matches!(
+        pool.get(method_type).unwrap(),
+        ConstantInfo::MethodType(_)
+    ));
+    assert_eq!(pool.get_module_name(module).unwrap(), "java.base");
+    assert_eq!(pool.get_package_name(package).unwrap(), "java/lang");
+
+    // The class still round trips with the extra constants
+    let bytes = class_file.to_bytes().unwrap();
+    let reparsed = parse_class_from_bytes(&bytes).unwrap();
+    assert_eq!(reparsed.const_pool.len(), class_file.const_pool.len());
+    assert_eq!(reparsed.this_class_name().unwrap(), "HelloWorld");
+}
+
+#[test]
+fn full_pool_is_an_error() {
+    let mut builder = ConstantPoolBuilder::new();
+    for value in 0..65533 {
+        builder.add_integer(value).unwrap();
+    }
+    // One slot left: too small for a Long
+    assert_eq!(builder.add_long(0), Err(ConstantPoolError::Full));
+    assert_eq!(builder.add_integer(65533).unwrap(), 65534);
+    assert_eq!(builder.add_integer(65534), Err(ConstantPoolError::Full));
+    // Existing constants can still be found
+    assert_eq!(builder.add_integer(0).unwrap(), 1);
+    assert_eq!(builder.const_pool_size(), u16::MAX);
+    assert_eq!(
+        ConstantPoolError::Full.to_string(),
+        "the constant pool is full, it can have at most 65534 entries"
+    );
+}
@@ -0,0 +1,196 @@
+extern crate classfile_parser;
+
+mod common;
+
+use classfile_parser::attribute_info::{CodeAttribute, ExceptionEntry};
+use classfile_parser::code_attribute::{Assembler, Branch, Instruction, code_parser};
+use classfile_parser::control_flow::{ControlFlowError, ControlFlowGraph, Edge, EdgeKind};
+use classfile_parser::parse_class;
+
+fn method_code(class: &str, name: &str) -> CodeAttribute {
+    let class_file = parse_class(&format!("./java-assets/compiled-classes/{}", class)).unwrap();
+    let pool = class_file.constant_pool();
+    let method = class_file
+        .methods
+        .iter()
+        .find(|method| pool.get_utf8(method.name_index).unwrap() == name)
+        .unwrap();
+    common::code(&class_file, method).unwrap()
+}
+
+fn graph(code: &CodeAttribute) -> ControlFlowGraph {
+    ControlFlowGraph::new(&code.instructions().unwrap(), &code.exception_table).unwrap()
+}
+
+fn edge(block: usize, kind: EdgeKind) -> Edge {
+    Edge { block, kind }
+}
+
+#[test]
+fn tableswitch() {
+    let cfg = graph(&method_code("Disassembly", "table"));
+    let starts: Vec<_> = cfg.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, [0, 28, 31, 34, 37]);
+
+    let entry = &cfg.blocks[0];
+    assert!(matches!(
+        entry.last_instruction(),
+        Instruction::Tableswitch { .. }
+    ));
+    assert_eq!(
+        entry.successors,
+        [
+            edge(4, EdgeKind::Switch),
+            edge(1, EdgeKind::Switch),
+            edge(2, EdgeKind::Switch),
+            edge(3, EdgeKind::Switch),
+        ]
+    );
+    for block in &cfg.blocks[1..] {
+        assert_eq!(block.predecessors, [edge(0, EdgeKind::Switch)]);
+        assert!(block.successors.is_empty());
+        assert_eq!(block.last_instruction(), &Instruction::Ireturn);
+    }
+}
+
+#[test]
+fn try_catch_finally() {
+    let code = method_code("Disassembly", "run");
+    let cfg = graph(&code);
+    let ranges: Vec<_> = cfg
+        .blocks
+        .iter()
+        .map(|block| (block.start, block.end))
+        .collect();
+    assert_eq!(
+        ranges,
+        [(0, 4), (4, 17), (17, 27), (27, 28), (28, 40), (40, 41)]
+    );
+
+    // Thread.sleep is covered by both catch clauses and the finally handler
+    let catch_types: Vec<_> = code
+        .exception_table
+        .iter()
+        .map(|entry| entry.catch_type)
+        .collect();
+    assert_eq!(
+        cfg.blocks[0].successors,
+        [
+            edge(1, EdgeKind::Fallthrough),
+            edge(2, EdgeKind::Exception(catch_types[0])),
+            edge(2, EdgeKind::Exception(catch_types[1])),
+            edge(3, EdgeKind::Exception(0)),
+        ]
+    );
+    assert_eq!(cfg.blocks[1].successors, [edge(5, EdgeKind::Branch)]);
+    // The catch block rethrows, so it only leaves through the finally handler
+    assert_eq!(cfg.blocks[2].successors, [edge(3, EdgeKind::Exception(0))]);
+    // javac lets the finally handler's astore cover itself
+    assert_eq!(
+        cfg.blocks[3].successors,
+        [
+            edge(4, EdgeKind::Fallthrough),
+            edge(3, EdgeKind::Exception(0))
+        ]
+    );
+    assert!(cfg.blocks[4].successors.is_empty());
+    assert_eq!(
+        cfg.blocks[3].predecessors,
+        [
+            edge(0, EdgeKind::Exception(0)),
+            edge(2, EdgeKind::Exception(0)),
+            edge(3, EdgeKind::Exception(0)),
+        ]
+    );
+
+    assert_eq!(cfg.block_at(0), Some(0));
+    assert_eq!(cfg.block_at(20), Some(2));
+    assert_eq!(cfg.block_at(40), Some(5));
+    assert_eq!(cfg.block_at(41), None);
+}
+
+#[test]
+fn loops_and_subroutines() {
+    // i = 10; do { i--; jsr sub } while (i != 0); return; sub: astore_1; ret 1
+    let mut assembler = Assembler::new();
+    let (head, sub) = (assembler.new_label(), assembler.new_label());
+    assembler.emit(Instruction::Bipush(10));
+    assembler.emit(Instruction::Istore0);
+    assembler.place(head);
+    assembler.emit(Instruction::Iinc {
+        index: 0,
+        value: -1,
+    });
+    assembler.branch(Branch::Jsr, sub);
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifne, head);
+    assembler.emit(Instruction::Return);
+    assembler.place(sub);
+    assembler.emit(Instruction::Astore1);
+    assembler.emit(Instruction::Ret(1));
+    let code = assembler.assemble().unwrap().code;
+    let (_, instructions) = code_parser(&code).unwrap();
+
+    let cfg = ControlFlowGraph::new(&instructions, &[]).unwrap();
+    let starts: Vec<_> = cfg.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, [0, 3, 9, 13, 14]);
+    assert_eq!(cfg.blocks[0].successors, [edge(1, EdgeKind::Fallthrough)]);
+    assert_eq!(
+        cfg.blocks[1].successors,
+        [
+            edge(4, EdgeKind::Subroutine),
+            edge(2, EdgeKind::Fallthrough)
+        ]
+    );
+    assert_eq!(
+        cfg.blocks[2].successors,
+        [edge(1, EdgeKind::Branch), edge(3, EdgeKind::Fallthrough)]
+    );
+    assert_eq!(
+        cfg.blocks[1].predecessors,
+        [edge(0, EdgeKind::Fallthrough), edge(2, EdgeKind::Branch)]
+    );
+    assert!(cfg.blocks[4].successors.is_empty());
+}
+
+#[test]
+fn invalid_code() {
+    assert!(ControlFlowGraph::new(&[], &[]).unwrap().blocks.is_empty());
+
+    // goto into the middle of itself
+    let instructions = [(0, Instruction::Goto(1)), (3, Instruction::Return)];
+    assert_eq!(
+        ControlFlowGraph::new(&instructions, &[]).unwrap_err(),
+        ControlFlowError::InvalidBranchTarget {
+            address: 0,
+            target: 1
+        }
+    );
+
+    let instructions = [(0, Instruction::Nop), (1, Instruction::Return)];
+    let entry = |start_pc, end_pc, handler_pc| ExceptionEntry {
+        start_pc,
+        end_pc,
+        handler_pc,
+        catch_type: 0,
+    };
+    assert!(ControlFlowGraph::new(&instructions, &[entry(0, 2, 1)]).is_ok());
+    for bad in [entry(0, 3, 1), entry(1, 1, 1), entry(0, 1, 2)] {
+        assert_eq!(
+            ControlFlowGraph::new(&instructions, &[entry(0, 1, 1), bad]).unwrap_err(),
+            ControlFlowError::InvalidExceptionEntry(1)
+        );
+    }
+}
+
+#[test]
+fn dot_export() {
+    let cfg = graph(&method_code("Disassembly", "run"));
+    let dot = cfg.to_dot();
+    assert!(dot.starts_with("digraph cfg {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("    b1 [label=\"4: aload_0\\l5: dup\\l"));
+    assert!(dot.contains("    b0 -> b1;\n"));
+    assert!(dot.contains("    b1 -> b5 [style=bold];\n"));
+    assert!(dot.contains("    b0 -> b3 [style=dashed, label=\"any\"];\n"));
+}
@@ -0,0 +1,66 @@
+extern crate classfile_parser;
+
+use classfile_parser::dependencies::DependencyKind::{Api, Implementation};
+use classfile_parser::parse_class;
+
+#[test]
+fn generic_signatures_are_api() {
+    let class_file = parse_class("./java-assets/compiled-classes/Generics").unwrap();
+    let classes = class_file.referenced_classes().unwrap();
+
+    assert_eq!(classes["java/util/ArrayList"], Api);
+    assert_eq!(classes["java/util/Map$Entry"], Api);
+    assert_eq!(classes["java/lang/Comparable"], Api);
+    assert_eq!(classes["java/util/List"], Api);
+    assert_eq!(classes["java/lang/Runnable"], Api);
+    assert_eq!(classes["java/lang/Exception"], Api);
+    assert_eq!(classes["java/io/IOException"], Api);
+    // Only the private `index` field mentions Map itself
+    assert_eq!(classes["java/util/Map"], Implementation);
+    assert!(!classes.contains_key("Generics"));
+}
+
+#[test]
+fn bytecode_references_are_implementation() {
+    let class_file = parse_class("./java-assets/compiled-classes/Dispatch").unwrap();
+    let classes = class_file.referenced_classes().unwrap();
+
+    assert_eq!(classes["Dispatch$Greeter"], Api);
+    assert_eq!(classes["Dispatch$Base"], Api);
+    assert_eq!(classes["java/lang/String"], Api);
+    assert_eq!(classes["java/lang/Object"], Api);
+    assert_eq!(classes["Dispatch$Loud"], Implementation);
+    assert_eq!(classes["java/lang/System"], Implementation);
+    assert_eq!(classes["java/io/PrintStream"], Implementation);
+}
+
+#[test]
+fn local_variable_types_are_implementation() {
+    let class_file = parse_class("./java-assets/compiled-classes/LocalVariableTable").unwrap();
+    let classes = class_file.referenced_classes().unwrap();
+
+    assert_eq!(classes["java/util/HashMap"], Implementation);
+    assert_eq!(classes["java/lang/Integer"], Implementation);
+    assert_eq!(classes["java/lang/Object"], Api);
+}
+
+#[test]
+fn annotations_are_api() {
+    let class_file = parse_class("./java-assets/compiled-classes/Annotations").unwrap();
+    let classes = class_file.referenced_classes().unwrap();
+
+    assert_eq!(classes["Annotations$VisibleAtRuntime"], Api);
+    assert_eq!(classes["Annotations$InvisibleAtRuntime"], Api);
+    assert_eq!(classes["Annotations$ParamVisibleAtRuntime"], Api);
+    assert_eq!(classes["Annotations$ParamInvisibleAtRuntime"], Api);
+    assert_eq!(classes["Annotations$TypeVisibleAtRuntime"], Api);
+    assert_eq!(classes["Annotations$TypeInvisibleAtRuntime"], Api);
+}
+
+#[test]
+fn arrays_are_reduced_to_their_element_class() {
+    let class_file = parse_class("./java-assets/compiled-classes/Dispatch").unwrap();
+    let classes = class_file.referenced_classes().unwrap();
+
+    assert!(classes.keys().all(|name| !name.starts_with('[')));
+}
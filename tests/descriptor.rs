@@ -0,0 +1,113 @@
+extern crate classfile_parser;
+
+use classfile_parser::descriptor::{
+    BaseType, DescriptorError, FieldType, MethodDescriptor, ReturnType, field_type_parser,
+};
+use classfile_parser::parse_class;
+
+#[test]
+fn field_types() {
+    assert_eq!("I".parse(), Ok(FieldType::Base(BaseType::Int)));
+    assert_eq!(
+        "Ljava/lang/String;".parse(),
+        Ok(FieldType::Object("java/lang/String".to_string()))
+    );
+    let array: FieldType = "[[J".parse().unwrap();
+    assert_eq!(
+        array,
+        FieldType::Array {
+            dimensions: 2,
+            element: Box::new(FieldType::Base(BaseType::Long)),
+        }
+    );
+    assert_eq!(array.to_java(), "long[][]");
+    assert_eq!(array.slot_size(), 1);
+    assert_eq!(FieldType::Base(BaseType::Double).slot_size(), 2);
+    assert_eq!(
+        "[Ljava/util/Map$Entry;"
+            .parse::<FieldType>()
+            .unwrap()
+            .to_java(),
+        "java.util.Map$Entry[]"
+    );
+
+    assert_eq!(
+        field_type_parser("ZI"),
+        Ok(("I", FieldType::Base(BaseType::Boolean)))
+    );
+}
+
+#[test]
+fn method_descriptors() {
+    let descriptor: MethodDescriptor = "(JD[ILjava/lang/Object;)[Ljava/lang/String;"
+        .parse()
+        .unwrap();
+    assert_eq!(descriptor.params.len(), 4);
+    assert_eq!(descriptor.param_slots(), 6);
+    assert_eq!(descriptor.return_type.slot_size(), 1);
+    assert_eq!(
+        descriptor.to_java("m"),
+        "java.lang.String[] m(long, double, int[], java.lang.Object)"
+    );
+
+    let no_args: MethodDescriptor = "()V".parse().unwrap();
+    assert!(no_args.params.is_empty());
+    assert_eq!(no_args.return_type, ReturnType::Void);
+    assert_eq!(no_args.to_java("<init>"), "void <init>()");
+}
+
+#[test]
+fn invalid_descriptors() {
+    for (descriptor, offset) in [
+        ("", 0),
+        ("V", 0),
+        ("Q", 0),
+        ("Ljava/lang/String", 17),
+        ("L;", 1),
+        ("II", 1),
+        ("[", 1),
+    ] {
+        assert_eq!(
+            descriptor.parse::<FieldType>(),
+            Err(DescriptorError {
+                descriptor: descriptor.to_string(),
+                offset
+            }),
+            "{}",
+            descriptor
+        );
+    }
+    for (descriptor, offset) in [("(I", 2), ("()", 2), ("(V)V", 1), ("()VV", 3)] {
+        assert_eq!(
+            descriptor.parse::<MethodDescriptor>().unwrap_err().offset,
+            offset,
+            "{}",
+            descriptor
+        );
+    }
+    assert_eq!("[".repeat(256).parse::<FieldType>().unwrap_err().offset, 0);
+}
+
+#[test]
+fn compiled_class_descriptors() {
+    for class_name in [
+        "BasicClass",
+        "Instructions",
+        "LocalVariableTable",
+        "Annotations",
+    ] {
+        let class_file =
+            parse_class(&format!("./java-assets/compiled-classes/{}", class_name)).unwrap();
+        let pool = class_file.constant_pool();
+        for method in &class_file.methods {
+            let raw = pool.get_utf8(method.descriptor_index).unwrap();
+            let descriptor: MethodDescriptor = raw.parse().unwrap();
+            assert_eq!(descriptor.to_string(), raw);
+        }
+        for field in &class_file.fields {
+            let raw = pool.get_utf8(field.descriptor_index).unwrap();
+            let field_type: FieldType = raw.parse().unwrap();
+            assert_eq!(field_type.to_string(), raw);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+extern crate classfile_parser;
+
+use std::fs;
+
+use classfile_parser::disassembler::disassemble;
+use classfile_parser::parse_class;
+
+/// Compare against `javap -v -p` output stored in `java-assets/javap`, which has the first
+/// three lines (file path, modification time and checksum) removed.
+fn assert_matches_javap(name: &str) {
+    let class_file = parse_class(&format!("./java-assets/compiled-classes/{}", name)).unwrap();
+    let expected = fs::read_to_string(format!("./java-assets/javap/{}.txt", name)).unwrap();
+    let actual = disassemble(&class_file);
+    for (line, (expected, actual)) in expected.lines().zip(actual.lines()).enumerate() {
+        assert_eq!(actual, expected, "{}.class line {}", name, line + 1);
+    }
+    assert_eq!(actual, expected, "{}.class", name);
+}
+
+#[test]
+fn matches_javap() {
+    for name in [
+        "HelloWorld",
+        "BasicClass",
+        "Instructions",
+        "Factorial",
+        "LocalVariableTable",
+        "Generics",
+        "BootstrapMethods",
+        "DeprecatedAnnotation",
+        "module-info",
+        "app-module-info",
+    ] {
+        assert_matches_javap(name);
+    }
+}
+
+#[test]
+fn members_and_annotations() {
+    for name in ["Disassembly", "Defaults", "Greeter", "Point", "Label"] {
+        assert_matches_javap(name);
+    }
+}
+
+#[test]
+fn nests() {
+    for name in [
+        "Nestmates",
+        "Nestmates$Shape",
+        "Nestmates$Circle",
+        "Nestmates$Square",
+        "Nestmates$Inner",
+    ] {
+        assert_matches_javap(name);
+    }
+}
+
+#[test]
+fn inner_classes_and_annotations() {
+    for name in [
+        "InnerClasses",
+        "InnerClasses$1",
+        "InnerClasses$2",
+        "InnerClasses$1EnglishGreeting",
+        "InnerClasses$HelloWorld",
+        "Annotations",
+        "Annotations$VisibleAtRuntime",
+        "Annotations$InvisibleAtRuntime",
+        "Annotations$ParamVisibleAtRuntime",
+        "Annotations$ParamInvisibleAtRuntime",
+        "Annotations$TypeVisibleAtRuntime",
+        "Annotations$TypeInvisibleAtRuntime",
+    ] {
+        assert_matches_javap(name);
+    }
+}
+
+#[test]
+fn display_disassembles() {
+    let class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let text = class_file.to_string();
+    assert_eq!(text, disassemble(&class_file));
+    assert!(text.contains("  public static int factorial(int);\n"));
+    assert!(text.contains("StackMapTable: number_of_entries = "));
+}
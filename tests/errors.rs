@@ -0,0 +1,121 @@
+extern crate classfile_parser;
+
+use classfile_parser::attribute_info::{AttributeInfo, CodeAttribute, typed_attribute_parser};
+use classfile_parser::{
+    ClassParseError, ParseContext, ParseErrorCause, parse_class, parse_class_from_bytes,
+};
+
+#[test]
+fn bad_magic() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/malformed.class");
+    let error = parse_class_from_bytes(class_bytes).unwrap_err();
+    assert_eq!(error.offset(), Some(0));
+    assert_eq!(error.context(), &[ParseContext::Header]);
+    assert_eq!(error.cause(), Some(&ParseErrorCause::BadMagic));
+}
+
+#[test]
+fn unknown_constant_tag() {
+    let mut class_bytes =
+        include_bytes!("../java-assets/compiled-classes/BasicClass.class").to_vec();
+    // The tag of the first constant follows the magic, versions and constant pool count
+    class_bytes[10] = 2;
+    let error = parse_class_from_bytes(&class_bytes).unwrap_err();
+    assert_eq!(error.offset(), Some(10));
+    assert_eq!(error.context(), &[ParseContext::Constant(1)]);
+    assert_eq!(error.cause(), Some(&ParseErrorCause::UnknownConstantTag(2)));
+    assert_eq!(
+        error.to_string(),
+        "unknown constant pool tag 2 at offset 10 in constant #1"
+    );
+}
+
+#[test]
+fn truncated_method_attribute() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let class_file = parse_class_from_bytes(class_bytes).unwrap();
+
+    // Cut the file in the middle of the last method's Code attribute
+    let cut = class_bytes.len()
+        - class_file
+            .attributes
+            .iter()
+            .map(|a| 6 + a.info.len())
+            .sum::<usize>()
+        - 2 // attributes_count
+        - 5;
+    let error = parse_class_from_bytes(&class_bytes[..cut]).unwrap_err();
+    assert_eq!(error.cause(), Some(&ParseErrorCause::Truncated));
+    assert_eq!(
+        error.context(),
+        &[
+            ParseContext::Method(class_file.methods_count - 1),
+            ParseContext::Attribute("Code".to_string())
+        ]
+    );
+    assert!(error.offset().unwrap() <= cut);
+}
+
+#[test]
+fn bad_opcode() {
+    let code = CodeAttribute {
+        max_stack: 1,
+        max_locals: 1,
+        code_length: 3,
+        code: vec![0x2a, 0xff, 0xb1], // aload_0, <invalid>, return
+        exception_table_length: 0,
+        exception_table: vec![],
+        attributes_count: 0,
+        attributes: vec![],
+    };
+    let error = code.instructions().unwrap_err();
+    assert_eq!(error.offset(), Some(1));
+    assert_eq!(error.context(), &[ParseContext::Code]);
+    assert_eq!(error.cause(), Some(&ParseErrorCause::BadOpcode(0xff)));
+
+    let truncated = CodeAttribute {
+        code: vec![0x2a, 0x11, 0x00], // aload_0, sipush <missing byte>
+        ..code
+    };
+    let error = truncated.instructions().unwrap_err();
+    assert_eq!(error.offset(), Some(1));
+    assert_eq!(error.cause(), Some(&ParseErrorCause::Truncated));
+}
+
+#[test]
+fn bad_attribute_name() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+    let class_file = parse_class_from_bytes(class_bytes).unwrap();
+    let attribute = AttributeInfo {
+        attribute_name_index: class_file.this_class,
+        attribute_length: 0,
+        info: vec![],
+    };
+    let error = typed_attribute_parser(&attribute, &class_file.const_pool).unwrap_err();
+    assert_eq!(
+        error.cause(),
+        Some(&ParseErrorCause::BadAttributeName(class_file.this_class))
+    );
+}
+
+#[test]
+fn missing_file() {
+    match parse_class("./java-assets/compiled-classes/DoesNotExist") {
+        Err(ClassParseError::Io(e)) => assert!(e.to_string().contains("DoesNotExist.class")),
+        other => panic!("Expected an I/O error, got {:?}", other),
+    }
+}
+
+#[test]
+fn converts_into_string() {
+    fn parse_malformed() -> Result<(), String> {
+        parse_class("./java-assets/compiled-classes/malformed")?;
+        Ok(())
+    }
+    assert_eq!(
+        parse_malformed().unwrap_err(),
+        parse_class("./java-assets/compiled-classes/malformed")
+            .unwrap_err()
+            .to_string()
+    );
+}
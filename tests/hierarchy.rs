@@ -0,0 +1,196 @@
+extern crate classfile_parser;
+
+use std::collections::BTreeSet;
+use std::fs;
+
+use classfile_parser::hierarchy::{ClassHierarchy, HierarchyClass};
+use classfile_parser::verifier::TypeHierarchy;
+use classfile_parser::{ClassAccessFlags, parse_class_from_bytes, parse_header};
+
+fn class(name: &str, super_class: &str, interfaces: &[&str]) -> HierarchyClass {
+    HierarchyClass {
+        name: name.to_string(),
+        super_class: Some(super_class.to_string()),
+        interfaces: interfaces.iter().map(|name| name.to_string()).collect(),
+        access_flags: ClassAccessFlags::PUBLIC,
+    }
+}
+
+fn interface(name: &str, interfaces: &[&str]) -> HierarchyClass {
+    HierarchyClass {
+        access_flags: ClassAccessFlags::PUBLIC
+            | ClassAccessFlags::INTERFACE
+            | ClassAccessFlags::ABSTRACT,
+        ..class(name, "java/lang/Object", interfaces)
+    }
+}
+
+/// `A <- B <- C`, `A <- D`, with `B` implementing `I` and `I` extending `J`.
+fn sample() -> ClassHierarchy {
+    let mut hierarchy = ClassHierarchy::new();
+    for class in [
+        class("A", "java/lang/Object", &[]),
+        class("B", "A", &["I"]),
+        class("C", "B", &[]),
+        class("D", "A", &["java/lang/Runnable"]),
+        interface("I", &["J"]),
+        interface("J", &[]),
+        interface("K", &["J"]),
+    ] {
+        assert!(hierarchy.insert(class));
+    }
+    hierarchy
+}
+
+#[test]
+fn queries() {
+    let hierarchy = sample();
+    assert_eq!(hierarchy.len(), 7);
+    assert_eq!(hierarchy.superclasses("C"), ["B", "A", "java/lang/Object"]);
+    assert_eq!(
+        hierarchy.supertypes("C"),
+        ["B", "A", "I", "java/lang/Object", "J"]
+    );
+    assert_eq!(hierarchy.subtypes("A"), ["B", "D", "C"]);
+    assert_eq!(
+        hierarchy.direct_subtypes("J").collect::<Vec<_>>(),
+        ["I", "K"]
+    );
+    assert_eq!(hierarchy.implementors("J"), ["B", "C"]);
+    assert!(hierarchy.implementors("K").is_empty());
+    assert_eq!(
+        hierarchy.missing(),
+        BTreeSet::from(["java/lang/Object", "java/lang/Runnable"])
+    );
+}
+
+#[test]
+fn subtypes() {
+    let hierarchy = sample();
+    assert!(hierarchy.is_subtype("C", "A"));
+    assert!(hierarchy.is_subtype("C", "J"));
+    assert!(hierarchy.is_subtype("D", "java/lang/Runnable"));
+    assert!(hierarchy.is_subtype("K", "java/lang/Object"));
+    assert!(hierarchy.is_subtype("Unknown", "java/lang/Object"));
+    assert!(!hierarchy.is_subtype("A", "C"));
+    assert!(!hierarchy.is_subtype("D", "I"));
+
+    assert!(hierarchy.is_subtype("[LC;", "[LJ;"));
+    assert!(hierarchy.is_subtype("[[LC;", "[[LA;"));
+    assert!(hierarchy.is_subtype("[[LC;", "[Ljava/lang/Object;"));
+    assert!(hierarchy.is_subtype("[I", "java/lang/Cloneable"));
+    assert!(hierarchy.is_subtype("[I", "[I"));
+    assert!(!hierarchy.is_subtype("[I", "[J"));
+    assert!(!hierarchy.is_subtype("[I", "[Ljava/lang/Object;"));
+    assert!(!hierarchy.is_subtype("[LA;", "[LC;"));
+    assert!(!hierarchy.is_subtype("A", "[LA;"));
+}
+
+#[test]
+fn least_common_superclass() {
+    let hierarchy = sample();
+    assert_eq!(hierarchy.least_common_superclass("C", "D"), "A");
+    assert_eq!(hierarchy.least_common_superclass("C", "B"), "B");
+    assert_eq!(hierarchy.least_common_superclass("B", "C"), "B");
+    assert_eq!(
+        hierarchy.least_common_superclass("C", "I"),
+        "java/lang/Object"
+    );
+    assert_eq!(
+        hierarchy.least_common_superclass("C", "Unknown"),
+        "java/lang/Object"
+    );
+    assert_eq!(hierarchy.common_superclass("D", "B"), "A");
+}
+
+#[test]
+fn least_common_superclass_of_arrays() {
+    let hierarchy = sample();
+    assert_eq!(hierarchy.least_common_superclass("[LC;", "[LD;"), "[LA;");
+    assert_eq!(hierarchy.least_common_superclass("[[LC;", "[[LB;"), "[[LB;");
+    assert_eq!(
+        hierarchy.least_common_superclass("[LC;", "[LI;"),
+        "[Ljava/lang/Object;"
+    );
+    assert_eq!(hierarchy.least_common_superclass("[I", "[I"), "[I");
+    assert_eq!(
+        hierarchy.least_common_superclass("[[I", "[LC;"),
+        "[Ljava/lang/Object;"
+    );
+    assert_eq!(
+        hierarchy.least_common_superclass("[I", "[J"),
+        "java/lang/Object"
+    );
+    assert_eq!(
+        hierarchy.least_common_superclass("[I", "[LC;"),
+        "java/lang/Object"
+    );
+    assert_eq!(
+        hierarchy.least_common_superclass("[LC;", "C"),
+        "java/lang/Object"
+    );
+}
+
+#[test]
+fn first_definition_wins() {
+    let mut hierarchy = sample();
+    assert!(!hierarchy.insert(class("C", "D", &[])));
+    assert_eq!(
+        hierarchy.get("C").unwrap().super_class.as_deref(),
+        Some("B")
+    );
+    assert_eq!(hierarchy.direct_subtypes("D").count(), 0);
+}
+
+#[test]
+fn cycles() {
+    let mut hierarchy = sample();
+    assert!(hierarchy.cycles().is_empty());
+
+    hierarchy.insert(class("X", "Y", &[]));
+    hierarchy.insert(class("Y", "Z", &[]));
+    hierarchy.insert(class("Z", "X", &[]));
+    hierarchy.insert(interface("P", &["Q"]));
+    hierarchy.insert(interface("Q", &["P"]));
+    hierarchy.insert(class("Self", "Self", &[]));
+    assert_eq!(
+        hierarchy.cycles(),
+        [vec!["P", "Q"], vec!["Self"], vec!["X", "Y", "Z"]]
+    );
+
+    // Queries still terminate
+    assert_eq!(hierarchy.superclasses("Y"), ["Z", "X"]);
+    assert_eq!(hierarchy.supertypes("P"), ["java/lang/Object", "Q"]);
+    assert!(hierarchy.superclasses("Self").is_empty());
+    assert!(!hierarchy.is_subtype("X", "A"));
+    assert_eq!(hierarchy.least_common_superclass("X", "Z"), "Z");
+}
+
+#[test]
+fn compiled_classes() {
+    let mut classes = Vec::new();
+    let mut headers = ClassHierarchy::new();
+    for entry in fs::read_dir("java-assets/compiled-classes").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "class") || path.ends_with("malformed.class") {
+            continue;
+        }
+        let bytes = fs::read(path).unwrap();
+        headers.add_header(&parse_header(&bytes).unwrap()).unwrap();
+        classes.push(parse_class_from_bytes(&bytes).unwrap());
+    }
+    let hierarchy = ClassHierarchy::from_classes(&classes).unwrap();
+    assert_eq!(
+        hierarchy.class_names().collect::<Vec<_>>(),
+        headers.class_names().collect::<Vec<_>>()
+    );
+
+    assert!(hierarchy.is_subtype("InnerClasses$1EnglishGreeting", "InnerClasses$HelloWorld"));
+    assert_eq!(
+        hierarchy.implementors("Nestmates$Shape"),
+        ["Nestmates$Circle", "Nestmates$Square"]
+    );
+    assert!(hierarchy.get("Nestmates$Shape").unwrap().is_interface());
+    assert!(hierarchy.missing().contains("java/lang/Object"));
+    assert!(hierarchy.cycles().is_empty());
+}
@@ -0,0 +1,235 @@
+#![cfg(feature = "jar")]
+
+extern crate classfile_parser;
+
+use std::io::{Cursor, Write};
+
+use classfile_parser::jar::{JarError, JarFile, Manifest};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const HELLO_WORLD: &[u8] = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+const FACTORIAL: &[u8] = include_bytes!("../java-assets/compiled-classes/Factorial.class");
+
+/// Build a JAR in memory from `(name, contents)` entries.
+fn jar(entries: &[(&str, &[u8])]) -> JarFile<Cursor<Vec<u8>>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        if name.ends_with('/') {
+            writer
+                .add_directory(*name, SimpleFileOptions::default())
+                .unwrap();
+        } else {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+    }
+    JarFile::new(writer.finish().unwrap()).unwrap()
+}
+
+/// HelloWorld with its minor version set, to tell overlays apart.
+fn hello_world_variant(minor_version: u8) -> Vec<u8> {
+    let mut bytes = HELLO_WORLD.to_vec();
+    bytes[5] = minor_version;
+    bytes
+}
+
+#[test]
+fn parses_classes() {
+    let mut jar = jar(&[
+        ("META-INF/", b""),
+        ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\r\n"),
+        ("com/", b""),
+        ("com/example/HelloWorld.class", HELLO_WORLD),
+        ("com/example/readme.txt", b"not a class"),
+        ("Factorial.class", FACTORIAL),
+    ]);
+    assert_eq!(jar.entry_names().count(), 6);
+    assert_eq!(
+        jar.class_names().collect::<Vec<_>>(),
+        ["Factorial", "com/example/HelloWorld"]
+    );
+
+    let classes = jar.classes().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(classes.len(), 2);
+    assert_eq!(classes[0].0, "Factorial");
+    assert_eq!(classes[0].1.this_class_name().unwrap(), "Factorial");
+    assert_eq!(classes[1].1.this_class_name().unwrap(), "HelloWorld");
+
+    assert!(jar.class("Factorial").unwrap().is_some());
+    assert!(jar.class("Missing").unwrap().is_none());
+    assert_eq!(
+        jar.read_entry("com/example/readme.txt").unwrap(),
+        b"not a class"
+    );
+    assert!(matches!(
+        jar.read_entry("missing.txt"),
+        Err(JarError::Zip(_))
+    ));
+}
+
+#[test]
+fn manifest() {
+    let jar = jar(&[(
+        "META-INF/MANIFEST.MF",
+        b"Manifest-Version: 1.0\r\nMain-Class: com.example.HelloWo\r\n rld\r\nCreated-By: 17 (Test)\r\n\r\nName: com/example/HelloWorld.class\r\nSHA-256-Digest: abc=\r\n\r\n",
+    )]);
+    let manifest = jar.manifest().unwrap();
+    assert_eq!(manifest.main_class(), Some("com.example.HelloWorld"));
+    assert_eq!(manifest.main_attribute("created-by"), Some("17 (Test)"));
+    assert_eq!(
+        manifest.entry_attribute("com/example/HelloWorld.class", "SHA-256-Digest"),
+        Some("abc=")
+    );
+    assert_eq!(manifest.entries.len(), 1);
+    assert!(!jar.is_multi_release());
+
+    let jar = self::jar(&[("HelloWorld.class", HELLO_WORLD)]);
+    assert_eq!(jar.manifest(), None);
+}
+
+#[test]
+fn manifest_without_trailing_newline() {
+    let manifest = Manifest::parse("Manifest-Version: 1.0\nMulti-Release: true");
+    assert!(manifest.is_multi_release());
+    assert_eq!(manifest.main_attributes.len(), 2);
+    assert!(manifest.entries.is_empty());
+}
+
+#[test]
+fn multi_release_overlays() {
+    let v9 = hello_world_variant(9);
+    let v11 = hello_world_variant(11);
+    let v21 = hello_world_variant(21);
+    let entries: &[(&str, &[u8])] = &[
+        (
+            "META-INF/MANIFEST.MF",
+            b"Manifest-Version: 1.0\r\nMulti-Release: true\r\n",
+        ),
+        ("HelloWorld.class", HELLO_WORLD),
+        ("Factorial.class", FACTORIAL),
+        ("META-INF/versions/9/HelloWorld.class", &v9),
+        ("META-INF/versions/11/HelloWorld.class", &v11),
+        ("META-INF/versions/21/HelloWorld.class", &v21),
+        ("META-INF/versions/11/Versioned.class", HELLO_WORLD),
+        ("META-INF/versions/8/Ignored.class", HELLO_WORLD),
+    ];
+
+    let minor_version = |release: Option<u16>| {
+        let mut jar = jar(entries);
+        if let Some(release) = release {
+            jar = jar.with_release(release);
+        }
+        assert!(jar.is_multi_release());
+        jar.class("HelloWorld").unwrap().unwrap().minor_version
+    };
+    assert_eq!(minor_version(None), 0);
+    assert_eq!(minor_version(Some(8)), 0);
+    assert_eq!(minor_version(Some(10)), 9);
+    assert_eq!(minor_version(Some(17)), 11);
+    assert_eq!(minor_version(Some(25)), 21);
+
+    let base = jar(entries);
+    assert_eq!(
+        base.class_names().collect::<Vec<_>>(),
+        ["Factorial", "HelloWorld"]
+    );
+    let jar = jar(entries).with_release(17);
+    assert_eq!(jar.release(), Some(17));
+    assert_eq!(
+        jar.class_names().collect::<Vec<_>>(),
+        ["Factorial", "HelloWorld", "Versioned"]
+    );
+    assert_eq!(
+        jar.class_entry_name("HelloWorld"),
+        Some("META-INF/versions/11/HelloWorld.class")
+    );
+    assert_eq!(jar.class_entry_name("Factorial"), Some("Factorial.class"));
+}
+
+#[test]
+fn overlays_need_multi_release_manifest() {
+    let v11 = hello_world_variant(11);
+    let mut jar = jar(&[
+        ("HelloWorld.class", HELLO_WORLD),
+        ("META-INF/versions/11/HelloWorld.class", &v11),
+    ])
+    .with_release(17);
+    assert_eq!(jar.class("HelloWorld").unwrap().unwrap().minor_version, 0);
+}
+
+#[test]
+fn class_errors_name_the_entry() {
+    let mut jar = jar(&[
+        ("Broken.class", &HELLO_WORLD[..20]),
+        ("HelloWorld.class", HELLO_WORLD),
+    ]);
+    let results = jar.classes().collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    match &results[0] {
+        Err(JarError::Class { entry, error }) => {
+            assert_eq!(entry, "Broken.class");
+            assert!(error.offset().is_some());
+        }
+        other => panic!(
+            "expected a class error, got {:?}",
+            other.as_ref().map(|(n, _)| n)
+        ),
+    }
+    assert!(results[1].is_ok());
+}
+
+#[test]
+fn open_from_file() {
+    let path = std::env::temp_dir().join(format!("classfile-parser-{}.jar", std::process::id()));
+    let mut writer = ZipWriter::new(std::fs::File::create(&path).unwrap());
+    writer
+        .start_file("HelloWorld.class", SimpleFileOptions::default())
+        .unwrap();
+    writer.write_all(HELLO_WORLD).unwrap();
+    writer.finish().unwrap();
+
+    let mut jar = JarFile::open(&path).unwrap();
+    let class_file = jar.class("HelloWorld").unwrap().unwrap();
+    assert_eq!(class_file.methods.len(), 2);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(JarFile::open(&path), Err(JarError::Io(_))));
+    assert!(matches!(
+        JarFile::new(Cursor::new(b"not a zip".to_vec())),
+        Err(JarError::Zip(_))
+    ));
+}
+
+#[test]
+fn inflated_entry_size_is_an_error() {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+    writer.start_file("HelloWorld.class", options).unwrap();
+    writer.write_all(HELLO_WORLD).unwrap();
+    let mut bytes = writer.finish().unwrap().into_inner();
+
+    // Claim a zip64 uncompressed size near u64::MAX in the central directory.
+    let central_directory = bytes
+        .windows(4)
+        .position(|window| window == b"PK\x01\x02")
+        .unwrap();
+    let zip64_size = central_directory
+        + bytes[central_directory..]
+            .windows(4)
+            .position(|window| window == [0x01, 0x00, 0x10, 0x00])
+            .unwrap()
+        + 4;
+    bytes[zip64_size..zip64_size + 8].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+
+    let mut jar = JarFile::new(Cursor::new(bytes)).unwrap();
+    assert!(matches!(
+        jar.read_entry("HelloWorld.class"),
+        Err(JarError::Io(_))
+    ));
+    assert!(matches!(jar.class("HelloWorld"), Err(JarError::Io(_))));
+}
@@ -0,0 +1,240 @@
+extern crate classfile_parser;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use classfile_parser::attribute_info::TypedAttribute;
+use classfile_parser::constant_info::ConstantInfo;
+use classfile_parser::remapper::{Relocator, Remapper, remap_class, remap_class_bytes};
+use classfile_parser::{parse_class, parse_class_from_bytes};
+
+/// Renames classes by exact name and methods by owner and name.
+struct Renames {
+    classes: Vec<(&'static str, &'static str)>,
+    methods: Vec<(&'static str, &'static str, &'static str)>,
+}
+
+impl Remapper for Renames {
+    fn map_class(&self, name: &str) -> Option<String> {
+        self.classes
+            .iter()
+            .find(|(from, _)| *from == name)
+            .map(|(_, to)| to.to_string())
+    }
+
+    fn map_method_name(&self, owner: &str, name: &str, _descriptor: &str) -> Option<String> {
+        self.methods
+            .iter()
+            .find(|(o, n, _)| *o == owner && *n == name)
+            .map(|(_, _, to)| to.to_string())
+    }
+}
+
+#[test]
+fn renaming_nothing_keeps_every_byte() {
+    let mut paths = Vec::new();
+    common::class_files(Path::new("java-assets/compiled-classes"), &mut paths);
+    let relocator = Relocator::new().relocate("org/example", "shaded/org/example");
+
+    for path in paths {
+        // malformed.class is deliberately not a class file
+        if path
+            .file_name()
+            .is_some_and(|name| name == "malformed.class")
+        {
+            continue;
+        }
+        let bytes = fs::read(&path).unwrap();
+        let remapped = remap_class_bytes(&bytes, &relocator).unwrap();
+        assert!(remapped == bytes, "{} changed", path.display());
+    }
+}
+
+#[test]
+fn relocate_generic_signatures() {
+    let class_file = parse_class("./java-assets/compiled-classes/Generics").unwrap();
+    let relocator = Relocator::new().relocate("java/util", "shaded/java/util");
+    let remapped = remap_class(&class_file, &relocator).unwrap();
+
+    let bytes = remapped.to_bytes().unwrap();
+    let remapped = parse_class_from_bytes(&bytes).unwrap();
+    let pool = remapped.constant_pool();
+
+    let signature = remapped
+        .typed_attributes()
+        .unwrap()
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::Signature(signature) => Some(signature.signature_index),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        pool.get_utf8(signature).unwrap(),
+        "<K::Ljava/lang/Comparable<TK;>;V:Ljava/lang/Object;>\
+         Lshaded/java/util/ArrayList<Lshaded/java/util/Map$Entry<TK;TV;>;>;\
+         Ljava/lang/Comparable<LGenerics<TK;*>;>;"
+    );
+    assert_eq!(
+        pool.get_utf8(remapped.fields[0].descriptor_index).unwrap(),
+        "Lshaded/java/util/Map;"
+    );
+
+    let classes = remapped.referenced_classes().unwrap();
+    assert!(classes.contains_key("shaded/java/util/List"));
+    assert!(classes.keys().all(|name| !name.starts_with("java/util/")));
+}
+
+#[test]
+fn rename_methods_by_owner() {
+    let renames = Renames {
+        classes: vec![],
+        methods: vec![("Dispatch$Greeter", "greet", "hello")],
+    };
+
+    let class_file = parse_class("./java-assets/compiled-classes/Dispatch").unwrap();
+    let remapped = remap_class(&class_file, &renames).unwrap();
+    let pool = remapped.constant_pool();
+    let refs: Vec<_> = pool
+        .iter()
+        .filter(|(_, constant)| {
+            matches!(
+                constant,
+                ConstantInfo::MethodRef(_) | ConstantInfo::InterfaceMethodRef(_)
+            )
+        })
+        .map(|(index, _)| pool.resolve_member_ref(index).unwrap())
+        .collect();
+    assert!(refs.contains(&(
+        "Dispatch$Greeter".into(),
+        "hello".into(),
+        "()Ljava/lang/String;".into()
+    )));
+    assert!(!refs.iter().any(|(_, name, _)| name == "greet"));
+    // `Greeter.name` shares nothing with `greet` and is left alone
+    assert!(refs.iter().any(|(_, name, _)| name == "name"));
+
+    let greeter = parse_class("./java-assets/compiled-classes/Dispatch$Greeter").unwrap();
+    let remapped = remap_class(&greeter, &renames).unwrap();
+    let pool = remapped.constant_pool();
+    let names: Vec<_> = remapped
+        .methods
+        .iter()
+        .map(|method| pool.get_utf8(method.name_index).unwrap())
+        .collect();
+    assert_eq!(names, ["hello", "name"]);
+
+    // Base.greet overrides Greeter.greet, but belongs to a different owner
+    let base = parse_class("./java-assets/compiled-classes/Dispatch$Base").unwrap();
+    let remapped = remap_class(&base, &renames).unwrap();
+    let pool = remapped.constant_pool();
+    assert!(
+        remapped
+            .methods
+            .iter()
+            .any(|method| pool.get_utf8(method.name_index).unwrap() == "greet")
+    );
+}
+
+#[test]
+fn rename_inner_and_enclosing_classes() {
+    let renames = Renames {
+        classes: vec![
+            ("InnerClasses", "com/example/Outer"),
+            ("InnerClasses$HelloWorld", "com/example/Outer$Greeter"),
+            (
+                "InnerClasses$1EnglishGreeting",
+                "com/example/Outer$1English",
+            ),
+        ],
+        methods: vec![("InnerClasses", "sayHello", "speak")],
+    };
+
+    let class_file =
+        parse_class("./java-assets/compiled-classes/InnerClasses$1EnglishGreeting").unwrap();
+    let bytes = remap_class(&class_file, &renames)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let remapped = parse_class_from_bytes(&bytes).unwrap();
+    let pool = remapped.constant_pool();
+
+    assert_eq!(
+        remapped.this_class_name().unwrap(),
+        "com/example/Outer$1English"
+    );
+    assert_eq!(
+        remapped.interface_names().unwrap(),
+        ["com/example/Outer$Greeter"]
+    );
+
+    let attributes = remapped.typed_attributes().unwrap();
+    let enclosing = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::EnclosingMethod(enclosing) => Some(enclosing),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        pool.get_class_name(enclosing.class_index).unwrap(),
+        "com/example/Outer"
+    );
+    assert_eq!(
+        pool.get_name_and_type(enclosing.method_index).unwrap(),
+        ("speak".into(), "()V".into())
+    );
+
+    let inner_classes = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::InnerClasses(inner_classes) => Some(inner_classes),
+            _ => None,
+        })
+        .unwrap();
+    let inner_names: Vec<_> = inner_classes
+        .classes
+        .iter()
+        .map(|class| {
+            (
+                pool.get_class_name(class.inner_class_info_index).unwrap(),
+                pool.get_utf8(class.inner_name_index).unwrap(),
+            )
+        })
+        .collect();
+    assert!(inner_names.contains(&("com/example/Outer$1English".into(), "English".into())));
+    assert!(inner_names.contains(&("com/example/Outer$Greeter".into(), "Greeter".into())));
+}
+
+#[test]
+fn relocate_annotations() {
+    let renames = Renames {
+        classes: vec![
+            ("Annotations$VisibleAtRuntime", "meta/Visible"),
+            ("Annotations$ParamInvisibleAtRuntime", "meta/ParamInvisible"),
+            ("Annotations$TypeVisibleAtRuntime", "meta/TypeVisible"),
+        ],
+        methods: vec![],
+    };
+    let class_file = parse_class("./java-assets/compiled-classes/Annotations").unwrap();
+    let remapped = remap_class(&class_file, &renames).unwrap();
+
+    let classes = remapped.referenced_classes().unwrap();
+    for (from, to) in &renames.classes {
+        assert!(classes.contains_key(*to), "{} is missing", to);
+        assert!(!classes.contains_key(*from), "{} is still referenced", from);
+    }
+}
+
+#[test]
+fn relocate_module_packages() {
+    let class_file = parse_class("./java-assets/compiled-classes/module-info").unwrap();
+    let relocator = Relocator::new().relocate("com/some", "shaded/com/some");
+    let remapped = remap_class(&class_file, &relocator).unwrap();
+
+    let module = remapped.module_descriptor().unwrap().unwrap();
+    assert_eq!(module.name, "my.module");
+    assert_eq!(module.exports[0].package, "shaded/com/some");
+}
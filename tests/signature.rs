@@ -0,0 +1,137 @@
+extern crate classfile_parser;
+
+use classfile_parser::attribute_info::TypedAttribute;
+use classfile_parser::descriptor::BaseType;
+use classfile_parser::parse_class;
+use classfile_parser::signature::{
+    ClassSignature, FieldSignature, JavaTypeSignature, MethodSignature, ReferenceTypeSignature,
+    TypeArgument,
+};
+
+fn signature_of(attributes: Vec<TypedAttribute>) -> Option<u16> {
+    attributes.into_iter().find_map(|a| match a {
+        TypedAttribute::Signature(s) => Some(s.signature_index),
+        _ => None,
+    })
+}
+
+#[test]
+fn compiled_signatures() {
+    let class_file = parse_class("./java-assets/compiled-classes/Generics").unwrap();
+    let pool = class_file.constant_pool();
+
+    let index = signature_of(class_file.typed_attributes().unwrap()).unwrap();
+    let raw = pool.get_utf8(index).unwrap();
+    let class_signature: ClassSignature = raw.parse().unwrap();
+    assert_eq!(class_signature.to_string(), raw);
+    assert_eq!(
+        class_signature.to_java("Generics"),
+        "Generics<K extends java.lang.Comparable<K>, V> \
+         extends java.util.ArrayList<java.util.Map$Entry<K, V>> \
+         implements java.lang.Comparable<Generics<K, ?>>"
+    );
+
+    let mut fields = Vec::new();
+    for field in &class_file.fields {
+        let attributes = field.typed_attributes(&class_file.const_pool).unwrap();
+        if let Some(index) = signature_of(attributes) {
+            let raw = pool.get_utf8(index).unwrap();
+            let signature: FieldSignature = raw.parse().unwrap();
+            assert_eq!(signature.to_string(), raw);
+            fields.push(signature.to_java());
+        }
+    }
+    assert_eq!(
+        fields,
+        ["java.util.Map<? super K, java.util.List<? extends V>[]>"]
+    );
+
+    let mut methods = Vec::new();
+    for method in &class_file.methods {
+        let attributes = method.typed_attributes(&class_file.const_pool).unwrap();
+        if let Some(index) = signature_of(attributes) {
+            let raw = pool.get_utf8(index).unwrap();
+            let signature: MethodSignature = raw.parse().unwrap();
+            assert_eq!(signature.to_string(), raw);
+            let name = pool.get_utf8(method.name_index).unwrap();
+            methods.push(signature.to_java(&name));
+        }
+    }
+    assert_eq!(
+        methods,
+        [
+            "<E extends java.lang.Exception & java.lang.Runnable> V lookup(K, java.util.List<? super E>) \
+             throws E, java.io.IOException",
+            "int compareTo(Generics<K, ?>)",
+        ]
+    );
+}
+
+#[test]
+fn inner_class_type_arguments() {
+    let signature: FieldSignature = "Lcom/example/Outer<TT;>.Inner<[I>.Leaf;".parse().unwrap();
+    let ReferenceTypeSignature::Class(class) = &signature.0 else {
+        panic!("Expected a class type, got {:?}", signature);
+    };
+    assert_eq!(class.package, "com/example");
+    assert_eq!(class.class.name, "Outer");
+    assert_eq!(
+        class.class.type_arguments,
+        [TypeArgument::Exact(ReferenceTypeSignature::TypeVariable(
+            "T".to_string()
+        ))]
+    );
+    assert_eq!(class.suffix.len(), 2);
+    assert_eq!(
+        class.suffix[0].type_arguments,
+        [TypeArgument::Exact(ReferenceTypeSignature::Array(
+            Box::new(JavaTypeSignature::Base(BaseType::Int))
+        ))]
+    );
+    assert_eq!(
+        signature.to_java(),
+        "com.example.Outer<T>.Inner<int[]>.Leaf"
+    );
+    assert_eq!(
+        signature.to_string(),
+        "Lcom/example/Outer<TT;>.Inner<[I>.Leaf;"
+    );
+}
+
+#[test]
+fn interface_only_bounds() {
+    let signature: MethodSignature = "<T::Ljava/lang/Runnable;>()TT;".parse().unwrap();
+    assert_eq!(signature.type_parameters[0].class_bound, None);
+    assert_eq!(signature.type_parameters[0].interface_bounds.len(), 1);
+    assert_eq!(
+        signature.to_java("make"),
+        "<T extends java.lang.Runnable> T make()"
+    );
+    assert_eq!(signature.to_string(), "<T::Ljava/lang/Runnable;>()TT;");
+}
+
+#[test]
+fn invalid_signatures() {
+    for (signature, offset) in [
+        ("", 0),
+        ("I", 0),
+        ("Ljava/util/List<>;", 15),
+        ("Ljava/util/List<TT;>", 20),
+        ("TT", 2),
+    ] {
+        assert_eq!(
+            signature.parse::<FieldSignature>().unwrap_err().offset,
+            offset,
+            "{}",
+            signature
+        );
+    }
+    assert_eq!(
+        "<T>Ljava/lang/Object;"
+            .parse::<ClassSignature>()
+            .unwrap_err()
+            .offset,
+        0
+    );
+    assert_eq!("()V^I".parse::<MethodSignature>().unwrap_err().offset, 3);
+}
@@ -0,0 +1,129 @@
+extern crate classfile_parser;
+
+use classfile_parser::attribute_info::{AttributeInfo, TypedAttribute, typed_attribute_parser};
+use classfile_parser::class_parser;
+use classfile_parser::constant_info::ConstantInfo;
+
+#[test]
+fn class_attributes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BootstrapMethods.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+
+    let attributes = class.typed_attributes().unwrap();
+    assert_eq!(attributes.len(), class.attributes.len());
+
+    let bootstrap_methods = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::BootstrapMethods(b) => Some(b),
+            _ => None,
+        })
+        .expect("BootstrapMethods attribute should be decoded");
+    assert_eq!(bootstrap_methods.num_bootstrap_methods, 1);
+    assert_eq!(
+        bootstrap_methods.bootstrap_methods[0].num_bootstrap_arguments,
+        3
+    );
+
+    assert!(
+        attributes
+            .iter()
+            .any(|attribute| matches!(attribute, TypedAttribute::SourceFile(_)))
+    );
+}
+
+#[test]
+fn method_and_code_attributes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+
+    for method in &class.methods {
+        let attributes = method.typed_attributes(&class.const_pool).unwrap();
+        let code = attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                TypedAttribute::Code(code) => Some(code),
+                _ => None,
+            })
+            .expect("every method in BasicClass has code");
+
+        // BasicClass is compiled with -g so line numbers and local variables are present
+        let code_attributes = code.typed_attributes(&class.const_pool).unwrap();
+        assert!(
+            code_attributes
+                .iter()
+                .any(|attribute| matches!(attribute, TypedAttribute::LineNumberTable(_)))
+        );
+    }
+
+    let last_method = class.methods.last().unwrap();
+    let attributes = last_method.typed_attributes(&class.const_pool).unwrap();
+    match &attributes[1] {
+        TypedAttribute::MethodParameters(parameters) => {
+            assert_eq!(parameters.parameters_count, 2)
+        }
+        other => panic!("Expected MethodParameters, got {:?}", other),
+    }
+    let code = match &attributes[0] {
+        TypedAttribute::Code(code) => code,
+        other => panic!("Expected Code, got {:?}", other),
+    };
+    assert!(
+        code.typed_attributes(&class.const_pool)
+            .unwrap()
+            .iter()
+            .any(|attribute| matches!(attribute, TypedAttribute::LocalVariableTable(_)))
+    );
+}
+
+#[test]
+fn field_attributes() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/DeprecatedAnnotation.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+
+    let field = &class.fields[0];
+    let attributes = field.typed_attributes(&class.const_pool).unwrap();
+    let annotations = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TypedAttribute::RuntimeVisibleAnnotations(a) => Some(a),
+            _ => None,
+        })
+        .expect("@Deprecated should be a runtime visible annotation");
+    assert_eq!(annotations.num_annotations, 1);
+}
+
+#[test]
+fn unknown_attribute() {
+    let class_bytes = include_bytes!("../java-assets/compiled-classes/BasicClass.class");
+    let (_, class) = class_parser(class_bytes).unwrap();
+
+    let name_index = class
+        .const_pool
+        .iter()
+        .position(
+            |c| matches!(c, ConstantInfo::Utf8(s) if s.utf8_string.to_string() == "BasicClass"),
+        )
+        .unwrap()
+        + 1;
+    let attribute = AttributeInfo {
+        attribute_name_index: name_index as u16,
+        attribute_length: 3,
+        info: vec![1, 2, 3],
+    };
+
+    match typed_attribute_parser(&attribute, &class.const_pool).unwrap() {
+        TypedAttribute::Unknown { name, bytes } => {
+            assert_eq!(name, "BasicClass");
+            assert_eq!(bytes, vec![1, 2, 3]);
+        }
+        other => panic!("Expected an unknown attribute, got {:?}", other),
+    }
+
+    let bad_name = AttributeInfo {
+        attribute_name_index: 0,
+        attribute_length: 0,
+        info: vec![],
+    };
+    assert!(typed_attribute_parser(&bad_name, &class.const_pool).is_err());
+}
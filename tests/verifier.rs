@@ -0,0 +1,487 @@
+extern crate classfile_parser;
+
+mod common;
+
+use std::fs;
+
+use std::cell::RefCell;
+
+use assert_matches::assert_matches;
+use classfile_parser::attribute_info::{StackMapFrame, TypedAttribute, VerificationTypeInfo};
+use classfile_parser::code_attribute::{Assembler, Branch, Instruction, code_parser};
+use classfile_parser::constant_info::{ClassConstant, ConstantInfo, Utf8Constant};
+use classfile_parser::parse_class;
+use classfile_parser::types::ClassFile;
+use classfile_parser::verifier::{
+    Frame, VerificationType, VerifyError, VerifyErrorKind, compute_frames, verify_method,
+};
+
+/// Factorial, with the code of `factorial(int)` replaced by `instructions` and the given
+/// limits, without a StackMapTable.
+fn factorial_with_code(
+    max_stack: u16,
+    max_locals: u16,
+    instructions: Vec<Instruction>,
+) -> ClassFile {
+    let mut assembler = Assembler::new();
+    for instruction in instructions {
+        assembler.emit(instruction);
+    }
+    let code = assembler.assemble().unwrap().code;
+
+    let mut info = Vec::new();
+    info.extend_from_slice(&max_stack.to_be_bytes());
+    info.extend_from_slice(&max_locals.to_be_bytes());
+    info.extend_from_slice(&(code.len() as u32).to_be_bytes());
+    info.extend_from_slice(&code);
+    info.extend_from_slice(&[0, 0, 0, 0]);
+
+    let mut class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let attribute = &mut class_file.methods[1].attributes[0];
+    attribute.attribute_length = info.len() as u32;
+    attribute.info = info;
+    class_file
+}
+
+fn errors(class_file: &ClassFile) -> Vec<VerifyError> {
+    verify_method(class_file, &class_file.methods[1])
+        .unwrap()
+        .errors
+}
+
+fn error(address: usize, kind: VerifyErrorKind) -> VerifyError {
+    VerifyError { address, kind }
+}
+
+#[test]
+fn compiled_classes_verify() {
+    for entry in fs::read_dir("./java-assets/compiled-classes").unwrap() {
+        let path = entry.unwrap().path();
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "class")
+            || path.ends_with("malformed.class")
+        {
+            continue;
+        }
+        let class_file = parse_class(path.with_extension("").to_str().unwrap()).unwrap();
+        for method in &class_file.methods {
+            let verification = verify_method(&class_file, method).unwrap();
+            assert!(
+                verification.is_ok(),
+                "{}: {:?}",
+                path.display(),
+                verification.errors
+            );
+            if let Some(code) = common::code(&class_file, method) {
+                assert_eq!(verification.max_stack, code.max_stack, "{}", path.display());
+            }
+        }
+    }
+}
+
+#[test]
+fn computed_frames() {
+    use VerificationType::*;
+
+    let class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let init = verify_method(&class_file, &class_file.methods[0]).unwrap();
+    assert_eq!(init.frames[&0].locals, [UninitializedThis]);
+    assert_eq!(init.frames[&4].locals, [Object("Factorial".to_string())]);
+
+    let factorial = verify_method(&class_file, &class_file.methods[1]).unwrap();
+    assert_eq!(
+        factorial.frames.keys().copied().collect::<Vec<_>>(),
+        [0, 1, 2, 5, 6, 9, 10, 11, 12, 13, 16, 17]
+    );
+    assert_eq!(
+        factorial.frames[&13],
+        Frame {
+            locals: vec![Integer],
+            stack: vec![Integer, Integer],
+        }
+    );
+    assert_eq!(factorial.frames[&17].stack, [Integer]);
+}
+
+#[test]
+fn stack_errors() {
+    let class_file = factorial_with_code(
+        2,
+        1,
+        vec![Instruction::Iload0, Instruction::Iadd, Instruction::Ireturn],
+    );
+    assert_eq!(
+        errors(&class_file),
+        [error(1, VerifyErrorKind::StackUnderflow)]
+    );
+
+    let class_file = factorial_with_code(
+        1,
+        1,
+        vec![
+            Instruction::Iload0,
+            Instruction::Iload0,
+            Instruction::Iadd,
+            Instruction::Ireturn,
+        ],
+    );
+    assert_eq!(
+        errors(&class_file),
+        [error(
+            1,
+            VerifyErrorKind::StackOverflow {
+                depth: 2,
+                max_stack: 1
+            }
+        )]
+    );
+}
+
+#[test]
+fn type_errors() {
+    let class_file = factorial_with_code(
+        2,
+        1,
+        vec![
+            Instruction::Iload0,
+            Instruction::Fconst1,
+            Instruction::Iadd,
+            Instruction::Ireturn,
+        ],
+    );
+    assert_eq!(
+        errors(&class_file),
+        [error(
+            2,
+            VerifyErrorKind::TypeMismatch {
+                expected: VerificationType::Integer,
+                found: VerificationType::Float
+            }
+        )]
+    );
+
+    let class_file = factorial_with_code(1, 1, vec![Instruction::Iload1, Instruction::Ireturn]);
+    assert_eq!(
+        errors(&class_file),
+        [error(
+            0,
+            VerifyErrorKind::InvalidLocal {
+                index: 1,
+                max_locals: 1
+            }
+        )]
+    );
+
+    let class_file = factorial_with_code(1, 1, vec![Instruction::Return]);
+    assert_eq!(errors(&class_file), [error(0, VerifyErrorKind::BadReturn)]);
+
+    let class_file = factorial_with_code(1, 1, vec![Instruction::Iload0, Instruction::Pop]);
+    assert_eq!(
+        errors(&class_file),
+        [error(1, VerifyErrorKind::FallsOffEnd)]
+    );
+}
+
+#[test]
+fn stack_map_frames() {
+    use VerificationType::*;
+
+    // iconst_1 at 5 replaced by fconst_1, so the frame at 17 expects an int but gets a float
+    let mut class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let code_start = 8;
+    assert_eq!(
+        class_file.methods[1].attributes[0].info[code_start + 5],
+        0x04
+    );
+    class_file.methods[1].attributes[0].info[code_start + 5] = 0x0c;
+    assert_eq!(
+        errors(&class_file),
+        [error(
+            17,
+            VerifyErrorKind::FrameMismatch {
+                computed: Frame {
+                    locals: vec![Integer],
+                    stack: vec![Float],
+                },
+                declared: Frame {
+                    locals: vec![Integer],
+                    stack: vec![Integer],
+                },
+            }
+        )]
+    );
+
+    // the same code without its StackMapTable
+    let class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let code = class_file.methods[1].attributes[0].info[code_start..code_start + 18].to_vec();
+    let instructions = code_parser(&code)
+        .unwrap()
+        .1
+        .into_iter()
+        .map(|(_, instruction)| instruction)
+        .collect();
+    let class_file = factorial_with_code(3, 1, instructions);
+    assert_eq!(
+        errors(&class_file),
+        [
+            error(9, VerifyErrorKind::MissingFrame),
+            error(17, VerifyErrorKind::MissingFrame)
+        ]
+    );
+}
+
+fn any_object(_: &str, _: &str) -> String {
+    "java/lang/Object".to_string()
+}
+
+fn offset_delta(frame: &StackMapFrame) -> u16 {
+    match *frame {
+        StackMapFrame::SameFrame { frame_type } => frame_type as u16,
+        StackMapFrame::SameLocals1StackItemFrame { frame_type, .. } => frame_type as u16 - 64,
+        StackMapFrame::SameLocals1StackItemFrameExtended { offset_delta, .. }
+        | StackMapFrame::ChopFrame { offset_delta, .. }
+        | StackMapFrame::SameFrameExtended { offset_delta, .. }
+        | StackMapFrame::AppendFrame { offset_delta, .. }
+        | StackMapFrame::FullFrame { offset_delta, .. } => offset_delta,
+    }
+}
+
+fn assembled(assembler: &Assembler) -> Vec<(usize, Instruction)> {
+    code_parser(&assembler.assemble().unwrap().code).unwrap().1
+}
+
+#[test]
+fn compute_frames_at_javac_offsets() {
+    for entry in fs::read_dir("./java-assets/compiled-classes").unwrap() {
+        let path = entry.unwrap().path();
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "class")
+            || path.ends_with("malformed.class")
+        {
+            continue;
+        }
+        let mut class_file = parse_class(path.with_extension("").to_str().unwrap()).unwrap();
+        for method_index in 0..class_file.methods.len() {
+            let Some(code) = common::code(&class_file, &class_file.methods[method_index]) else {
+                continue;
+            };
+            let declared = code
+                .typed_attributes(&class_file.const_pool)
+                .unwrap()
+                .into_iter()
+                .find_map(|attribute| match attribute {
+                    TypedAttribute::StackMapTable(table) => Some(table.entries),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let computed = compute_frames(
+                &mut class_file,
+                method_index,
+                code.max_locals,
+                &code.instructions().unwrap(),
+                &code.exception_table,
+                &any_object,
+            )
+            .unwrap();
+            assert_eq!(
+                computed.iter().map(offset_delta).collect::<Vec<_>>(),
+                declared.iter().map(offset_delta).collect::<Vec<_>>(),
+                "{} method {}",
+                path.display(),
+                method_index
+            );
+        }
+    }
+}
+
+#[test]
+fn compact_frame_types() {
+    // static int factorial(int)
+    let mut class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let mut assembler = Assembler::new();
+    let (appended, full, chopped) = (
+        assembler.new_label(),
+        assembler.new_label(),
+        assembler.new_label(),
+    );
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifeq, chopped);
+    assembler.emit(Instruction::Iconst0);
+    assembler.emit(Instruction::Istore1);
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifeq, appended);
+    assembler.emit(Instruction::Iconst0);
+    assembler.emit(Instruction::Istore1);
+    assembler.place(appended);
+    assembler.emit(Instruction::Fconst0);
+    assembler.emit(Instruction::Fstore1);
+    assembler.emit(Instruction::Iload0);
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifeq, full);
+    assembler.emit(Instruction::Nop);
+    assembler.place(full);
+    assembler.emit(Instruction::Ireturn);
+    assembler.place(chopped);
+    assembler.emit(Instruction::Iload0);
+    assembler.emit(Instruction::Ireturn);
+
+    let frames = compute_frames(
+        &mut class_file,
+        1,
+        2,
+        &assembled(&assembler),
+        &[],
+        &any_object,
+    )
+    .unwrap();
+    assert_eq!(frames.len(), 3);
+    // [int] -> [int, int] at 12
+    assert_matches!(
+        &frames[0],
+        StackMapFrame::AppendFrame { frame_type: 252, offset_delta: 12, locals }
+            if matches!(locals[..], [VerificationTypeInfo::Integer])
+    );
+    // [int, float] with an int on the stack at 20
+    assert_matches!(
+        &frames[1],
+        StackMapFrame::FullFrame { offset_delta: 7, locals, stack, .. }
+            if matches!(locals[..], [VerificationTypeInfo::Integer, VerificationTypeInfo::Float])
+                && matches!(stack[..], [VerificationTypeInfo::Integer])
+    );
+    // back to [int] at 21, where local 1 was never set
+    assert_matches!(
+        frames[2],
+        StackMapFrame::ChopFrame {
+            frame_type: 250,
+            offset_delta: 0
+        }
+    );
+
+    let mut assembler = Assembler::new();
+    let target = assembler.new_label();
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Goto, target);
+    for _ in 0..70 {
+        assembler.emit(Instruction::Nop);
+    }
+    assembler.place(target);
+    assembler.emit(Instruction::Ireturn);
+    let frames = compute_frames(
+        &mut class_file,
+        1,
+        1,
+        &assembled(&assembler),
+        &[],
+        &any_object,
+    );
+    assert_eq!(
+        frames.unwrap_err(),
+        VerifyError {
+            address: 4,
+            kind: VerifyErrorKind::Unreachable
+        }
+    );
+
+    let mut assembler = Assembler::new();
+    let target = assembler.new_label();
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifeq, target);
+    for _ in 0..70 {
+        assembler.emit(Instruction::Nop);
+    }
+    assembler.place(target);
+    assembler.emit(Instruction::Iload0);
+    assembler.emit(Instruction::Ireturn);
+    let frames = compute_frames(
+        &mut class_file,
+        1,
+        1,
+        &assembled(&assembler),
+        &[],
+        &any_object,
+    )
+    .unwrap();
+    assert_matches!(
+        frames[..],
+        [StackMapFrame::SameFrameExtended {
+            frame_type: 251,
+            offset_delta: 74
+        }]
+    );
+}
+
+#[test]
+fn compute_frames_merges_with_hierarchy() {
+    let mut class_file = parse_class("./java-assets/compiled-classes/Factorial").unwrap();
+    let mut class = |name: &str| {
+        class_file
+            .const_pool
+            .push(ConstantInfo::Utf8(Utf8Constant::from(name)));
+        let name_index = class_file.const_pool.len() as u16;
+        class_file
+            .const_pool
+            .push(ConstantInfo::Class(ClassConstant { name_index }));
+        class_file.const_pool.len() as u16
+    };
+    let (integer, long) = (class("java/lang/Integer"), class("java/lang/Long"));
+    class_file.const_pool_size = class_file.const_pool.len() as u16 + 1;
+
+    let mut assembler = Assembler::new();
+    let (other, join) = (assembler.new_label(), assembler.new_label());
+    assembler.emit(Instruction::Iload0);
+    assembler.branch(Branch::Ifeq, other);
+    assembler.emit(Instruction::Aconstnull);
+    assembler.emit(Instruction::Checkcast(integer));
+    assembler.branch(Branch::Goto, join);
+    assembler.place(other);
+    assembler.emit(Instruction::Aconstnull);
+    assembler.emit(Instruction::Checkcast(long));
+    assembler.place(join);
+    assembler.emit(Instruction::Pop);
+    assembler.emit(Instruction::Iload0);
+    assembler.emit(Instruction::Ireturn);
+
+    let queries = RefCell::new(Vec::new());
+    let hierarchy = |a: &str, b: &str| {
+        queries.borrow_mut().push((a.to_string(), b.to_string()));
+        "java/lang/Number".to_string()
+    };
+    let pool_size = class_file.const_pool.len();
+    let frames = compute_frames(
+        &mut class_file,
+        1,
+        1,
+        &assembled(&assembler),
+        &[],
+        &hierarchy,
+    )
+    .unwrap();
+    assert_eq!(
+        queries.into_inner(),
+        [(
+            "java/lang/Integer".to_string(),
+            "java/lang/Long".to_string()
+        )]
+    );
+
+    // java/lang/Number had no Class constant, so one was added
+    assert_eq!(class_file.const_pool.len(), pool_size + 2);
+    assert_eq!(class_file.const_pool_size as usize, pool_size + 3);
+    let number = class_file.const_pool.len() as u16;
+    assert_eq!(
+        class_file.constant_pool().get_class_name(number).unwrap(),
+        "java/lang/Number"
+    );
+    assert_matches!(
+        frames[..],
+        [
+            StackMapFrame::SameFrame { frame_type: 11 },
+            StackMapFrame::SameLocals1StackItemFrame {
+                frame_type: 67,
+                stack: VerificationTypeInfo::Object { class }
+            }
+        ] if class == number
+    );
+}
@@ -0,0 +1,96 @@
+extern crate classfile_parser;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use classfile_parser::constant_info::{ConstantInfo, Utf8Constant};
+use classfile_parser::parse_class_from_bytes;
+
+#[test]
+fn round_trip_compiled_classes() {
+    let mut paths = Vec::new();
+    common::class_files(Path::new("java-assets/compiled-classes"), &mut paths);
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        // malformed.class is deliberately not a class file
+        if path
+            .file_name()
+            .is_some_and(|name| name == "malformed.class")
+        {
+            continue;
+        }
+        let bytes = fs::read(&path).unwrap();
+        let class_file = parse_class_from_bytes(&bytes).unwrap();
+        let written = class_file.to_bytes().unwrap();
+        assert!(written == bytes, "{} did not round trip", path.display());
+    }
+}
+
+#[test]
+fn write_modified_constant() {
+    let bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let mut class_file = parse_class_from_bytes(bytes).unwrap();
+
+    let index = class_file
+        .const_pool
+        .iter()
+        .position(
+            |c| matches!(c, ConstantInfo::Utf8(s) if s.utf8_string.to_string() == "Hello World!"),
+        )
+        .expect("HelloWorld should contain its greeting");
+    class_file.const_pool[index] = ConstantInfo::Utf8(Utf8Constant::from("Hallo \0 Welt!"));
+
+    let mut written = Vec::new();
+    class_file.write_to(&mut written).unwrap();
+    // "\0" is encoded as two bytes in modified UTF-8
+    assert_eq!(written.len(), bytes.len() + 2);
+
+    let reparsed = parse_class_from_bytes(&written).unwrap();
+    match &reparsed.const_pool[index] {
+        ConstantInfo::Utf8(s) => assert_eq!(s.utf8_string.to_string(), "Hallo \0 Welt!"),
+        other => panic!("Expected a Utf8 constant, got {:?}", other),
+    }
+}
+
+#[test]
+fn write_edited_utf8_string() {
+    let bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let mut class_file = parse_class_from_bytes(bytes).unwrap();
+
+    let index = class_file
+        .const_pool
+        .iter()
+        .position(
+            |c| matches!(c, ConstantInfo::Utf8(s) if s.utf8_string.to_string() == "Hello World!"),
+        )
+        .unwrap();
+    match &mut class_file.const_pool[index] {
+        ConstantInfo::Utf8(s) => {
+            assert_eq!(&s.bytes()[..], b"Hello World!");
+            s.utf8_string = "Hi \0".into();
+            assert_eq!(&s.bytes()[..], b"Hi \xc0\x80");
+        }
+        _ => unreachable!(),
+    }
+
+    let reparsed = parse_class_from_bytes(&class_file.to_bytes().unwrap()).unwrap();
+    assert_eq!(
+        reparsed.constant_pool().get_utf8(index as u16 + 1).unwrap(),
+        "Hi \0"
+    );
+
+    let constant = Utf8Constant::new("Hi \0");
+    assert_eq!(&constant.bytes()[..], b"Hi \xc0\x80");
+    assert_eq!(constant.to_str(), "Hi \0");
+}
+
+#[test]
+fn inconsistent_counts() {
+    let bytes = include_bytes!("../java-assets/compiled-classes/HelloWorld.class");
+    let mut class_file = parse_class_from_bytes(bytes).unwrap();
+    class_file.methods.pop();
+    assert!(class_file.to_bytes().is_err());
+}